@@ -0,0 +1,111 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use lsp_types::{
+    borrowed, DidChangeTextDocumentParams, DidOpenTextDocumentParams, SemanticToken,
+    SemanticTokens, TextDocumentContentChangeEvent, TextDocumentItem,
+    VersionedTextDocumentIdentifier,
+};
+
+/// About 20 MB of generated code, either minified onto a single line, which needs no escapes in
+/// JSON, or spread over many lines.
+fn generated_source(multiline: bool) -> String {
+    let separator = if multiline { "\n" } else { " " };
+    (0..400_000)
+        .map(|i| format!("var v{} = f({}, 'x');", i, i))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn did_open(text: String) -> String {
+    serde_json::to_string(&DidOpenTextDocumentParams {
+        text_document: TextDocumentItem::new(
+            "file:///generated.js".parse().unwrap(),
+            "javascript".into(),
+            1,
+            text,
+        ),
+    })
+    .unwrap()
+}
+
+fn did_change(text: String) -> String {
+    serde_json::to_string(&DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier::new(
+            "file:///generated.js".parse().unwrap(),
+            2,
+        ),
+        content_changes: vec![TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text,
+        }],
+    })
+    .unwrap()
+}
+
+fn bench_deserialize(c: &mut Criterion) {
+    for (name, multiline) in [("minified", false), ("multiline", true)] {
+        let source = generated_source(multiline);
+        let open = did_open(source.clone());
+        let change = did_change(source);
+
+        let mut group = c.benchmark_group(format!("did_open/{}", name));
+        group.throughput(Throughput::Bytes(open.len() as u64));
+        group.sample_size(20);
+        group.bench_function("owned", |b| {
+            b.iter(|| serde_json::from_str::<DidOpenTextDocumentParams>(black_box(&open)).unwrap())
+        });
+        group.bench_function("borrowed", |b| {
+            b.iter(|| {
+                serde_json::from_str::<borrowed::DidOpenTextDocumentParams<'_>>(black_box(&open))
+                    .unwrap()
+            })
+        });
+        group.finish();
+
+        let mut group = c.benchmark_group(format!("did_change/{}", name));
+        group.throughput(Throughput::Bytes(change.len() as u64));
+        group.sample_size(20);
+        group.bench_function("owned", |b| {
+            b.iter(|| {
+                serde_json::from_str::<DidChangeTextDocumentParams>(black_box(&change)).unwrap()
+            })
+        });
+        group.bench_function("borrowed", |b| {
+            b.iter(|| {
+                serde_json::from_str::<borrowed::DidChangeTextDocumentParams<'_>>(black_box(
+                    &change,
+                ))
+                .unwrap()
+            })
+        });
+        group.finish();
+    }
+}
+
+fn bench_semantic_tokens(c: &mut Criterion) {
+    let tokens = SemanticTokens {
+        result_id: Some("1".into()),
+        data: (0..1_000_000)
+            .map(|i| SemanticToken {
+                delta_line: i % 2,
+                delta_start: 4,
+                length: 8,
+                token_type: i % 10,
+                token_modifiers_bitset: 0,
+            })
+            .collect(),
+    };
+
+    let mut group = c.benchmark_group("semantic_tokens/serialize");
+    group.sample_size(20);
+    group.bench_function("owned", |b| {
+        b.iter(|| serde_json::to_vec(black_box(&tokens)).unwrap())
+    });
+    group.bench_function("borrowed", |b| {
+        b.iter(|| serde_json::to_vec(&borrowed::SemanticTokens::from(black_box(&tokens))).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_deserialize, bench_semantic_tokens);
+criterion_main!(benches);
@@ -0,0 +1,93 @@
+/*!
+
+Generators for the fields whose types do not implement `Arbitrary`, used with
+`#[arbitrary(with = ...)]`.
+
+The generated JSON values only contain numbers that survive a round trip through JSON text, so
+that types holding them can be compared after serializing and deserializing them.
+
+*/
+
+use std::borrow::Cow;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use serde_json::{Map, Number, Value};
+
+/// Nested arrays and objects are not generated deeper than this.
+const MAX_DEPTH: u32 = 3;
+
+fn number(u: &mut Unstructured<'_>) -> Result<Number> {
+    Ok(match u.int_in_range(0..=2)? {
+        0 => i64::arbitrary(u)?.into(),
+        1 => u64::arbitrary(u)?.into(),
+        // Quarters of small integers have short decimal representations that parse back exactly.
+        _ => Number::from_f64(f64::from(i32::arbitrary(u)?) / 4.0).unwrap_or_else(|| 0.into()),
+    })
+}
+
+fn value_with_depth(u: &mut Unstructured<'_>, depth: u32) -> Result<Value> {
+    let max_kind = if depth < MAX_DEPTH { 5 } else { 3 };
+    Ok(match u.int_in_range(0..=max_kind)? {
+        0 => Value::Null,
+        1 => Value::Bool(bool::arbitrary(u)?),
+        2 => Value::Number(number(u)?),
+        3 => Value::String(String::arbitrary(u)?),
+        4 => Value::Array(
+            (0..u.arbitrary_len::<u8>()?)
+                .map(|_| value_with_depth(u, depth + 1))
+                .collect::<Result<_>>()?,
+        ),
+        _ => Value::Object(object_with_depth(u, depth + 1)?),
+    })
+}
+
+fn object_with_depth(u: &mut Unstructured<'_>, depth: u32) -> Result<Map<String, Value>> {
+    let mut object = Map::new();
+    for _ in 0..u.arbitrary_len::<(String, u8)>()? {
+        object.insert(String::arbitrary(u)?, value_with_depth(u, depth)?);
+    }
+    Ok(object)
+}
+
+pub(crate) fn value(u: &mut Unstructured<'_>) -> Result<Value> {
+    value_with_depth(u, 0)
+}
+
+pub(crate) fn object(u: &mut Unstructured<'_>) -> Result<Value> {
+    object_with_depth(u, 0).map(Value::Object)
+}
+
+pub(crate) fn option_value(u: &mut Unstructured<'_>) -> Result<Option<Value>> {
+    // `null` reads back as `None`.
+    Ok(match bool::arbitrary(u)? {
+        true => Some(value(u)?).filter(|value| !value.is_null()),
+        false => None,
+    })
+}
+
+pub(crate) fn vec_value(u: &mut Unstructured<'_>) -> Result<Vec<Value>> {
+    (0..u.arbitrary_len::<u8>()?).map(|_| value(u)).collect()
+}
+
+pub(crate) fn option_vec_value(u: &mut Unstructured<'_>) -> Result<Option<Vec<Value>>> {
+    Ok(match bool::arbitrary(u)? {
+        true => Some(vec_value(u)?),
+        false => None,
+    })
+}
+
+pub(crate) fn option_object(u: &mut Unstructured<'_>) -> Result<Option<Map<String, Value>>> {
+    Ok(match bool::arbitrary(u)? {
+        true => Some(object_with_depth(u, 0)?),
+        false => None,
+    })
+}
+
+/// A color channel in `[0, 1]` with 8 bits of precision.
+pub(crate) fn color_channel(u: &mut Unstructured<'_>) -> Result<f32> {
+    Ok(f32::from(u8::arbitrary(u)?) / 255.0)
+}
+
+pub(crate) fn cow_str(u: &mut Unstructured<'_>) -> Result<Cow<'static, str>> {
+    String::arbitrary(u).map(Cow::Owned)
+}
@@ -0,0 +1,299 @@
+/*!
+
+Borrowed variants of the types carrying large payloads.
+
+The types in this module mirror the owned types of the same name, but hold their text and token
+data as `Cow`s. Deserializing them from a `&str` buffer with `serde_json::from_str` borrows strings
+that contain no escape sequences instead of copying them; strings with escapes, such as most
+multi-line documents, are unescaped into an owned buffer once. Serializing them from a borrowed
+owned value, see the `From<&_>` implementations, does not copy the payload either.
+
+*/
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Range, SemanticToken, TextDocumentIdentifier, Uri, VersionedTextDocumentIdentifier};
+
+/// An item to transfer a text document from the client to the server.
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TextDocumentItem<'a> {
+    /// The text document's URI.
+    pub uri: Uri,
+
+    /// The text document's language identifier.
+    #[serde(borrow)]
+    pub language_id: Cow<'a, str>,
+
+    /// The version number of this document (it will strictly increase after each
+    /// change, including undo/redo).
+    pub version: i32,
+
+    /// The content of the opened text document.
+    #[serde(borrow)]
+    pub text: Cow<'a, str>,
+}
+
+impl TextDocumentItem<'_> {
+    pub fn into_owned(self) -> crate::TextDocumentItem {
+        crate::TextDocumentItem {
+            uri: self.uri,
+            language_id: self.language_id.into_owned(),
+            version: self.version,
+            text: self.text.into_owned(),
+        }
+    }
+}
+
+impl<'a> From<&'a crate::TextDocumentItem> for TextDocumentItem<'a> {
+    fn from(item: &'a crate::TextDocumentItem) -> Self {
+        TextDocumentItem {
+            uri: item.uri.clone(),
+            language_id: Cow::Borrowed(&item.language_id),
+            version: item.version,
+            text: Cow::Borrowed(&item.text),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct DidOpenTextDocumentParams<'a> {
+    /// The document that was opened.
+    #[serde(borrow)]
+    pub text_document: TextDocumentItem<'a>,
+}
+
+impl DidOpenTextDocumentParams<'_> {
+    pub fn into_owned(self) -> crate::DidOpenTextDocumentParams {
+        crate::DidOpenTextDocumentParams {
+            text_document: self.text_document.into_owned(),
+        }
+    }
+}
+
+impl<'a> From<&'a crate::DidOpenTextDocumentParams> for DidOpenTextDocumentParams<'a> {
+    fn from(params: &'a crate::DidOpenTextDocumentParams) -> Self {
+        DidOpenTextDocumentParams {
+            text_document: (&params.text_document).into(),
+        }
+    }
+}
+
+/// An event describing a change to a text document. If range and rangeLength are omitted
+/// the new text is considered to be the full content of the document.
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TextDocumentContentChangeEvent<'a> {
+    /// The range of the document that changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<Range>,
+
+    /// The length of the range that got replaced.
+    ///
+    /// Deprecated: Use range instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range_length: Option<u32>,
+
+    /// The new text of the document.
+    #[serde(borrow)]
+    pub text: Cow<'a, str>,
+}
+
+impl TextDocumentContentChangeEvent<'_> {
+    pub fn into_owned(self) -> crate::TextDocumentContentChangeEvent {
+        crate::TextDocumentContentChangeEvent {
+            range: self.range,
+            range_length: self.range_length,
+            text: self.text.into_owned(),
+        }
+    }
+}
+
+impl<'a> From<&'a crate::TextDocumentContentChangeEvent> for TextDocumentContentChangeEvent<'a> {
+    fn from(event: &'a crate::TextDocumentContentChangeEvent) -> Self {
+        TextDocumentContentChangeEvent {
+            range: event.range,
+            range_length: event.range_length,
+            text: Cow::Borrowed(&event.text),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct DidChangeTextDocumentParams<'a> {
+    /// The document that did change. The version number points
+    /// to the version after all provided content changes have
+    /// been applied.
+    pub text_document: VersionedTextDocumentIdentifier,
+    /// The actual content changes.
+    #[serde(borrow)]
+    pub content_changes: Vec<TextDocumentContentChangeEvent<'a>>,
+}
+
+impl DidChangeTextDocumentParams<'_> {
+    pub fn into_owned(self) -> crate::DidChangeTextDocumentParams {
+        crate::DidChangeTextDocumentParams {
+            text_document: self.text_document,
+            content_changes: self
+                .content_changes
+                .into_iter()
+                .map(TextDocumentContentChangeEvent::into_owned)
+                .collect(),
+        }
+    }
+}
+
+impl<'a> From<&'a crate::DidChangeTextDocumentParams> for DidChangeTextDocumentParams<'a> {
+    fn from(params: &'a crate::DidChangeTextDocumentParams) -> Self {
+        DidChangeTextDocumentParams {
+            text_document: params.text_document.clone(),
+            content_changes: params.content_changes.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct DidSaveTextDocumentParams<'a> {
+    /// The document that was saved.
+    pub text_document: TextDocumentIdentifier,
+
+    /// Optional the content when saved. Depends on the includeText value
+    /// when the save notification was requested.
+    #[serde(borrow, default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<Cow<'a, str>>,
+}
+
+impl DidSaveTextDocumentParams<'_> {
+    pub fn into_owned(self) -> crate::DidSaveTextDocumentParams {
+        crate::DidSaveTextDocumentParams {
+            text_document: self.text_document,
+            text: self.text.map(Cow::into_owned),
+        }
+    }
+}
+
+impl<'a> From<&'a crate::DidSaveTextDocumentParams> for DidSaveTextDocumentParams<'a> {
+    fn from(params: &'a crate::DidSaveTextDocumentParams) -> Self {
+        DidSaveTextDocumentParams {
+            text_document: params.text_document.clone(),
+            text: params.text.as_deref().map(Cow::Borrowed),
+        }
+    }
+}
+
+fn deserialize_tokens<'de, D>(deserializer: D) -> Result<Cow<'static, [SemanticToken]>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    SemanticToken::deserialize_tokens(deserializer).map(Cow::Owned)
+}
+
+// `serialize_with` passes a reference to the field.
+#[allow(clippy::ptr_arg)]
+fn serialize_tokens<S>(tokens: &Cow<'_, [SemanticToken]>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    SemanticToken::serialize_tokens(tokens, serializer)
+}
+
+/// Semantic tokens whose data can be serialized straight from a slice, for example the tokens a
+/// server keeps to compute deltas. The numbers of the data can not be borrowed from JSON, so
+/// deserialization always produces owned data.
+#[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticTokens<'a> {
+    /// An optional result id.
+    #[serde(borrow, default, skip_serializing_if = "Option::is_none")]
+    pub result_id: Option<Cow<'a, str>>,
+
+    /// The actual tokens.
+    #[serde(
+        deserialize_with = "deserialize_tokens",
+        serialize_with = "serialize_tokens"
+    )]
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(with = "crate::semantic_tokens::SemanticTokenData")
+    )]
+    pub data: Cow<'a, [SemanticToken]>,
+}
+
+impl SemanticTokens<'_> {
+    pub fn into_owned(self) -> crate::SemanticTokens {
+        crate::SemanticTokens {
+            result_id: self.result_id.map(Cow::into_owned),
+            data: self.data.into_owned(),
+        }
+    }
+}
+
+impl<'a> From<&'a crate::SemanticTokens> for SemanticTokens<'a> {
+    fn from(tokens: &'a crate::SemanticTokens) -> Self {
+        SemanticTokens {
+            result_id: tokens.result_id.as_deref().map(Cow::Borrowed),
+            data: Cow::Borrowed(&tokens.data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_borrow_text() {
+        let json = r#"{"textDocument":{"uri":"file:///a.js","languageId":"javascript","version":1,"text":"let a = 1;"}}"#;
+        let params: DidOpenTextDocumentParams<'_> = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            params.text_document.text,
+            Cow::Borrowed("let a = 1;")
+        ));
+        let owned: crate::DidOpenTextDocumentParams = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            serde_json::to_string(&DidOpenTextDocumentParams::from(&owned)).unwrap(),
+            json
+        );
+        assert_eq!(params.into_owned(), owned);
+
+        let json = r#"{"textDocument":{"uri":"file:///a.js","version":2},"contentChanges":[{"text":"a\nb"}]}"#;
+        let params: DidChangeTextDocumentParams<'_> = serde_json::from_str(json).unwrap();
+        assert!(matches!(params.content_changes[0].text, Cow::Owned(_)));
+        assert_eq!(params.content_changes[0].text, "a\nb");
+    }
+
+    #[test]
+    fn test_semantic_tokens() {
+        let tokens = crate::SemanticTokens {
+            result_id: Some("1".into()),
+            data: vec![SemanticToken {
+                delta_line: 1,
+                delta_start: 2,
+                length: 3,
+                token_type: 4,
+                token_modifiers_bitset: 5,
+            }],
+        };
+        let json = serde_json::to_string(&SemanticTokens::from(&tokens)).unwrap();
+        assert_eq!(json, r#"{"resultId":"1","data":[1,2,3,4,5]}"#);
+        let borrowed: SemanticTokens<'_> = serde_json::from_str(&json).unwrap();
+        assert_eq!(borrowed.into_owned(), tokens);
+    }
+}
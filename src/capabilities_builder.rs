@@ -0,0 +1,252 @@
+/*!
+
+Building `ServerCapabilities` that are consistent with the client and with the server itself.
+
+A [`ServerCapabilitiesBuilder`] negotiates the position encoding with the `ClientCapabilities` of
+`InitializeParams` and reports capabilities that the client or the server can not honor as
+[`CapabilityWarning`]s.
+
+*/
+
+use std::fmt;
+
+use crate::{
+    capabilities_merge::merge_server_capabilities, client_capabilities::ResolvedClientCapabilities,
+    ClientCapabilities, CodeActionProviderCapability, PositionEncodingKind, ServerCapabilities,
+};
+
+/// An inconsistency found by [`ServerCapabilitiesBuilder::build`].
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum CapabilityWarning {
+    /// The client offers none of the position encodings of the server, so UTF-16, which every
+    /// client supports, is used.
+    NoCommonPositionEncoding,
+    /// The capabilities announced an encoding the client did not offer. It was replaced by the
+    /// negotiated one.
+    PositionEncodingNotOffered(PositionEncodingKind),
+    /// A capability enables this method, but the server has no handler for it.
+    MissingHandler(&'static str),
+    /// `code_action_provider` has a `resolve_provider`, but the client can not resolve code
+    /// actions.
+    CodeActionResolveUnsupported,
+}
+
+impl fmt::Display for CapabilityWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CapabilityWarning::NoCommonPositionEncoding => {
+                write!(
+                    f,
+                    "the client offers none of the server's position encodings"
+                )
+            }
+            CapabilityWarning::PositionEncodingNotOffered(encoding) => write!(
+                f,
+                "position encoding `{}` was not offered by the client",
+                encoding.as_str()
+            ),
+            CapabilityWarning::MissingHandler(method) => {
+                write!(f, "`{}` is advertised but not handled", method)
+            }
+            CapabilityWarning::CodeActionResolveUnsupported => write!(
+                f,
+                "code action resolving is advertised but not supported by the client"
+            ),
+        }
+    }
+}
+
+/// Builds the `ServerCapabilities` to answer `initialize` with.
+#[derive(Debug, Clone)]
+pub struct ServerCapabilitiesBuilder<'a> {
+    client: ResolvedClientCapabilities<'a>,
+    capabilities: ServerCapabilities,
+    position_encodings: Vec<PositionEncodingKind>,
+    handlers: Option<Vec<String>>,
+}
+
+impl<'a> ServerCapabilitiesBuilder<'a> {
+    pub fn new(client: &'a ClientCapabilities) -> Self {
+        ServerCapabilitiesBuilder {
+            client: client.resolved(),
+            capabilities: ServerCapabilities::default(),
+            position_encodings: vec![PositionEncodingKind::UTF16],
+            handlers: None,
+        }
+    }
+
+    /// The capabilities to advertise. An explicit `position_encoding` is kept if the client
+    /// offered it.
+    pub fn capabilities(mut self, capabilities: ServerCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// The position encodings the server supports. The one the client prefers most is picked.
+    /// Defaults to UTF-16.
+    pub fn position_encodings(mut self, encodings: &[PositionEncodingKind]) -> Self {
+        self.position_encodings = encodings.to_vec();
+        self
+    }
+
+    /// The methods the server handles. If set, every method enabled by the capabilities must be
+    /// among them.
+    pub fn handlers<I, S>(mut self, methods: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.handlers = Some(methods.into_iter().map(Into::into).collect());
+        self
+    }
+
+    fn negotiate_position_encoding(
+        &self,
+        warnings: &mut Vec<CapabilityWarning>,
+    ) -> PositionEncodingKind {
+        if let Some(encoding) = &self.capabilities.position_encoding {
+            if self.client.supports_position_encoding(encoding) {
+                return encoding.clone();
+            }
+            warnings.push(CapabilityWarning::PositionEncodingNotOffered(
+                encoding.clone(),
+            ));
+        }
+        let client_encodings = self.client.position_encodings();
+        let negotiated = client_encodings
+            .iter()
+            .find(|encoding| self.position_encodings.contains(encoding))
+            .or_else(|| {
+                self.position_encodings
+                    .iter()
+                    .find(|encoding| self.client.supports_position_encoding(encoding))
+            });
+        match negotiated {
+            Some(encoding) => encoding.clone(),
+            None => {
+                warnings.push(CapabilityWarning::NoCommonPositionEncoding);
+                PositionEncodingKind::UTF16
+            }
+        }
+    }
+
+    /// Negotiates the position encoding and validates the capabilities. The warnings do not
+    /// change the capabilities, except for the position encoding.
+    pub fn build(mut self) -> (ServerCapabilities, Vec<CapabilityWarning>) {
+        let mut warnings = Vec::new();
+        let encoding = self.negotiate_position_encoding(&mut warnings);
+        self.capabilities.position_encoding = Some(encoding);
+
+        if let Some(handlers) = &self.handlers {
+            let routes = merge_server_capabilities(std::slice::from_ref(&self.capabilities)).routes;
+            for (method, _) in routes.methods() {
+                if !handlers.iter().any(|handler| handler == method) {
+                    warnings.push(CapabilityWarning::MissingHandler(method));
+                }
+            }
+        }
+
+        if let Some(CodeActionProviderCapability::Options(options)) =
+            &self.capabilities.code_action_provider
+        {
+            if options.resolve_provider == Some(true)
+                && self.client.code_action_resolve_properties().is_empty()
+            {
+                warnings.push(CapabilityWarning::CodeActionResolveUnsupported);
+            }
+        }
+
+        (self.capabilities, warnings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CodeActionOptions, GeneralClientCapabilities, SemanticTokensFullOptions,
+        SemanticTokensOptions,
+    };
+
+    fn client(encodings: Vec<PositionEncodingKind>) -> ClientCapabilities {
+        ClientCapabilities {
+            general: Some(GeneralClientCapabilities {
+                position_encodings: Some(encodings),
+                ..GeneralClientCapabilities::default()
+            }),
+            ..ClientCapabilities::default()
+        }
+    }
+
+    #[test]
+    fn test_negotiate_position_encoding() {
+        let client = client(vec![
+            PositionEncodingKind::UTF32,
+            PositionEncodingKind::UTF8,
+        ]);
+        let (capabilities, warnings) = ServerCapabilitiesBuilder::new(&client)
+            .position_encodings(&[PositionEncodingKind::UTF8, PositionEncodingKind::UTF32])
+            .build();
+        assert_eq!(
+            capabilities.position_encoding,
+            Some(PositionEncodingKind::UTF32)
+        );
+        assert!(warnings.is_empty());
+
+        let (capabilities, warnings) =
+            ServerCapabilitiesBuilder::new(&ClientCapabilities::default())
+                .capabilities(ServerCapabilities {
+                    position_encoding: Some(PositionEncodingKind::UTF8),
+                    ..ServerCapabilities::default()
+                })
+                .position_encodings(&[PositionEncodingKind::UTF8])
+                .build();
+        assert_eq!(
+            capabilities.position_encoding,
+            Some(PositionEncodingKind::UTF16)
+        );
+        assert_eq!(
+            warnings,
+            [
+                CapabilityWarning::PositionEncodingNotOffered(PositionEncodingKind::UTF8),
+                CapabilityWarning::NoCommonPositionEncoding,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_capabilities() {
+        let client = ClientCapabilities::default();
+        let (_, warnings) = ServerCapabilitiesBuilder::new(&client)
+            .capabilities(ServerCapabilities {
+                semantic_tokens_provider: Some(
+                    SemanticTokensOptions {
+                        full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                        ..SemanticTokensOptions::default()
+                    }
+                    .into(),
+                ),
+                code_action_provider: Some(
+                    CodeActionOptions {
+                        resolve_provider: Some(true),
+                        ..CodeActionOptions::default()
+                    }
+                    .into(),
+                ),
+                ..ServerCapabilities::default()
+            })
+            .handlers([
+                "textDocument/semanticTokens/full",
+                "textDocument/codeAction",
+                "codeAction/resolve",
+            ])
+            .build();
+        assert_eq!(
+            warnings,
+            [
+                CapabilityWarning::MissingHandler("textDocument/semanticTokens/full/delta"),
+                CapabilityWarning::CodeActionResolveUnsupported,
+            ]
+        );
+    }
+}
@@ -0,0 +1,1030 @@
+/*!
+
+Merging the `ServerCapabilities` of several language servers that are multiplexed behind a single
+client connection.
+
+[`merge_server_capabilities`] computes the capabilities a proxy announces to the client, which
+backends serve each method, and the conflicts the proxy can not resolve on its own. Backends are
+identified by their index in the slice passed in.
+
+*/
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    CallHierarchyServerCapability, CodeActionOptions, CodeActionProviderCapability,
+    CodeLensOptions, ColorProviderCapability, CompletionOptions, CompletionOptionsCompletionItem,
+    DeclarationCapability, DiagnosticServerCapabilities, DocumentLinkOptions,
+    DocumentOnTypeFormattingOptions, ExecuteCommandOptions, FileOperationRegistrationOptions,
+    FoldingRangeProviderCapability, HoverProviderCapability, ImplementationProviderCapability,
+    LinkedEditingRangeServerCapabilities, NotebookDocumentSyncOptions,
+    NotebookDocumentSyncRegistrationOptions, OneOf, PositionEncodingKind, SaveOptions,
+    SelectionRangeProviderCapability, SemanticTokensFullOptions, SemanticTokensOptions,
+    SemanticTokensServerCapabilities, ServerCapabilities, SignatureHelpOptions,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+    TextDocumentSyncSaveOptions, TypeDefinitionProviderCapability, WorkDoneProgressOptions,
+    WorkspaceFileOperationsServerCapabilities, WorkspaceFoldersServerCapabilities,
+    WorkspaceServerCapabilities,
+};
+
+/// A capability value that may announce that the capability is not provided.
+trait Provider {
+    fn is_enabled(&self) -> bool;
+}
+
+impl<T> Provider for OneOf<bool, T> {
+    fn is_enabled(&self) -> bool {
+        !matches!(self, OneOf::Left(false))
+    }
+}
+
+impl Provider for OneOf<NotebookDocumentSyncOptions, NotebookDocumentSyncRegistrationOptions> {
+    fn is_enabled(&self) -> bool {
+        true
+    }
+}
+
+macro_rules! simple_provider {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Provider for $ty {
+                fn is_enabled(&self) -> bool {
+                    !matches!(self, Self::Simple(false))
+                }
+            }
+        )*
+    };
+}
+
+simple_provider!(
+    CallHierarchyServerCapability,
+    CodeActionProviderCapability,
+    ColorProviderCapability,
+    DeclarationCapability,
+    FoldingRangeProviderCapability,
+    HoverProviderCapability,
+    ImplementationProviderCapability,
+    LinkedEditingRangeServerCapabilities,
+    SelectionRangeProviderCapability,
+    TypeDefinitionProviderCapability,
+);
+
+macro_rules! options_provider {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Provider for $ty {
+                fn is_enabled(&self) -> bool {
+                    true
+                }
+            }
+        )*
+    };
+}
+
+options_provider!(
+    CodeLensOptions,
+    CompletionOptions,
+    DiagnosticServerCapabilities,
+    DocumentLinkOptions,
+    DocumentOnTypeFormattingOptions,
+    SemanticTokensServerCapabilities,
+    SignatureHelpOptions,
+);
+
+/// A problem the merge could not resolve. The merged capabilities use the value of the first
+/// backend involved.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum CapabilityConflict {
+    /// The backends whose position encoding differs from the one of the first backend.
+    PositionEncoding { backends: Vec<usize> },
+    /// The backends use different semantic token legends, so their tokens can not be combined. Only
+    /// the first backend is advertised and routed.
+    SemanticTokensLegend { backends: Vec<usize> },
+    /// More than one backend registered the same command for `workspace/executeCommand`.
+    DuplicateCommand {
+        command: String,
+        backends: Vec<usize>,
+    },
+    /// More than one backend provides a method whose responses can not be combined, such as
+    /// formatting or rename.
+    ExclusiveMethod {
+        method: &'static str,
+        backends: Vec<usize>,
+    },
+}
+
+/// Which backends serve each method.
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct CapabilityRoutes {
+    methods: BTreeMap<&'static str, Vec<usize>>,
+    commands: HashMap<String, usize>,
+}
+
+impl CapabilityRoutes {
+    /// The backends providing `method`, in order.
+    pub fn backends(&self, method: &str) -> &[usize] {
+        self.methods.get(method).map_or(&[], Vec::as_slice)
+    }
+
+    /// The first backend providing `method`, which serves it if its responses can not be combined.
+    pub fn owner(&self, method: &str) -> Option<usize> {
+        self.backends(method).first().copied()
+    }
+
+    /// The backend that executes `command`.
+    pub fn command_owner(&self, command: &str) -> Option<usize> {
+        self.commands.get(command).copied()
+    }
+
+    /// All routed methods with their backends.
+    pub fn methods(&self) -> impl Iterator<Item = (&'static str, &[usize])> + '_ {
+        self.methods
+            .iter()
+            .map(|(method, backends)| (*method, backends.as_slice()))
+    }
+
+    fn add(&mut self, method: &'static str, backend: usize) {
+        self.methods.entry(method).or_default().push(backend);
+    }
+}
+
+/// The result of [`merge_server_capabilities`].
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct MergedServerCapabilities {
+    /// The capabilities to announce to the client.
+    pub capabilities: ServerCapabilities,
+    pub routes: CapabilityRoutes,
+    pub conflicts: Vec<CapabilityConflict>,
+}
+
+/// Merges the capabilities of `backends`:
+///
+/// * trigger characters, code action kinds, commands and file operation filters are united,
+/// * the text document sync kind is widened to the richest one requested by any backend; the proxy
+///   is expected to convert changes for backends asking for less,
+/// * other providers take the options of the first backend providing them.
+pub fn merge_server_capabilities(backends: &[ServerCapabilities]) -> MergedServerCapabilities {
+    let mut merger = Merger {
+        backends,
+        routes: CapabilityRoutes::default(),
+        conflicts: Vec::new(),
+    };
+
+    let capabilities = ServerCapabilities {
+        position_encoding: merger.position_encoding(),
+        text_document_sync: merger.text_document_sync(),
+        notebook_document_sync: merger.first("notebookDocument/sync", |caps| {
+            caps.notebook_document_sync.as_ref()
+        }),
+        selection_range_provider: merger.exclusive("textDocument/selectionRange", |caps| {
+            caps.selection_range_provider.as_ref()
+        }),
+        hover_provider: merger.first("textDocument/hover", |caps| caps.hover_provider.as_ref()),
+        completion_provider: merger.completion(),
+        signature_help_provider: merger.signature_help(),
+        definition_provider: merger.first("textDocument/definition", |caps| {
+            caps.definition_provider.as_ref()
+        }),
+        type_definition_provider: merger.first("textDocument/typeDefinition", |caps| {
+            caps.type_definition_provider.as_ref()
+        }),
+        implementation_provider: merger.first("textDocument/implementation", |caps| {
+            caps.implementation_provider.as_ref()
+        }),
+        references_provider: merger.first("textDocument/references", |caps| {
+            caps.references_provider.as_ref()
+        }),
+        document_highlight_provider: merger.first("textDocument/documentHighlight", |caps| {
+            caps.document_highlight_provider.as_ref()
+        }),
+        document_symbol_provider: merger.first("textDocument/documentSymbol", |caps| {
+            caps.document_symbol_provider.as_ref()
+        }),
+        workspace_symbol_provider: merger.first("workspace/symbol", |caps| {
+            caps.workspace_symbol_provider.as_ref()
+        }),
+        code_action_provider: merger.code_action(),
+        code_lens_provider: merger.code_lens(),
+        document_formatting_provider: merger.exclusive("textDocument/formatting", |caps| {
+            caps.document_formatting_provider.as_ref()
+        }),
+        document_range_formatting_provider: merger
+            .exclusive("textDocument/rangeFormatting", |caps| {
+                caps.document_range_formatting_provider.as_ref()
+            }),
+        document_on_type_formatting_provider: merger
+            .exclusive("textDocument/onTypeFormatting", |caps| {
+                caps.document_on_type_formatting_provider.as_ref()
+            }),
+        rename_provider: merger
+            .exclusive("textDocument/rename", |caps| caps.rename_provider.as_ref()),
+        document_link_provider: merger.document_link(),
+        color_provider: merger.first("textDocument/documentColor", |caps| {
+            caps.color_provider.as_ref()
+        }),
+        folding_range_provider: merger.first("textDocument/foldingRange", |caps| {
+            caps.folding_range_provider.as_ref()
+        }),
+        declaration_provider: merger.first("textDocument/declaration", |caps| {
+            caps.declaration_provider.as_ref()
+        }),
+        execute_command_provider: merger.execute_command(),
+        workspace: merger.workspace(),
+        call_hierarchy_provider: merger.first("textDocument/prepareCallHierarchy", |caps| {
+            caps.call_hierarchy_provider.as_ref()
+        }),
+        semantic_tokens_provider: merger.semantic_tokens(),
+        moniker_provider: merger.first("textDocument/moniker", |caps| {
+            caps.moniker_provider.as_ref()
+        }),
+        linked_editing_range_provider: merger
+            .exclusive("textDocument/linkedEditingRange", |caps| {
+                caps.linked_editing_range_provider.as_ref()
+            }),
+        inline_value_provider: merger.first("textDocument/inlineValue", |caps| {
+            caps.inline_value_provider.as_ref()
+        }),
+        inlay_hint_provider: merger.first("textDocument/inlayHint", |caps| {
+            caps.inlay_hint_provider.as_ref()
+        }),
+        diagnostic_provider: merger.first("textDocument/diagnostic", |caps| {
+            caps.diagnostic_provider.as_ref()
+        }),
+        #[cfg(feature = "proposed")]
+        inline_completion_provider: merger.first("textDocument/inlineCompletion", |caps| {
+            caps.inline_completion_provider.as_ref()
+        }),
+        experimental: backends.iter().find_map(|caps| caps.experimental.clone()),
+    };
+
+    MergedServerCapabilities {
+        capabilities,
+        routes: merger.routes,
+        conflicts: merger.conflicts,
+    }
+}
+
+/// Appends the values of `extra` that are not in `into` yet.
+fn union<T: PartialEq + Clone>(into: &mut Vec<T>, extra: &[T]) {
+    for value in extra {
+        if !into.contains(value) {
+            into.push(value.clone());
+        }
+    }
+}
+
+fn non_empty<T>(values: Vec<T>) -> Option<Vec<T>> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values)
+    }
+}
+
+fn sync_rank(kind: TextDocumentSyncKind) -> u8 {
+    match kind {
+        TextDocumentSyncKind::FULL => 1,
+        TextDocumentSyncKind::INCREMENTAL => 2,
+        _ => 0,
+    }
+}
+
+/// The options equivalent to `sync`. A bare kind also enables open, close and save notifications
+/// unless it is `None`.
+fn sync_options(sync: &TextDocumentSyncCapability) -> TextDocumentSyncOptions {
+    match sync {
+        TextDocumentSyncCapability::Kind(kind) => {
+            let enabled = *kind != TextDocumentSyncKind::NONE;
+            TextDocumentSyncOptions {
+                open_close: Some(enabled),
+                change: Some(*kind),
+                will_save: None,
+                will_save_wait_until: None,
+                save: Some(TextDocumentSyncSaveOptions::Supported(enabled)),
+            }
+        }
+        TextDocumentSyncCapability::Options(options) => options.clone(),
+    }
+}
+
+/// Whether save notifications are requested, and whether they should include the text.
+fn save_options(save: Option<&TextDocumentSyncSaveOptions>) -> Option<bool> {
+    match save? {
+        TextDocumentSyncSaveOptions::Supported(false) => None,
+        TextDocumentSyncSaveOptions::Supported(true) => Some(false),
+        TextDocumentSyncSaveOptions::SaveOptions(options) => {
+            Some(options.include_text.unwrap_or(false))
+        }
+    }
+}
+
+fn semantic_tokens_options(
+    capability: &SemanticTokensServerCapabilities,
+) -> &SemanticTokensOptions {
+    match capability {
+        SemanticTokensServerCapabilities::SemanticTokensOptions(options) => options,
+        SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+            &options.semantic_tokens_options
+        }
+    }
+}
+
+struct Merger<'a> {
+    backends: &'a [ServerCapabilities],
+    routes: CapabilityRoutes,
+    conflicts: Vec<CapabilityConflict>,
+}
+
+impl<'a> Merger<'a> {
+    /// The backends enabling the capability returned by `field`.
+    fn enabled<T: Provider>(
+        &self,
+        field: impl Fn(&'a ServerCapabilities) -> Option<&'a T>,
+    ) -> Vec<(usize, &'a T)> {
+        self.backends
+            .iter()
+            .enumerate()
+            .filter_map(|(backend, caps)| Some((backend, field(caps)?)))
+            .filter(|(_, provider)| provider.is_enabled())
+            .collect()
+    }
+
+    /// Routes `method` to every backend enabling the capability returned by `field`, and returns
+    /// them.
+    fn providers<T: Provider>(
+        &mut self,
+        method: &'static str,
+        field: impl Fn(&'a ServerCapabilities) -> Option<&'a T>,
+    ) -> Vec<(usize, &'a T)> {
+        let providers = self.enabled(field);
+        for (backend, _) in &providers {
+            self.routes.add(method, *backend);
+        }
+        providers
+    }
+
+    fn first<T: Provider + Clone + 'a>(
+        &mut self,
+        method: &'static str,
+        field: impl Fn(&'a ServerCapabilities) -> Option<&'a T>,
+    ) -> Option<T> {
+        self.providers(method, field)
+            .first()
+            .map(|(_, provider)| (*provider).clone())
+    }
+
+    /// Like [`Self::first`], for methods whose responses can not be combined.
+    fn exclusive<T: Provider + Clone + 'a>(
+        &mut self,
+        method: &'static str,
+        field: impl Fn(&'a ServerCapabilities) -> Option<&'a T>,
+    ) -> Option<T> {
+        let providers = self.providers(method, field);
+        if providers.len() > 1 {
+            self.conflicts.push(CapabilityConflict::ExclusiveMethod {
+                method,
+                backends: providers.iter().map(|(backend, _)| *backend).collect(),
+            });
+        }
+        providers.first().map(|(_, provider)| (*provider).clone())
+    }
+
+    fn position_encoding(&mut self) -> Option<PositionEncodingKind> {
+        let encoding = |caps: &ServerCapabilities| {
+            caps.position_encoding
+                .clone()
+                .unwrap_or(PositionEncodingKind::UTF16)
+        };
+        let first = encoding(self.backends.first()?);
+        let backends: Vec<usize> = (0..self.backends.len())
+            .filter(|&backend| encoding(&self.backends[backend]) != first)
+            .collect();
+        if !backends.is_empty() {
+            self.conflicts
+                .push(CapabilityConflict::PositionEncoding { backends });
+        }
+        // Absent means UTF-16, which every backend can interpret.
+        self.backends
+            .iter()
+            .any(|caps| caps.position_encoding.is_some())
+            .then_some(first)
+    }
+
+    fn text_document_sync(&mut self) -> Option<TextDocumentSyncCapability> {
+        let mut merged = TextDocumentSyncOptions::default();
+        let mut include_text = None;
+        for (backend, caps) in self.backends.iter().enumerate() {
+            let Some(sync) = &caps.text_document_sync else {
+                continue;
+            };
+            let options = sync_options(sync);
+            if options.open_close == Some(true) {
+                merged.open_close = Some(true);
+                self.routes.add("textDocument/didOpen", backend);
+                self.routes.add("textDocument/didClose", backend);
+            }
+            if let Some(kind) = options.change.filter(|kind| sync_rank(*kind) > 0) {
+                if merged
+                    .change
+                    .is_none_or(|merged| sync_rank(kind) > sync_rank(merged))
+                {
+                    merged.change = Some(kind);
+                }
+                self.routes.add("textDocument/didChange", backend);
+            }
+            if options.will_save == Some(true) {
+                merged.will_save = Some(true);
+                self.routes.add("textDocument/willSave", backend);
+            }
+            if options.will_save_wait_until == Some(true) {
+                merged.will_save_wait_until = Some(true);
+                self.routes.add("textDocument/willSaveWaitUntil", backend);
+            }
+            if let Some(text) = save_options(options.save.as_ref()) {
+                include_text = Some(include_text.unwrap_or(false) || text);
+                self.routes.add("textDocument/didSave", backend);
+            }
+        }
+        let backends = self.routes.backends("textDocument/willSaveWaitUntil");
+        if backends.len() > 1 {
+            self.conflicts.push(CapabilityConflict::ExclusiveMethod {
+                method: "textDocument/willSaveWaitUntil",
+                backends: backends.to_vec(),
+            });
+        }
+        merged.save = include_text.map(|include_text| {
+            TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
+                include_text: Some(include_text),
+            })
+        });
+        if merged == TextDocumentSyncOptions::default() {
+            None
+        } else {
+            Some(TextDocumentSyncCapability::Options(merged))
+        }
+    }
+
+    fn work_done_progress<'b>(
+        options: impl IntoIterator<Item = &'b WorkDoneProgressOptions>,
+    ) -> WorkDoneProgressOptions {
+        let progress = options
+            .into_iter()
+            .any(|options| options.work_done_progress == Some(true));
+        WorkDoneProgressOptions {
+            work_done_progress: if progress { Some(true) } else { None },
+        }
+    }
+
+    /// Unites trigger characters. Commit characters apply to every item, so they are only kept if
+    /// all backends agree.
+    fn completion(&mut self) -> Option<CompletionOptions> {
+        let providers = self.providers("textDocument/completion", |caps| {
+            caps.completion_provider.as_ref()
+        });
+        let (_, first) = providers.first()?;
+        let mut trigger_characters = Vec::new();
+        let mut label_details_support = false;
+        for (backend, options) in &providers {
+            union(
+                &mut trigger_characters,
+                options.trigger_characters.as_deref().unwrap_or_default(),
+            );
+            if options.resolve_provider == Some(true) {
+                self.routes.add("completionItem/resolve", *backend);
+            }
+            label_details_support |= options
+                .completion_item
+                .as_ref()
+                .is_some_and(|item| item.label_details_support == Some(true));
+        }
+        let all_commit_characters = first.all_commit_characters.clone().filter(|characters| {
+            providers
+                .iter()
+                .all(|(_, options)| options.all_commit_characters.as_ref() == Some(characters))
+        });
+        Some(CompletionOptions {
+            resolve_provider: self.resolves("completionItem/resolve"),
+            trigger_characters: non_empty(trigger_characters),
+            all_commit_characters,
+            work_done_progress_options: Self::work_done_progress(
+                providers
+                    .iter()
+                    .map(|(_, options)| &options.work_done_progress_options),
+            ),
+            completion_item: if label_details_support {
+                Some(CompletionOptionsCompletionItem {
+                    label_details_support: Some(true),
+                })
+            } else {
+                None
+            },
+        })
+    }
+
+    fn resolves(&self, method: &str) -> Option<bool> {
+        if self.routes.backends(method).is_empty() {
+            None
+        } else {
+            Some(true)
+        }
+    }
+
+    fn signature_help(&mut self) -> Option<SignatureHelpOptions> {
+        let providers = self.providers("textDocument/signatureHelp", |caps| {
+            caps.signature_help_provider.as_ref()
+        });
+        if providers.is_empty() {
+            return None;
+        }
+        let mut trigger_characters = Vec::new();
+        let mut retrigger_characters = Vec::new();
+        for (_, options) in &providers {
+            union(
+                &mut trigger_characters,
+                options.trigger_characters.as_deref().unwrap_or_default(),
+            );
+            union(
+                &mut retrigger_characters,
+                options.retrigger_characters.as_deref().unwrap_or_default(),
+            );
+        }
+        Some(SignatureHelpOptions {
+            trigger_characters: non_empty(trigger_characters),
+            retrigger_characters: non_empty(retrigger_characters),
+            work_done_progress_options: Self::work_done_progress(
+                providers
+                    .iter()
+                    .map(|(_, options)| &options.work_done_progress_options),
+            ),
+        })
+    }
+
+    /// Unites code action kinds. A backend that does not list its kinds may return any kind, so
+    /// the merged kinds are only announced if every backend lists them.
+    fn code_action(&mut self) -> Option<CodeActionProviderCapability> {
+        let providers = self.providers("textDocument/codeAction", |caps| {
+            caps.code_action_provider.as_ref()
+        });
+        if providers.is_empty() {
+            return None;
+        }
+        let mut kinds = Some(Vec::new());
+        let mut options = Vec::new();
+        for (backend, provider) in &providers {
+            match provider {
+                CodeActionProviderCapability::Simple(_) => kinds = None,
+                CodeActionProviderCapability::Options(provider_options) => {
+                    match (&mut kinds, &provider_options.code_action_kinds) {
+                        (Some(kinds), Some(provider_kinds)) => union(kinds, provider_kinds),
+                        _ => kinds = None,
+                    }
+                    if provider_options.resolve_provider == Some(true) {
+                        self.routes.add("codeAction/resolve", *backend);
+                    }
+                    options.push(provider_options);
+                }
+            }
+        }
+        if options.is_empty() {
+            return Some(CodeActionProviderCapability::Simple(true));
+        }
+        Some(CodeActionProviderCapability::Options(CodeActionOptions {
+            code_action_kinds: kinds,
+            work_done_progress_options: Self::work_done_progress(
+                options
+                    .iter()
+                    .map(|options| &options.work_done_progress_options),
+            ),
+            resolve_provider: self.resolves("codeAction/resolve"),
+        }))
+    }
+
+    fn code_lens(&mut self) -> Option<CodeLensOptions> {
+        let providers = self.providers("textDocument/codeLens", |caps| {
+            caps.code_lens_provider.as_ref()
+        });
+        if providers.is_empty() {
+            return None;
+        }
+        for (backend, options) in &providers {
+            if options.resolve_provider == Some(true) {
+                self.routes.add("codeLens/resolve", *backend);
+            }
+        }
+        Some(CodeLensOptions {
+            resolve_provider: self.resolves("codeLens/resolve"),
+        })
+    }
+
+    fn document_link(&mut self) -> Option<DocumentLinkOptions> {
+        let providers = self.providers("textDocument/documentLink", |caps| {
+            caps.document_link_provider.as_ref()
+        });
+        if providers.is_empty() {
+            return None;
+        }
+        for (backend, options) in &providers {
+            if options.resolve_provider == Some(true) {
+                self.routes.add("documentLink/resolve", *backend);
+            }
+        }
+        Some(DocumentLinkOptions {
+            resolve_provider: self.resolves("documentLink/resolve"),
+            work_done_progress_options: Self::work_done_progress(
+                providers
+                    .iter()
+                    .map(|(_, options)| &options.work_done_progress_options),
+            ),
+        })
+    }
+
+    fn execute_command(&mut self) -> Option<ExecuteCommandOptions> {
+        let mut commands = Vec::new();
+        let mut owners: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        let mut progress = Vec::new();
+        for (backend, caps) in self.backends.iter().enumerate() {
+            let Some(options) = &caps.execute_command_provider else {
+                continue;
+            };
+            self.routes.add("workspace/executeCommand", backend);
+            progress.push(&options.work_done_progress_options);
+            for command in &options.commands {
+                let backends = owners.entry(command).or_default();
+                if backends.is_empty() {
+                    commands.push(command.clone());
+                    self.routes.commands.insert(command.clone(), backend);
+                }
+                if !backends.contains(&backend) {
+                    backends.push(backend);
+                }
+            }
+        }
+        for (command, backends) in owners {
+            if backends.len() > 1 {
+                self.conflicts.push(CapabilityConflict::DuplicateCommand {
+                    command: command.to_string(),
+                    backends,
+                });
+            }
+        }
+        if progress.is_empty() {
+            return None;
+        }
+        Some(ExecuteCommandOptions {
+            commands,
+            work_done_progress_options: Self::work_done_progress(progress),
+        })
+    }
+
+    fn file_operation(
+        &mut self,
+        method: &'static str,
+        field: impl Fn(
+            &'a WorkspaceFileOperationsServerCapabilities,
+        ) -> Option<&'a FileOperationRegistrationOptions>,
+    ) -> Option<FileOperationRegistrationOptions> {
+        let mut filters = Vec::new();
+        for (backend, caps) in self.backends.iter().enumerate() {
+            let options = caps
+                .workspace
+                .as_ref()
+                .and_then(|workspace| workspace.file_operations.as_ref())
+                .and_then(&field);
+            if let Some(options) = options {
+                union(&mut filters, &options.filters);
+                self.routes.add(method, backend);
+            }
+        }
+        if self.routes.backends(method).is_empty() {
+            None
+        } else {
+            Some(FileOperationRegistrationOptions { filters })
+        }
+    }
+
+    fn workspace(&mut self) -> Option<WorkspaceServerCapabilities> {
+        let mut workspace_folders: Option<WorkspaceFoldersServerCapabilities> = None;
+        for (backend, caps) in self.backends.iter().enumerate() {
+            let Some(folders) = caps
+                .workspace
+                .as_ref()
+                .and_then(|workspace| workspace.workspace_folders.as_ref())
+            else {
+                continue;
+            };
+            let merged = workspace_folders.get_or_insert_with(Default::default);
+            if folders.supported == Some(true) {
+                merged.supported = Some(true);
+            }
+            match &folders.change_notifications {
+                Some(OneOf::Left(false)) | None => {}
+                Some(notifications) => {
+                    if matches!(merged.change_notifications, None | Some(OneOf::Left(_))) {
+                        merged.change_notifications = Some(notifications.clone());
+                    }
+                    self.routes
+                        .add("workspace/didChangeWorkspaceFolders", backend);
+                }
+            }
+        }
+
+        let file_operations = WorkspaceFileOperationsServerCapabilities {
+            did_create: self
+                .file_operation("workspace/didCreateFiles", |ops| ops.did_create.as_ref()),
+            will_create: self
+                .file_operation("workspace/willCreateFiles", |ops| ops.will_create.as_ref()),
+            did_rename: self
+                .file_operation("workspace/didRenameFiles", |ops| ops.did_rename.as_ref()),
+            will_rename: self
+                .file_operation("workspace/willRenameFiles", |ops| ops.will_rename.as_ref()),
+            did_delete: self
+                .file_operation("workspace/didDeleteFiles", |ops| ops.did_delete.as_ref()),
+            will_delete: self
+                .file_operation("workspace/willDeleteFiles", |ops| ops.will_delete.as_ref()),
+        };
+        let file_operations = if file_operations == Default::default() {
+            None
+        } else {
+            Some(file_operations)
+        };
+
+        if workspace_folders.is_none() && file_operations.is_none() {
+            return None;
+        }
+        Some(WorkspaceServerCapabilities {
+            workspace_folders,
+            file_operations,
+        })
+    }
+
+    /// With equal legends the requests of every backend are advertised and routed to it. Token
+    /// indices are only meaningful with the legend of the backend that produced them, so with
+    /// conflicting legends only the first backend is advertised and routed.
+    fn semantic_tokens(&mut self) -> Option<SemanticTokensServerCapabilities> {
+        let mut providers = self.enabled(|caps| caps.semantic_tokens_provider.as_ref());
+        let (_, first) = *providers.first()?;
+        let legend = &semantic_tokens_options(first).legend;
+        if providers
+            .iter()
+            .any(|(_, provider)| &semantic_tokens_options(provider).legend != legend)
+        {
+            self.conflicts
+                .push(CapabilityConflict::SemanticTokensLegend {
+                    backends: providers.iter().map(|(backend, _)| *backend).collect(),
+                });
+            providers.truncate(1);
+        }
+
+        let (mut full, mut delta, mut range) = (false, false, false);
+        for (backend, provider) in &providers {
+            let options = semantic_tokens_options(provider);
+            match &options.full {
+                None | Some(SemanticTokensFullOptions::Bool(false)) => {}
+                Some(options) => {
+                    full = true;
+                    self.routes
+                        .add("textDocument/semanticTokens/full", *backend);
+                    if matches!(
+                        options,
+                        SemanticTokensFullOptions::Delta { delta: Some(true) }
+                    ) {
+                        delta = true;
+                        self.routes
+                            .add("textDocument/semanticTokens/full/delta", *backend);
+                    }
+                }
+            }
+            if options.range == Some(true) {
+                range = true;
+                self.routes
+                    .add("textDocument/semanticTokens/range", *backend);
+            }
+        }
+
+        let mut merged = first.clone();
+        let options = match &mut merged {
+            SemanticTokensServerCapabilities::SemanticTokensOptions(options) => options,
+            SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+                &mut options.semantic_tokens_options
+            }
+        };
+        options.full = match (full, delta) {
+            (_, true) => Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+            (true, false) => Some(SemanticTokensFullOptions::Bool(true)),
+            (false, false) => None,
+        };
+        options.range = range.then_some(true);
+        Some(merged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CodeActionKind, SemanticTokenType, SemanticTokensLegend};
+
+    #[test]
+    fn test_merge_server_capabilities() {
+        let checker = ServerCapabilities {
+            text_document_sync: Some(TextDocumentSyncKind::INCREMENTAL.into()),
+            completion_provider: Some(CompletionOptions {
+                trigger_characters: Some(vec![".".into(), ":".into()]),
+                resolve_provider: Some(true),
+                ..CompletionOptions::default()
+            }),
+            code_action_provider: Some(
+                CodeActionOptions {
+                    code_action_kinds: Some(vec![CodeActionKind::QUICKFIX]),
+                    ..CodeActionOptions::default()
+                }
+                .into(),
+            ),
+            execute_command_provider: Some(ExecuteCommandOptions {
+                commands: vec!["check.run".into(), "fix".into()],
+                ..ExecuteCommandOptions::default()
+            }),
+            ..ServerCapabilities::default()
+        };
+        let linter = ServerCapabilities {
+            text_document_sync: Some(TextDocumentSyncKind::FULL.into()),
+            completion_provider: Some(CompletionOptions {
+                trigger_characters: Some(vec![".".into(), "@".into()]),
+                ..CompletionOptions::default()
+            }),
+            code_action_provider: Some(
+                CodeActionOptions {
+                    code_action_kinds: Some(vec![
+                        CodeActionKind::QUICKFIX,
+                        CodeActionKind::SOURCE_FIX_ALL,
+                    ]),
+                    ..CodeActionOptions::default()
+                }
+                .into(),
+            ),
+            execute_command_provider: Some(ExecuteCommandOptions {
+                commands: vec!["fix".into()],
+                ..ExecuteCommandOptions::default()
+            }),
+            ..ServerCapabilities::default()
+        };
+        let formatter = ServerCapabilities {
+            text_document_sync: Some(TextDocumentSyncKind::FULL.into()),
+            document_formatting_provider: Some(OneOf::Left(true)),
+            ..ServerCapabilities::default()
+        };
+
+        let merged = merge_server_capabilities(&[checker, linter, formatter]);
+        let capabilities = &merged.capabilities;
+        match &capabilities.text_document_sync {
+            Some(TextDocumentSyncCapability::Options(options)) => {
+                assert_eq!(options.change, Some(TextDocumentSyncKind::INCREMENTAL));
+                assert_eq!(options.open_close, Some(true));
+            }
+            sync => panic!("unexpected sync capability {:?}", sync),
+        }
+        let completion = capabilities.completion_provider.as_ref().unwrap();
+        assert_eq!(
+            completion.trigger_characters,
+            Some(vec![".".into(), ":".into(), "@".into()])
+        );
+        assert_eq!(completion.resolve_provider, Some(true));
+        assert_eq!(
+            capabilities.code_action_provider,
+            Some(
+                CodeActionOptions {
+                    code_action_kinds: Some(vec![
+                        CodeActionKind::QUICKFIX,
+                        CodeActionKind::SOURCE_FIX_ALL,
+                    ]),
+                    ..CodeActionOptions::default()
+                }
+                .into()
+            )
+        );
+        assert_eq!(
+            capabilities
+                .execute_command_provider
+                .as_ref()
+                .unwrap()
+                .commands,
+            ["check.run", "fix"]
+        );
+
+        let routes = &merged.routes;
+        assert_eq!(routes.backends("textDocument/didChange"), [0, 1, 2]);
+        assert_eq!(routes.backends("textDocument/completion"), [0, 1]);
+        assert_eq!(routes.backends("completionItem/resolve"), [0]);
+        assert_eq!(routes.owner("textDocument/formatting"), Some(2));
+        assert_eq!(routes.owner("textDocument/hover"), None);
+        assert_eq!(routes.command_owner("fix"), Some(0));
+        assert_eq!(
+            merged.conflicts,
+            [CapabilityConflict::DuplicateCommand {
+                command: "fix".into(),
+                backends: vec![0, 1],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_semantic_tokens_legend_conflict() {
+        let tokens = |token_types| ServerCapabilities {
+            semantic_tokens_provider: Some(
+                SemanticTokensOptions {
+                    legend: SemanticTokensLegend {
+                        token_types,
+                        token_modifiers: vec![],
+                    },
+                    full: Some(SemanticTokensFullOptions::Bool(true)),
+                    ..SemanticTokensOptions::default()
+                }
+                .into(),
+            ),
+            ..ServerCapabilities::default()
+        };
+        let merged = merge_server_capabilities(&[
+            tokens(vec![SemanticTokenType::FUNCTION]),
+            tokens(vec![SemanticTokenType::TYPE]),
+        ]);
+        assert_eq!(
+            merged.conflicts,
+            [CapabilityConflict::SemanticTokensLegend {
+                backends: vec![0, 1]
+            }]
+        );
+        assert_eq!(
+            merged.routes.backends("textDocument/semanticTokens/full"),
+            [0]
+        );
+        assert!(merged
+            .routes
+            .backends("textDocument/semanticTokens/range")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_semantic_tokens_request_kinds() {
+        let tokens = |full, range| ServerCapabilities {
+            semantic_tokens_provider: Some(
+                SemanticTokensOptions {
+                    full,
+                    range,
+                    ..SemanticTokensOptions::default()
+                }
+                .into(),
+            ),
+            ..ServerCapabilities::default()
+        };
+        let merged = merge_server_capabilities(&[
+            tokens(None, Some(true)),
+            tokens(
+                Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                None,
+            ),
+        ]);
+        assert!(merged.conflicts.is_empty());
+        assert_eq!(
+            merged.capabilities.semantic_tokens_provider,
+            Some(
+                SemanticTokensOptions {
+                    full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                    range: Some(true),
+                    ..SemanticTokensOptions::default()
+                }
+                .into()
+            )
+        );
+        let routes = &merged.routes;
+        assert_eq!(routes.backends("textDocument/semanticTokens/range"), [0]);
+        assert_eq!(routes.backends("textDocument/semanticTokens/full"), [1]);
+        assert_eq!(
+            routes.backends("textDocument/semanticTokens/full/delta"),
+            [1]
+        );
+    }
+
+    #[test]
+    fn test_position_encoding_conflict() {
+        let encoding = |position_encoding| ServerCapabilities {
+            position_encoding,
+            ..ServerCapabilities::default()
+        };
+        let merged = merge_server_capabilities(&[
+            encoding(None),
+            encoding(Some(PositionEncodingKind::UTF8)),
+            encoding(Some(PositionEncodingKind::UTF16)),
+        ]);
+        assert_eq!(
+            merged.capabilities.position_encoding,
+            Some(PositionEncodingKind::UTF16)
+        );
+        assert_eq!(
+            merged.conflicts,
+            [CapabilityConflict::PositionEncoding { backends: vec![1] }]
+        );
+
+        let merged = merge_server_capabilities(&[encoding(None), encoding(None)]);
+        assert_eq!(merged.capabilities.position_encoding, None);
+        assert!(merged.conflicts.is_empty());
+    }
+}
@@ -0,0 +1,971 @@
+/*!
+
+A flat, resolved view over `ClientCapabilities`.
+
+Every capability of the client is optional and nested several levels deep. The accessors of
+[`ResolvedClientCapabilities`] walk the nesting and apply the default the specification gives
+when a capability is missing: `false` for flags, the initial value sets of `SymbolKind` and
+`CompletionItemKind`, UTF-16 for position encodings and plain text for markup.
+
+*/
+
+use crate::{
+    ClientCapabilities, CodeActionClientCapabilities, CompletionClientCapabilities,
+    CompletionItemCapability, CompletionItemKind, CompletionItemTag, DiagnosticTag,
+    DocumentSymbolClientCapabilities, DynamicRegistrationClientCapabilities, FailureHandlingKind,
+    FoldingRangeClientCapabilities, FoldingRangeKind, GeneralClientCapabilities, GotoCapability,
+    InsertTextMode, MarkdownClientCapabilities, MarkupKind, PositionEncodingKind,
+    PrepareSupportDefaultBehavior, PublishDiagnosticsClientCapabilities,
+    RegularExpressionsClientCapabilities, RenameClientCapabilities, ResourceOperationKind,
+    SemanticTokenModifier, SemanticTokenType, SemanticTokensClientCapabilities,
+    SemanticTokensFullOptions, SignatureInformationSettings, SymbolKind, SymbolTag,
+    TextDocumentClientCapabilities, TextDocumentSyncClientCapabilities, TokenFormat,
+    WindowClientCapabilities, WorkspaceClientCapabilities, WorkspaceEditClientCapabilities,
+    WorkspaceFileOperationsClientCapabilities, WorkspaceSymbolClientCapabilities,
+};
+
+/// The symbol kinds a client supports if it does not send a value set, `File` to `Array`.
+const DEFAULT_SYMBOL_KINDS: &[SymbolKind] = &[
+    SymbolKind::FILE,
+    SymbolKind::MODULE,
+    SymbolKind::NAMESPACE,
+    SymbolKind::PACKAGE,
+    SymbolKind::CLASS,
+    SymbolKind::METHOD,
+    SymbolKind::PROPERTY,
+    SymbolKind::FIELD,
+    SymbolKind::CONSTRUCTOR,
+    SymbolKind::ENUM,
+    SymbolKind::INTERFACE,
+    SymbolKind::FUNCTION,
+    SymbolKind::VARIABLE,
+    SymbolKind::CONSTANT,
+    SymbolKind::STRING,
+    SymbolKind::NUMBER,
+    SymbolKind::BOOLEAN,
+    SymbolKind::ARRAY,
+];
+
+/// The completion item kinds a client supports if it does not send a value set, `Text` to
+/// `Reference`.
+const DEFAULT_COMPLETION_ITEM_KINDS: &[CompletionItemKind] = &[
+    CompletionItemKind::TEXT,
+    CompletionItemKind::METHOD,
+    CompletionItemKind::FUNCTION,
+    CompletionItemKind::CONSTRUCTOR,
+    CompletionItemKind::FIELD,
+    CompletionItemKind::VARIABLE,
+    CompletionItemKind::CLASS,
+    CompletionItemKind::INTERFACE,
+    CompletionItemKind::MODULE,
+    CompletionItemKind::PROPERTY,
+    CompletionItemKind::UNIT,
+    CompletionItemKind::VALUE,
+    CompletionItemKind::ENUM,
+    CompletionItemKind::KEYWORD,
+    CompletionItemKind::SNIPPET,
+    CompletionItemKind::COLOR,
+    CompletionItemKind::FILE,
+    CompletionItemKind::REFERENCE,
+];
+
+const DEFAULT_POSITION_ENCODINGS: &[PositionEncodingKind] = &[PositionEncodingKind::UTF16];
+
+const DEFAULT_MARKUP_KINDS: &[MarkupKind] = &[MarkupKind::PlainText];
+
+/// A view over `ClientCapabilities` with the defaults of the specification applied.
+#[derive(Debug, Clone, Copy)]
+pub struct ResolvedClientCapabilities<'a> {
+    capabilities: &'a ClientCapabilities,
+}
+
+impl ClientCapabilities {
+    /// Returns a flat view over these capabilities, see [`ResolvedClientCapabilities`].
+    pub fn resolved(&self) -> ResolvedClientCapabilities<'_> {
+        ResolvedClientCapabilities::new(self)
+    }
+}
+
+impl<'a> ResolvedClientCapabilities<'a> {
+    pub fn new(capabilities: &'a ClientCapabilities) -> Self {
+        ResolvedClientCapabilities { capabilities }
+    }
+
+    /// The underlying capabilities.
+    pub fn capabilities(&self) -> &'a ClientCapabilities {
+        self.capabilities
+    }
+
+    fn general(&self) -> Option<&'a GeneralClientCapabilities> {
+        self.capabilities.general.as_ref()
+    }
+
+    fn window(&self) -> Option<&'a WindowClientCapabilities> {
+        self.capabilities.window.as_ref()
+    }
+
+    fn workspace(&self) -> Option<&'a WorkspaceClientCapabilities> {
+        self.capabilities.workspace.as_ref()
+    }
+
+    fn workspace_edit(&self) -> Option<&'a WorkspaceEditClientCapabilities> {
+        self.workspace()?.workspace_edit.as_ref()
+    }
+
+    fn workspace_symbol(&self) -> Option<&'a WorkspaceSymbolClientCapabilities> {
+        self.workspace()?.symbol.as_ref()
+    }
+
+    fn file_operations(&self) -> Option<&'a WorkspaceFileOperationsClientCapabilities> {
+        self.workspace()?.file_operations.as_ref()
+    }
+
+    fn text_document(&self) -> Option<&'a TextDocumentClientCapabilities> {
+        self.capabilities.text_document.as_ref()
+    }
+
+    fn synchronization(&self) -> Option<&'a TextDocumentSyncClientCapabilities> {
+        self.text_document()?.synchronization.as_ref()
+    }
+
+    fn completion(&self) -> Option<&'a CompletionClientCapabilities> {
+        self.text_document()?.completion.as_ref()
+    }
+
+    fn completion_item(&self) -> Option<&'a CompletionItemCapability> {
+        self.completion()?.completion_item.as_ref()
+    }
+
+    fn signature_information(&self) -> Option<&'a SignatureInformationSettings> {
+        self.text_document()?
+            .signature_help
+            .as_ref()?
+            .signature_information
+            .as_ref()
+    }
+
+    fn document_symbol(&self) -> Option<&'a DocumentSymbolClientCapabilities> {
+        self.text_document()?.document_symbol.as_ref()
+    }
+
+    fn code_action(&self) -> Option<&'a CodeActionClientCapabilities> {
+        self.text_document()?.code_action.as_ref()
+    }
+
+    fn rename(&self) -> Option<&'a RenameClientCapabilities> {
+        self.text_document()?.rename.as_ref()
+    }
+
+    fn publish_diagnostics(&self) -> Option<&'a PublishDiagnosticsClientCapabilities> {
+        self.text_document()?.publish_diagnostics.as_ref()
+    }
+
+    fn folding_range(&self) -> Option<&'a FoldingRangeClientCapabilities> {
+        self.text_document()?.folding_range.as_ref()
+    }
+
+    fn semantic_tokens(&self) -> Option<&'a SemanticTokensClientCapabilities> {
+        self.text_document()?.semantic_tokens.as_ref()
+    }
+
+    // ------------------------------ general ------------------------------
+
+    /// The position encodings the client supports, in order of preference. UTF-16 if the client
+    /// does not say.
+    pub fn position_encodings(&self) -> &'a [PositionEncodingKind] {
+        self.general()
+            .and_then(|general| general.position_encodings.as_deref())
+            .filter(|encodings| !encodings.is_empty())
+            .unwrap_or(DEFAULT_POSITION_ENCODINGS)
+    }
+
+    /// Whether the client supports `encoding`. UTF-16 is always supported.
+    pub fn supports_position_encoding(&self, encoding: &PositionEncodingKind) -> bool {
+        *encoding == PositionEncodingKind::UTF16 || self.position_encodings().contains(encoding)
+    }
+
+    pub fn regular_expressions(&self) -> Option<&'a RegularExpressionsClientCapabilities> {
+        self.general()?.regular_expressions.as_ref()
+    }
+
+    pub fn markdown(&self) -> Option<&'a MarkdownClientCapabilities> {
+        self.general()?.markdown.as_ref()
+    }
+
+    /// Whether the client cancels stale requests.
+    pub fn cancels_stale_requests(&self) -> bool {
+        self.general()
+            .and_then(|general| general.stale_request_support.as_ref())
+            .is_some_and(|support| support.cancel)
+    }
+
+    /// The requests the client retries when they fail with `ContentModified`.
+    pub fn retry_on_content_modified(&self) -> &'a [String] {
+        self.general()
+            .and_then(|general| general.stale_request_support.as_ref())
+            .map_or(&[], |support| &support.retry_on_content_modified)
+    }
+
+    // ------------------------------ window ------------------------------
+
+    pub fn supports_work_done_progress(&self) -> bool {
+        self.window()
+            .and_then(|window| window.work_done_progress)
+            .unwrap_or(false)
+    }
+
+    pub fn supports_show_document(&self) -> bool {
+        self.window()
+            .and_then(|window| window.show_document.as_ref())
+            .is_some_and(|show_document| show_document.support)
+    }
+
+    /// Whether the client keeps additional properties of `MessageActionItem`s.
+    pub fn message_action_item_additional_properties(&self) -> bool {
+        self.window()
+            .and_then(|window| window.show_message.as_ref())
+            .and_then(|show_message| show_message.message_action_item.as_ref())
+            .and_then(|item| item.additional_properties_support)
+            .unwrap_or(false)
+    }
+
+    // ------------------------------ workspace ------------------------------
+
+    pub fn supports_apply_edit(&self) -> bool {
+        self.workspace()
+            .and_then(|workspace| workspace.apply_edit)
+            .unwrap_or(false)
+    }
+
+    /// Whether the client supports `WorkspaceEdit.document_changes`.
+    pub fn supports_document_changes(&self) -> bool {
+        self.workspace_edit()
+            .and_then(|edit| edit.document_changes)
+            .unwrap_or(false)
+    }
+
+    /// The resource operations the client supports in workspace edits.
+    pub fn resource_operations(&self) -> &'a [ResourceOperationKind] {
+        self.workspace_edit()
+            .and_then(|edit| edit.resource_operations.as_deref())
+            .unwrap_or(&[])
+    }
+
+    /// How the client handles failing workspace edits. The specification has no default.
+    pub fn failure_handling(&self) -> Option<FailureHandlingKind> {
+        self.workspace_edit()?.failure_handling
+    }
+
+    pub fn normalizes_line_endings(&self) -> bool {
+        self.workspace_edit()
+            .and_then(|edit| edit.normalizes_line_endings)
+            .unwrap_or(false)
+    }
+
+    pub fn supports_change_annotations(&self) -> bool {
+        self.workspace_edit()
+            .is_some_and(|edit| edit.change_annotation_support.is_some())
+    }
+
+    pub fn change_annotation_groups_on_label(&self) -> bool {
+        self.workspace_edit()
+            .and_then(|edit| edit.change_annotation_support.as_ref())
+            .and_then(|support| support.groups_on_label)
+            .unwrap_or(false)
+    }
+
+    pub fn supports_workspace_folders(&self) -> bool {
+        self.workspace()
+            .and_then(|workspace| workspace.workspace_folders)
+            .unwrap_or(false)
+    }
+
+    /// Whether the client supports `workspace/configuration` requests.
+    pub fn supports_configuration(&self) -> bool {
+        self.workspace()
+            .and_then(|workspace| workspace.configuration)
+            .unwrap_or(false)
+    }
+
+    pub fn watched_files_relative_pattern_support(&self) -> bool {
+        self.workspace()
+            .and_then(|workspace| workspace.did_change_watched_files.as_ref())
+            .and_then(|watched| watched.relative_pattern_support)
+            .unwrap_or(false)
+    }
+
+    pub fn workspace_symbol_kinds(&self) -> &'a [SymbolKind] {
+        self.workspace_symbol()
+            .and_then(|symbol| symbol.symbol_kind.as_ref())
+            .and_then(|kind| kind.value_set.as_deref())
+            .unwrap_or(DEFAULT_SYMBOL_KINDS)
+    }
+
+    pub fn workspace_symbol_tags(&self) -> &'a [SymbolTag] {
+        self.workspace_symbol()
+            .and_then(|symbol| symbol.tag_support.as_ref())
+            .map_or(&[], |tags| &tags.value_set)
+    }
+
+    pub fn workspace_symbol_resolve_properties(&self) -> &'a [String] {
+        self.workspace_symbol()
+            .and_then(|symbol| symbol.resolve_support.as_ref())
+            .map_or(&[], |support| &support.properties)
+    }
+
+    pub fn semantic_tokens_refresh_support(&self) -> bool {
+        self.workspace()
+            .and_then(|workspace| workspace.semantic_tokens.as_ref())
+            .and_then(|tokens| tokens.refresh_support)
+            .unwrap_or(false)
+    }
+
+    pub fn code_lens_refresh_support(&self) -> bool {
+        self.workspace()
+            .and_then(|workspace| workspace.code_lens.as_ref())
+            .and_then(|code_lens| code_lens.refresh_support)
+            .unwrap_or(false)
+    }
+
+    pub fn inline_value_refresh_support(&self) -> bool {
+        self.workspace()
+            .and_then(|workspace| workspace.inline_value.as_ref())
+            .and_then(|inline_value| inline_value.refresh_support)
+            .unwrap_or(false)
+    }
+
+    pub fn inlay_hint_refresh_support(&self) -> bool {
+        self.workspace()
+            .and_then(|workspace| workspace.inlay_hint.as_ref())
+            .and_then(|inlay_hint| inlay_hint.refresh_support)
+            .unwrap_or(false)
+    }
+
+    pub fn diagnostic_refresh_support(&self) -> bool {
+        self.workspace()
+            .and_then(|workspace| workspace.diagnostic.as_ref())
+            .and_then(|diagnostic| diagnostic.refresh_support)
+            .unwrap_or(false)
+    }
+
+    pub fn supports_did_create_files(&self) -> bool {
+        self.file_operations()
+            .and_then(|operations| operations.did_create)
+            .unwrap_or(false)
+    }
+
+    pub fn supports_will_create_files(&self) -> bool {
+        self.file_operations()
+            .and_then(|operations| operations.will_create)
+            .unwrap_or(false)
+    }
+
+    pub fn supports_did_rename_files(&self) -> bool {
+        self.file_operations()
+            .and_then(|operations| operations.did_rename)
+            .unwrap_or(false)
+    }
+
+    pub fn supports_will_rename_files(&self) -> bool {
+        self.file_operations()
+            .and_then(|operations| operations.will_rename)
+            .unwrap_or(false)
+    }
+
+    pub fn supports_did_delete_files(&self) -> bool {
+        self.file_operations()
+            .and_then(|operations| operations.did_delete)
+            .unwrap_or(false)
+    }
+
+    pub fn supports_will_delete_files(&self) -> bool {
+        self.file_operations()
+            .and_then(|operations| operations.will_delete)
+            .unwrap_or(false)
+    }
+
+    // ------------------------------ text document ------------------------------
+
+    pub fn supports_will_save(&self) -> bool {
+        self.synchronization()
+            .and_then(|sync| sync.will_save)
+            .unwrap_or(false)
+    }
+
+    pub fn supports_will_save_wait_until(&self) -> bool {
+        self.synchronization()
+            .and_then(|sync| sync.will_save_wait_until)
+            .unwrap_or(false)
+    }
+
+    pub fn supports_did_save(&self) -> bool {
+        self.synchronization()
+            .and_then(|sync| sync.did_save)
+            .unwrap_or(false)
+    }
+
+    pub fn supports_snippets(&self) -> bool {
+        self.completion_item()
+            .and_then(|item| item.snippet_support)
+            .unwrap_or(false)
+    }
+
+    pub fn completion_commit_characters(&self) -> bool {
+        self.completion_item()
+            .and_then(|item| item.commit_characters_support)
+            .unwrap_or(false)
+    }
+
+    pub fn completion_documentation_formats(&self) -> &'a [MarkupKind] {
+        self.completion_item()
+            .and_then(|item| item.documentation_format.as_deref())
+            .unwrap_or(DEFAULT_MARKUP_KINDS)
+    }
+
+    pub fn completion_deprecated_support(&self) -> bool {
+        self.completion_item()
+            .and_then(|item| item.deprecated_support)
+            .unwrap_or(false)
+    }
+
+    pub fn completion_preselect_support(&self) -> bool {
+        self.completion_item()
+            .and_then(|item| item.preselect_support)
+            .unwrap_or(false)
+    }
+
+    pub fn completion_item_tags(&self) -> &'a [CompletionItemTag] {
+        self.completion_item()
+            .and_then(|item| item.tag_support.as_ref())
+            .map_or(&[], |tags| &tags.value_set)
+    }
+
+    /// Whether completion items may use an `InsertReplaceEdit`.
+    pub fn completion_insert_replace_support(&self) -> bool {
+        self.completion_item()
+            .and_then(|item| item.insert_replace_support)
+            .unwrap_or(false)
+    }
+
+    pub fn completion_resolve_properties(&self) -> &'a [String] {
+        self.completion_item()
+            .and_then(|item| item.resolve_support.as_ref())
+            .map_or(&[], |support| &support.properties)
+    }
+
+    pub fn completion_insert_text_modes(&self) -> &'a [InsertTextMode] {
+        self.completion_item()
+            .and_then(|item| item.insert_text_mode_support.as_ref())
+            .map_or(&[], |support| &support.value_set)
+    }
+
+    pub fn completion_label_details_support(&self) -> bool {
+        self.completion_item()
+            .and_then(|item| item.label_details_support)
+            .unwrap_or(false)
+    }
+
+    pub fn completion_item_kinds(&self) -> &'a [CompletionItemKind] {
+        self.completion()
+            .and_then(|completion| completion.completion_item_kind.as_ref())
+            .and_then(|kind| kind.value_set.as_deref())
+            .unwrap_or(DEFAULT_COMPLETION_ITEM_KINDS)
+    }
+
+    pub fn completion_context_support(&self) -> bool {
+        self.completion()
+            .and_then(|completion| completion.context_support)
+            .unwrap_or(false)
+    }
+
+    /// The client's default insert text mode. The specification has no default.
+    pub fn completion_default_insert_text_mode(&self) -> Option<InsertTextMode> {
+        self.completion()?.insert_text_mode
+    }
+
+    /// The properties of `CompletionList.item_defaults` the client supports.
+    pub fn completion_list_item_defaults(&self) -> &'a [String] {
+        self.completion()
+            .and_then(|completion| completion.completion_list.as_ref())
+            .and_then(|list| list.item_defaults.as_deref())
+            .unwrap_or(&[])
+    }
+
+    pub fn hover_content_formats(&self) -> &'a [MarkupKind] {
+        self.text_document()
+            .and_then(|text_document| text_document.hover.as_ref())
+            .and_then(|hover| hover.content_format.as_deref())
+            .unwrap_or(DEFAULT_MARKUP_KINDS)
+    }
+
+    pub fn signature_documentation_formats(&self) -> &'a [MarkupKind] {
+        self.signature_information()
+            .and_then(|information| information.documentation_format.as_deref())
+            .unwrap_or(DEFAULT_MARKUP_KINDS)
+    }
+
+    pub fn signature_parameter_label_offset_support(&self) -> bool {
+        self.signature_information()
+            .and_then(|information| information.parameter_information.as_ref())
+            .and_then(|parameter| parameter.label_offset_support)
+            .unwrap_or(false)
+    }
+
+    pub fn signature_active_parameter_support(&self) -> bool {
+        self.signature_information()
+            .and_then(|information| information.active_parameter_support)
+            .unwrap_or(false)
+    }
+
+    pub fn signature_help_context_support(&self) -> bool {
+        self.text_document()
+            .and_then(|text_document| text_document.signature_help.as_ref())
+            .and_then(|signature_help| signature_help.context_support)
+            .unwrap_or(false)
+    }
+
+    pub fn document_symbol_kinds(&self) -> &'a [SymbolKind] {
+        self.document_symbol()
+            .and_then(|symbol| symbol.symbol_kind.as_ref())
+            .and_then(|kind| kind.value_set.as_deref())
+            .unwrap_or(DEFAULT_SYMBOL_KINDS)
+    }
+
+    /// Whether the client supports `DocumentSymbol` rather than only `SymbolInformation`.
+    pub fn hierarchical_document_symbols(&self) -> bool {
+        self.document_symbol()
+            .and_then(|symbol| symbol.hierarchical_document_symbol_support)
+            .unwrap_or(false)
+    }
+
+    pub fn document_symbol_tags(&self) -> &'a [SymbolTag] {
+        self.document_symbol()
+            .and_then(|symbol| symbol.tag_support.as_ref())
+            .map_or(&[], |tags| &tags.value_set)
+    }
+
+    fn link_support(goto: Option<&GotoCapability>) -> bool {
+        goto.and_then(|goto| goto.link_support).unwrap_or(false)
+    }
+
+    pub fn declaration_link_support(&self) -> bool {
+        Self::link_support(self.text_document().and_then(|t| t.declaration.as_ref()))
+    }
+
+    pub fn definition_link_support(&self) -> bool {
+        Self::link_support(self.text_document().and_then(|t| t.definition.as_ref()))
+    }
+
+    pub fn type_definition_link_support(&self) -> bool {
+        Self::link_support(
+            self.text_document()
+                .and_then(|t| t.type_definition.as_ref()),
+        )
+    }
+
+    pub fn implementation_link_support(&self) -> bool {
+        Self::link_support(self.text_document().and_then(|t| t.implementation.as_ref()))
+    }
+
+    /// The code action kinds the client supports in code action literals, or `None` if the client
+    /// only supports `Command`s.
+    pub fn code_action_kinds(&self) -> Option<&'a [String]> {
+        self.code_action()?
+            .code_action_literal_support
+            .as_ref()
+            .map(|literals| &literals.code_action_kind.value_set[..])
+    }
+
+    pub fn supports_code_action_literals(&self) -> bool {
+        self.code_action_kinds().is_some()
+    }
+
+    pub fn code_action_is_preferred_support(&self) -> bool {
+        self.code_action()
+            .and_then(|code_action| code_action.is_preferred_support)
+            .unwrap_or(false)
+    }
+
+    pub fn code_action_disabled_support(&self) -> bool {
+        self.code_action()
+            .and_then(|code_action| code_action.disabled_support)
+            .unwrap_or(false)
+    }
+
+    pub fn code_action_data_support(&self) -> bool {
+        self.code_action()
+            .and_then(|code_action| code_action.data_support)
+            .unwrap_or(false)
+    }
+
+    pub fn code_action_resolve_properties(&self) -> &'a [String] {
+        self.code_action()
+            .and_then(|code_action| code_action.resolve_support.as_ref())
+            .map_or(&[], |support| &support.properties)
+    }
+
+    pub fn code_action_honors_change_annotations(&self) -> bool {
+        self.code_action()
+            .and_then(|code_action| code_action.honors_change_annotations)
+            .unwrap_or(false)
+    }
+
+    pub fn document_link_tooltip_support(&self) -> bool {
+        self.text_document()
+            .and_then(|text_document| text_document.document_link.as_ref())
+            .and_then(|link| link.tooltip_support)
+            .unwrap_or(false)
+    }
+
+    pub fn rename_prepare_support(&self) -> bool {
+        self.rename()
+            .and_then(|rename| rename.prepare_support)
+            .unwrap_or(false)
+    }
+
+    pub fn rename_prepare_support_default_behavior(&self) -> Option<PrepareSupportDefaultBehavior> {
+        self.rename()?.prepare_support_default_behavior
+    }
+
+    pub fn rename_honors_change_annotations(&self) -> bool {
+        self.rename()
+            .and_then(|rename| rename.honors_change_annotations)
+            .unwrap_or(false)
+    }
+
+    pub fn diagnostic_related_information(&self) -> bool {
+        self.publish_diagnostics()
+            .and_then(|diagnostics| diagnostics.related_information)
+            .unwrap_or(false)
+    }
+
+    /// The diagnostic tags the client supports. Empty if the client does not support tags.
+    pub fn diagnostic_tag_value_set(&self) -> &'a [DiagnosticTag] {
+        self.publish_diagnostics()
+            .and_then(|diagnostics| diagnostics.tag_support.as_ref())
+            .map_or(&[], |tags| &tags.value_set)
+    }
+
+    pub fn diagnostic_version_support(&self) -> bool {
+        self.publish_diagnostics()
+            .and_then(|diagnostics| diagnostics.version_support)
+            .unwrap_or(false)
+    }
+
+    pub fn diagnostic_code_description_support(&self) -> bool {
+        self.publish_diagnostics()
+            .and_then(|diagnostics| diagnostics.code_description_support)
+            .unwrap_or(false)
+    }
+
+    pub fn diagnostic_data_support(&self) -> bool {
+        self.publish_diagnostics()
+            .and_then(|diagnostics| diagnostics.data_support)
+            .unwrap_or(false)
+    }
+
+    /// Whether the client supports related documents in pull diagnostic reports.
+    pub fn diagnostic_related_document_support(&self) -> bool {
+        self.text_document()
+            .and_then(|text_document| text_document.diagnostic.as_ref())
+            .and_then(|diagnostic| diagnostic.related_document_support)
+            .unwrap_or(false)
+    }
+
+    /// The maximum number of folding ranges the client wants per document, if limited.
+    pub fn folding_range_limit(&self) -> Option<u32> {
+        self.folding_range()?.range_limit
+    }
+
+    pub fn folding_range_line_folding_only(&self) -> bool {
+        self.folding_range()
+            .and_then(|folding| folding.line_folding_only)
+            .unwrap_or(false)
+    }
+
+    /// The folding range kinds the client supports. Clients that send them guarantee to handle
+    /// unknown kinds gracefully, so there is no default.
+    pub fn folding_range_kinds(&self) -> Option<&'a [FoldingRangeKind]> {
+        self.folding_range()?
+            .folding_range_kind
+            .as_ref()?
+            .value_set
+            .as_deref()
+    }
+
+    pub fn folding_range_collapsed_text(&self) -> bool {
+        self.folding_range()
+            .and_then(|folding| folding.folding_range.as_ref())
+            .and_then(|range| range.collapsed_text)
+            .unwrap_or(false)
+    }
+
+    pub fn semantic_token_types(&self) -> &'a [SemanticTokenType] {
+        self.semantic_tokens()
+            .map_or(&[], |tokens| &tokens.token_types)
+    }
+
+    pub fn semantic_token_modifiers(&self) -> &'a [SemanticTokenModifier] {
+        self.semantic_tokens()
+            .map_or(&[], |tokens| &tokens.token_modifiers)
+    }
+
+    pub fn semantic_token_formats(&self) -> &'a [TokenFormat] {
+        self.semantic_tokens().map_or(&[], |tokens| &tokens.formats)
+    }
+
+    pub fn supports_semantic_tokens_range(&self) -> bool {
+        self.semantic_tokens()
+            .and_then(|tokens| tokens.requests.range)
+            .unwrap_or(false)
+    }
+
+    pub fn supports_semantic_tokens_full(&self) -> bool {
+        match self
+            .semantic_tokens()
+            .and_then(|tokens| tokens.requests.full.as_ref())
+        {
+            Some(SemanticTokensFullOptions::Bool(full)) => *full,
+            Some(SemanticTokensFullOptions::Delta { .. }) => true,
+            None => false,
+        }
+    }
+
+    pub fn supports_semantic_tokens_delta(&self) -> bool {
+        match self
+            .semantic_tokens()
+            .and_then(|tokens| tokens.requests.full.as_ref())
+        {
+            Some(SemanticTokensFullOptions::Delta { delta }) => delta.unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    pub fn semantic_tokens_overlapping_support(&self) -> bool {
+        self.semantic_tokens()
+            .and_then(|tokens| tokens.overlapping_token_support)
+            .unwrap_or(false)
+    }
+
+    pub fn semantic_tokens_multiline_support(&self) -> bool {
+        self.semantic_tokens()
+            .and_then(|tokens| tokens.multiline_token_support)
+            .unwrap_or(false)
+    }
+
+    pub fn semantic_tokens_server_cancel_support(&self) -> bool {
+        self.semantic_tokens()
+            .and_then(|tokens| tokens.server_cancel_support)
+            .unwrap_or(false)
+    }
+
+    pub fn semantic_tokens_augments_syntax_tokens(&self) -> bool {
+        self.semantic_tokens()
+            .and_then(|tokens| tokens.augments_syntax_tokens)
+            .unwrap_or(false)
+    }
+
+    pub fn inlay_hint_resolve_properties(&self) -> &'a [String] {
+        self.text_document()
+            .and_then(|text_document| text_document.inlay_hint.as_ref())
+            .and_then(|inlay_hint| inlay_hint.resolve_support.as_ref())
+            .map_or(&[], |support| &support.properties)
+    }
+
+    // ------------------------------ notebook document ------------------------------
+
+    pub fn notebook_execution_summary_report(&self) -> bool {
+        self.capabilities
+            .notebook_document
+            .as_ref()
+            .and_then(|notebook| notebook.synchronization.execution_summary_report)
+            .unwrap_or(false)
+    }
+
+    // ------------------------------ dynamic registration ------------------------------
+
+    /// Whether the client supports dynamic registration of the request or notification `method`,
+    /// like `textDocument/hover`. Methods without a registration capability return `false`.
+    pub fn supports_dynamic_registration(&self, method: &str) -> bool {
+        let simple = |capability: Option<&DynamicRegistrationClientCapabilities>| {
+            capability.and_then(|capability| capability.dynamic_registration)
+        };
+        let workspace = self.workspace();
+        let text_document = self.text_document();
+        let notebook = self.capabilities.notebook_document.as_ref();
+        let registration = match method {
+            "workspace/didChangeConfiguration" => {
+                simple(workspace.and_then(|w| w.did_change_configuration.as_ref()))
+            }
+            "workspace/didChangeWatchedFiles" => workspace
+                .and_then(|w| w.did_change_watched_files.as_ref())
+                .and_then(|w| w.dynamic_registration),
+            "workspace/symbol" => self.workspace_symbol().and_then(|s| s.dynamic_registration),
+            "workspace/executeCommand" => {
+                simple(workspace.and_then(|w| w.execute_command.as_ref()))
+            }
+            "workspace/didCreateFiles"
+            | "workspace/willCreateFiles"
+            | "workspace/didRenameFiles"
+            | "workspace/willRenameFiles"
+            | "workspace/didDeleteFiles"
+            | "workspace/willDeleteFiles" => {
+                self.file_operations().and_then(|f| f.dynamic_registration)
+            }
+            "textDocument/didOpen"
+            | "textDocument/didChange"
+            | "textDocument/didClose"
+            | "textDocument/willSave"
+            | "textDocument/willSaveWaitUntil"
+            | "textDocument/didSave" => self.synchronization().and_then(|s| s.dynamic_registration),
+            "notebookDocument/sync" => {
+                notebook.and_then(|n| n.synchronization.dynamic_registration)
+            }
+            "textDocument/completion" => self.completion().and_then(|c| c.dynamic_registration),
+            "textDocument/hover" => text_document
+                .and_then(|t| t.hover.as_ref())
+                .and_then(|h| h.dynamic_registration),
+            "textDocument/signatureHelp" => text_document
+                .and_then(|t| t.signature_help.as_ref())
+                .and_then(|s| s.dynamic_registration),
+            "textDocument/declaration" => text_document
+                .and_then(|t| t.declaration.as_ref())
+                .and_then(|g| g.dynamic_registration),
+            "textDocument/definition" => text_document
+                .and_then(|t| t.definition.as_ref())
+                .and_then(|g| g.dynamic_registration),
+            "textDocument/typeDefinition" => text_document
+                .and_then(|t| t.type_definition.as_ref())
+                .and_then(|g| g.dynamic_registration),
+            "textDocument/implementation" => text_document
+                .and_then(|t| t.implementation.as_ref())
+                .and_then(|g| g.dynamic_registration),
+            "textDocument/references" => simple(text_document.and_then(|t| t.references.as_ref())),
+            "textDocument/documentHighlight" => {
+                simple(text_document.and_then(|t| t.document_highlight.as_ref()))
+            }
+            "textDocument/documentSymbol" => {
+                self.document_symbol().and_then(|s| s.dynamic_registration)
+            }
+            "textDocument/codeAction" => self.code_action().and_then(|c| c.dynamic_registration),
+            "textDocument/codeLens" => simple(text_document.and_then(|t| t.code_lens.as_ref())),
+            "textDocument/documentLink" => text_document
+                .and_then(|t| t.document_link.as_ref())
+                .and_then(|l| l.dynamic_registration),
+            "textDocument/documentColor" => {
+                simple(text_document.and_then(|t| t.color_provider.as_ref()))
+            }
+            "textDocument/formatting" => simple(text_document.and_then(|t| t.formatting.as_ref())),
+            "textDocument/rangeFormatting" => {
+                simple(text_document.and_then(|t| t.range_formatting.as_ref()))
+            }
+            "textDocument/onTypeFormatting" => {
+                simple(text_document.and_then(|t| t.on_type_formatting.as_ref()))
+            }
+            "textDocument/rename" => self.rename().and_then(|r| r.dynamic_registration),
+            "textDocument/foldingRange" => {
+                self.folding_range().and_then(|f| f.dynamic_registration)
+            }
+            "textDocument/selectionRange" => text_document
+                .and_then(|t| t.selection_range.as_ref())
+                .and_then(|s| s.dynamic_registration),
+            "textDocument/linkedEditingRange" => {
+                simple(text_document.and_then(|t| t.linked_editing_range.as_ref()))
+            }
+            "textDocument/prepareCallHierarchy" => {
+                simple(text_document.and_then(|t| t.call_hierarchy.as_ref()))
+            }
+            "textDocument/semanticTokens" => {
+                self.semantic_tokens().and_then(|s| s.dynamic_registration)
+            }
+            "textDocument/moniker" => simple(text_document.and_then(|t| t.moniker.as_ref())),
+            "textDocument/prepareTypeHierarchy" => {
+                simple(text_document.and_then(|t| t.type_hierarchy.as_ref()))
+            }
+            "textDocument/inlineValue" => {
+                simple(text_document.and_then(|t| t.inline_value.as_ref()))
+            }
+            "textDocument/inlayHint" => text_document
+                .and_then(|t| t.inlay_hint.as_ref())
+                .and_then(|i| i.dynamic_registration),
+            "textDocument/diagnostic" => text_document
+                .and_then(|t| t.diagnostic.as_ref())
+                .and_then(|d| d.dynamic_registration),
+            #[cfg(feature = "proposed")]
+            "textDocument/inlineCompletion" => text_document
+                .and_then(|t| t.inline_completion.as_ref())
+                .and_then(|i| i.dynamic_registration),
+            _ => None,
+        };
+        registration.unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CompletionItemCapability, HoverClientCapabilities, PublishDiagnosticsClientCapabilities,
+        TagSupport,
+    };
+
+    #[test]
+    fn test_defaults() {
+        let capabilities = ClientCapabilities::default();
+        let resolved = capabilities.resolved();
+        assert!(!resolved.supports_snippets());
+        assert!(!resolved.hierarchical_document_symbols());
+        assert!(!resolved.supports_work_done_progress());
+        assert_eq!(resolved.position_encodings(), [PositionEncodingKind::UTF16]);
+        assert!(resolved.diagnostic_tag_value_set().is_empty());
+        assert_eq!(resolved.document_symbol_kinds().len(), 18);
+        assert_eq!(
+            resolved.completion_item_kinds().last(),
+            Some(&CompletionItemKind::REFERENCE)
+        );
+        assert_eq!(resolved.hover_content_formats(), [MarkupKind::PlainText]);
+        assert!(!resolved.supports_dynamic_registration("textDocument/hover"));
+    }
+
+    #[test]
+    fn test_resolved_values() {
+        let capabilities = ClientCapabilities {
+            text_document: Some(TextDocumentClientCapabilities {
+                completion: Some(CompletionClientCapabilities {
+                    completion_item: Some(CompletionItemCapability {
+                        snippet_support: Some(true),
+                        ..CompletionItemCapability::default()
+                    }),
+                    ..CompletionClientCapabilities::default()
+                }),
+                hover: Some(HoverClientCapabilities {
+                    dynamic_registration: Some(true),
+                    content_format: Some(vec![MarkupKind::Markdown, MarkupKind::PlainText]),
+                }),
+                publish_diagnostics: Some(PublishDiagnosticsClientCapabilities {
+                    tag_support: Some(TagSupport {
+                        value_set: vec![DiagnosticTag::UNNECESSARY],
+                    }),
+                    ..PublishDiagnosticsClientCapabilities::default()
+                }),
+                ..TextDocumentClientCapabilities::default()
+            }),
+            general: Some(GeneralClientCapabilities {
+                position_encodings: Some(vec![PositionEncodingKind::UTF8]),
+                ..GeneralClientCapabilities::default()
+            }),
+            ..ClientCapabilities::default()
+        };
+        let resolved = capabilities.resolved();
+        assert!(resolved.supports_snippets());
+        assert_eq!(resolved.hover_content_formats()[0], MarkupKind::Markdown);
+        assert!(resolved.supports_dynamic_registration("textDocument/hover"));
+        assert_eq!(
+            resolved.diagnostic_tag_value_set(),
+            [DiagnosticTag::UNNECESSARY]
+        );
+        assert_eq!(resolved.position_encodings(), [PositionEncodingKind::UTF8]);
+        assert!(resolved.supports_position_encoding(&PositionEncodingKind::UTF16));
+        assert!(!resolved.supports_position_encoding(&PositionEncodingKind::UTF32));
+    }
+}
@@ -8,6 +8,8 @@ use serde_json::Value;
 
 use std::borrow::Cow;
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum CodeActionProviderCapability {
     Simple(bool),
@@ -27,6 +29,8 @@ impl From<bool> for CodeActionProviderCapability {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CodeActionClientCapabilities {
     ///
@@ -83,6 +87,8 @@ pub struct CodeActionClientCapabilities {
 ///
 /// @since 3.16.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CodeActionCapabilityResolveSupport {
     /// The properties that a client can resolve lazily.
@@ -90,6 +96,8 @@ pub struct CodeActionCapabilityResolveSupport {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CodeActionLiteralSupport {
     /// The code action kind is support with the following value set.
@@ -97,6 +105,8 @@ pub struct CodeActionLiteralSupport {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CodeActionKindLiteralSupport {
     /// The code action kind values the client supports. When this
@@ -108,6 +118,8 @@ pub struct CodeActionKindLiteralSupport {
 
 /// Params for the CodeActionRequest
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CodeActionParams {
     /// The document in which the command was invoked.
@@ -130,7 +142,10 @@ pub struct CodeActionParams {
 pub type CodeActionResponse = Vec<CodeActionOrCommand>;
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum CodeActionOrCommand {
     Command(Command),
     CodeAction(CodeAction),
@@ -149,7 +164,12 @@ impl From<CodeAction> for CodeActionOrCommand {
 }
 
 #[derive(Debug, Eq, PartialEq, Hash, PartialOrd, Clone, Deserialize, Serialize)]
-pub struct CodeActionKind(Cow<'static, str>);
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CodeActionKind(
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::cow_str))]
+    Cow<'static, str>,
+);
 
 impl CodeActionKind {
     /// Empty kind.
@@ -234,6 +254,8 @@ impl From<&'static str> for CodeActionKind {
 }
 
 #[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CodeAction {
     /// A short, human-readable, title for this code action.
@@ -291,10 +313,13 @@ pub struct CodeAction {
     ///
     /// @since 3.16.0
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::option_value))]
     pub data: Option<Value>,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CodeActionDisabled {
     /// Human readable description of why the code action is currently disabled.
@@ -307,6 +332,7 @@ pub struct CodeActionDisabled {
 ///
 /// @since 3.17.0
 #[derive(Eq, PartialEq, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(transparent)]
 pub struct CodeActionTriggerKind(i32);
 lsp_enum! {
@@ -325,6 +351,8 @@ impl CodeActionTriggerKind {
 /// Contains additional diagnostic information about the context in which
 /// a code action is run.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CodeActionContext {
     /// An array of diagnostics.
@@ -345,6 +373,8 @@ pub struct CodeActionContext {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CodeActionOptions {
     /// CodeActionKinds that this server may return.
@@ -7,10 +7,14 @@ use serde::{Deserialize, Serialize};
 pub type DocumentColorClientCapabilities = DynamicRegistrationClientCapabilities;
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ColorProviderOptions {}
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct StaticTextDocumentColorProviderOptions {
     /// A document selector to identify the scope of the registration. If set to null
@@ -21,7 +25,9 @@ pub struct StaticTextDocumentColorProviderOptions {
     pub id: Option<String>,
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum ColorProviderCapability {
     Simple(bool),
@@ -29,6 +35,18 @@ pub enum ColorProviderCapability {
     Options(StaticTextDocumentColorProviderOptions),
 }
 
+impl<'de> Deserialize<'de> for ColorProviderCapability {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::deserialize_registration_options(deserializer, Self::Options, |value| match value {
+            serde_json::Value::Bool(simple) => Ok(Self::Simple(simple)),
+            value => ColorProviderOptions::deserialize(value).map(Self::ColorProvider),
+        })
+    }
+}
+
 impl From<ColorProviderOptions> for ColorProviderCapability {
     fn from(from: ColorProviderOptions) -> Self {
         Self::ColorProvider(from)
@@ -48,6 +66,8 @@ impl From<bool> for ColorProviderCapability {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DocumentColorParams {
     /// The text document
@@ -61,6 +81,8 @@ pub struct DocumentColorParams {
 }
 
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ColorInformation {
     /// The range in the document where this color appears.
@@ -70,19 +92,27 @@ pub struct ColorInformation {
 }
 
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Copy)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Color {
     /// The red component of this color in the range [0-1].
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::color_channel))]
     pub red: f32,
     /// The green component of this color in the range [0-1].
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::color_channel))]
     pub green: f32,
     /// The blue component of this color in the range [0-1].
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::color_channel))]
     pub blue: f32,
     /// The alpha component of this color in the range [0-1].
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::color_channel))]
     pub alpha: f32,
 }
 
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ColorPresentationParams {
     /// The text document.
@@ -102,6 +132,8 @@ pub struct ColorPresentationParams {
 }
 
 #[derive(Debug, PartialEq, Eq, Deserialize, Serialize, Default, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ColorPresentation {
     /// The label of this color presentation. It will be shown on the color
@@ -120,3 +152,24 @@ pub struct ColorPresentation {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub additional_text_edits: Option<Vec<TextEdit>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::test_deserialization, DocumentFilter};
+
+    #[test]
+    fn test_registration_options_deserialization() {
+        test_deserialization(
+            r#"{"documentSelector":[{"language":"rust"}],"id":"x"}"#,
+            &ColorProviderCapability::Options(StaticTextDocumentColorProviderOptions {
+                document_selector: Some(vec![DocumentFilter {
+                    language: Some("rust".to_string()),
+                    scheme: None,
+                    pattern: None,
+                }]),
+                id: Some("x".to_string()),
+            }),
+        );
+    }
+}
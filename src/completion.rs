@@ -12,6 +12,7 @@ use std::fmt::Debug;
 
 /// Defines how to interpret the insert text in a completion item
 #[derive(Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(transparent)]
 pub struct InsertTextFormat(i32);
 lsp_enum! {
@@ -23,6 +24,7 @@ impl InsertTextFormat {
 
 /// The kind of a completion entry.
 #[derive(Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(transparent)]
 pub struct CompletionItemKind(i32);
 lsp_enum! {
@@ -56,6 +58,8 @@ impl CompletionItemKind {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionItemCapability {
     /// Client supports snippets as insert text.
@@ -127,6 +131,8 @@ pub struct CompletionItemCapability {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionItemCapabilityResolveSupport {
     /// The properties that a client can resolve lazily.
@@ -134,6 +140,8 @@ pub struct CompletionItemCapabilityResolveSupport {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct InsertTextModeSupport {
     pub value_set: Vec<InsertTextMode>,
@@ -144,6 +152,7 @@ pub struct InsertTextModeSupport {
 ///
 /// @since 3.16.0
 #[derive(Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(transparent)]
 pub struct InsertTextMode(i32);
 lsp_enum! {
@@ -167,6 +176,7 @@ impl InsertTextMode {
 }
 
 #[derive(Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(transparent)]
 pub struct CompletionItemTag(i32);
 lsp_enum! {
@@ -176,6 +186,8 @@ impl CompletionItemTag {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionItemKindCapability {
     /// The completion item kind values the client supports. When this
@@ -191,6 +203,8 @@ pub struct CompletionItemKindCapability {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionListCapability {
     /// The client supports the following itemDefaults on
@@ -206,6 +220,8 @@ pub struct CompletionListCapability {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionClientCapabilities {
     /// Whether completion supports dynamic registration.
@@ -244,6 +260,8 @@ pub struct CompletionClientCapabilities {
 ///
 /// @since 3.16.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct InsertReplaceEdit {
     /// The string to be inserted.
@@ -257,6 +275,8 @@ pub struct InsertReplaceEdit {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum CompletionTextEdit {
     Edit(TextEdit),
@@ -277,6 +297,8 @@ impl From<InsertReplaceEdit> for CompletionTextEdit {
 
 /// Completion options.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionOptions {
     /// The server provides support to resolve additional information for a completion item.
@@ -320,6 +342,8 @@ pub struct CompletionOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionOptionsCompletionItem {
     /// The server has support for completion item label
@@ -332,6 +356,8 @@ pub struct CompletionOptionsCompletionItem {
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct CompletionRegistrationOptions {
     #[serde(flatten)]
     pub text_document_registration_options: TextDocumentRegistrationOptions,
@@ -341,6 +367,8 @@ pub struct CompletionRegistrationOptions {
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum CompletionResponse {
     Array(Vec<CompletionItem>),
@@ -360,6 +388,8 @@ impl From<CompletionList> for CompletionResponse {
 }
 
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionParams {
     // This field was "mixed-in" from TextDocumentPositionParams
@@ -378,6 +408,8 @@ pub struct CompletionParams {
 }
 
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionContext {
     /// How the completion was triggered.
@@ -391,6 +423,7 @@ pub struct CompletionContext {
 
 /// How a completion was triggered.
 #[derive(Eq, PartialEq, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(transparent)]
 pub struct CompletionTriggerKind(i32);
 lsp_enum! {
@@ -404,6 +437,8 @@ impl CompletionTriggerKind {
 /// Represents a collection of [completion items](#CompletionItem) to be presented
 /// in the editor.
 #[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionList {
     /// This list it not complete. Further typing should result in recomputing
@@ -415,6 +450,8 @@ pub struct CompletionList {
 }
 
 #[derive(Debug, PartialEq, Default, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionItem {
     /// The label of this completion item. By default
@@ -534,6 +571,7 @@ pub struct CompletionItem {
     /// An data entry field that is preserved on a completion item between
     /// a completion and a completion resolve request.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::option_value))]
     pub data: Option<Value>,
 
     /// Tags for this completion item.
@@ -556,6 +594,8 @@ impl CompletionItem {
 ///
 /// @since 3.17.0
 #[derive(Debug, PartialEq, Default, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionItemLabelDetails {
     /// An optional string which is rendered less prominently directly after
@@ -577,6 +617,7 @@ mod tests {
     use crate::tests::test_deserialization;
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_tag_support_deserialization() {
         let mut empty = CompletionItemCapability::default();
         empty.tag_support = None;
@@ -11,6 +11,8 @@ use crate::{
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DiagnosticClientCapabilities {
     /// Whether implementation supports dynamic registration.
@@ -29,6 +31,8 @@ pub struct DiagnosticClientCapabilities {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DiagnosticOptions {
     /// An optional identifier under which the diagnostics are
@@ -52,6 +56,8 @@ pub struct DiagnosticOptions {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DiagnosticRegistrationOptions {
     #[serde(flatten)]
@@ -64,17 +70,32 @@ pub struct DiagnosticRegistrationOptions {
     pub static_registration_options: StaticRegistrationOptions,
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum DiagnosticServerCapabilities {
     Options(DiagnosticOptions),
     RegistrationOptions(DiagnosticRegistrationOptions),
 }
 
+impl<'de> Deserialize<'de> for DiagnosticServerCapabilities {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::deserialize_registration_options(deserializer, Self::RegistrationOptions, |value| {
+            DiagnosticOptions::deserialize(value).map(Self::Options)
+        })
+    }
+}
+
 /// Parameters of the document diagnostic request.
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DocumentDiagnosticParams {
     /// The text document.
@@ -97,6 +118,8 @@ pub struct DocumentDiagnosticParams {
 ///
 /// @since 3.17.0
 #[derive(Debug, PartialEq, Default, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct FullDocumentDiagnosticReport {
     /// An optional result ID. If provided it will be sent on the next diagnostic request for the
@@ -114,6 +137,8 @@ pub struct FullDocumentDiagnosticReport {
 ///
 /// @since 3.17.0
 #[derive(Debug, PartialEq, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct UnchangedDocumentDiagnosticReport {
     /// A result ID which will be sent on the next diagnostic request for the same document.
@@ -124,6 +149,8 @@ pub struct UnchangedDocumentDiagnosticReport {
 ///
 /// @since 3.17.0
 #[derive(Debug, PartialEq, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(tag = "kind", rename_all = "lowercase")]
 pub enum DocumentDiagnosticReportKind {
     /// A diagnostic report with a full set of problems.
@@ -148,6 +175,8 @@ impl From<UnchangedDocumentDiagnosticReport> for DocumentDiagnosticReportKind {
 ///
 /// @since 3.17.0
 #[derive(Debug, PartialEq, Default, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct RelatedFullDocumentDiagnosticReport {
     /// Diagnostics of related documents.
@@ -169,6 +198,8 @@ pub struct RelatedFullDocumentDiagnosticReport {
 ///
 /// @since 3.17.0
 #[derive(Debug, PartialEq, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct RelatedUnchangedDocumentDiagnosticReport {
     /// Diagnostics of related documents.
@@ -194,6 +225,8 @@ pub struct RelatedUnchangedDocumentDiagnosticReport {
 ///
 /// @since 3.17.0
 #[derive(Debug, PartialEq, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(tag = "kind", rename_all = "lowercase")]
 pub enum DocumentDiagnosticReport {
     /// A diagnostic report with a full set of problems.
@@ -218,6 +251,8 @@ impl From<RelatedUnchangedDocumentDiagnosticReport> for DocumentDiagnosticReport
 ///
 /// @since 3.17.0
 #[derive(Debug, PartialEq, Default, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DocumentDiagnosticReportPartialResult {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -227,6 +262,8 @@ pub struct DocumentDiagnosticReportPartialResult {
 }
 
 #[derive(Debug, PartialEq, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum DocumentDiagnosticReportResult {
     Report(DocumentDiagnosticReport),
@@ -251,6 +288,8 @@ impl From<DocumentDiagnosticReportPartialResult> for DocumentDiagnosticReportRes
 ///
 /// @since 3.17.0
 #[derive(Debug, PartialEq, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DiagnosticServerCancellationData {
     pub retrigger_request: bool,
@@ -263,3 +302,32 @@ impl Default for DiagnosticServerCancellationData {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::test_deserialization, DocumentFilter};
+
+    #[test]
+    fn test_registration_options_deserialization() {
+        test_deserialization(
+            r#"{"documentSelector":[{"language":"rust"}],"interFileDependencies":true,"workspaceDiagnostics":false,"id":"x"}"#,
+            &DiagnosticServerCapabilities::RegistrationOptions(DiagnosticRegistrationOptions {
+                text_document_registration_options: TextDocumentRegistrationOptions {
+                    document_selector: Some(vec![DocumentFilter {
+                        language: Some("rust".to_string()),
+                        scheme: None,
+                        pattern: None,
+                    }]),
+                },
+                diagnostic_options: DiagnosticOptions {
+                    inter_file_dependencies: true,
+                    ..DiagnosticOptions::default()
+                },
+                static_registration_options: StaticRegistrationOptions {
+                    id: Some("x".to_string()),
+                },
+            }),
+        );
+    }
+}
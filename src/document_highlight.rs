@@ -8,6 +8,8 @@ use crate::{
 pub type DocumentHighlightClientCapabilities = DynamicRegistrationClientCapabilities;
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DocumentHighlightParams {
     #[serde(flatten)]
@@ -24,6 +26,8 @@ pub struct DocumentHighlightParams {
 /// special attention. Usually a document highlight is visualized by changing
 /// the background color of its range.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DocumentHighlight {
     /// The range this highlight applies to.
     pub range: Range,
@@ -35,6 +39,7 @@ pub struct DocumentHighlight {
 
 /// A document highlight kind.
 #[derive(Eq, PartialEq, Copy, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(transparent)]
 pub struct DocumentHighlightKind(i32);
 lsp_enum! {
@@ -0,0 +1,358 @@
+/*!
+
+Evaluating `DocumentSelector`s and notebook filters against documents.
+
+Scores follow VS Code's `languages.match`: each property of a filter that is set must match, an
+exact match scores `10` and a `*` wildcard (or a glob `pattern`) scores `5`. The score of a filter
+is the highest score of its properties, and `0` means the filter does not match. A selector scores
+the highest score of its filters.
+
+*/
+
+use crate::{
+    glob::GlobPatternMatcher, DocumentFilter, DocumentSelector, GlobPattern, Notebook,
+    NotebookCellTextDocumentFilter, NotebookDocumentFilter, NotebookSelector, Uri,
+};
+
+const EXACT_MATCH: u32 = 10;
+const WILDCARD_MATCH: u32 = 5;
+
+/// Scores a `language`, `scheme` or `notebookType` property against `value`.
+fn score_name(expected: &str, value: &str) -> Option<u32> {
+    if expected == value {
+        Some(EXACT_MATCH)
+    } else if expected == "*" {
+        Some(WILDCARD_MATCH)
+    } else {
+        None
+    }
+}
+
+fn score_scheme(expected: &str, uri: &Uri) -> Option<u32> {
+    score_name(expected, uri.scheme().map_or("", |scheme| scheme.as_str()))
+}
+
+/// Compiles a glob `pattern` property. Invalid patterns compile to `None` and never match.
+fn compile_pattern(pattern: &str) -> Option<GlobPatternMatcher> {
+    GlobPatternMatcher::new(&GlobPattern::String(pattern.to_string())).ok()
+}
+
+/// Scores a glob `pattern`, compiled to `matcher`, against the path of `uri`.
+fn score_pattern(pattern: &str, matcher: Option<&GlobPatternMatcher>, uri: &Uri) -> Option<u32> {
+    let path = uri.path().as_estr().decode().into_string_lossy();
+    if pattern == path {
+        return Some(EXACT_MATCH);
+    }
+    match matcher {
+        Some(matcher) if matcher.is_match(uri) => Some(WILDCARD_MATCH),
+        _ => None,
+    }
+}
+
+/// Combines the scores of the properties of a filter. Unset properties are skipped, a property
+/// that does not match makes the whole filter fail.
+fn combine(scores: impl IntoIterator<Item = Option<Option<u32>>>) -> u32 {
+    let mut result = 0;
+    for score in scores.into_iter().flatten() {
+        match score {
+            Some(score) => result = result.max(score),
+            None => return 0,
+        }
+    }
+    result
+}
+
+impl DocumentFilter {
+    /// How well this filter matches a document, `0` if it does not match. The `pattern` is
+    /// compiled on every call, see [`DocumentSelectorMatcher`] to compile it once.
+    pub fn score(&self, uri: &Uri, language_id: &str) -> u32 {
+        let matcher = self.pattern.as_deref().and_then(compile_pattern);
+        self.score_compiled(matcher.as_ref(), uri, language_id)
+    }
+
+    /// Scores this filter with its `pattern` compiled to `matcher`.
+    fn score_compiled(
+        &self,
+        matcher: Option<&GlobPatternMatcher>,
+        uri: &Uri,
+        language_id: &str,
+    ) -> u32 {
+        combine([
+            self.language
+                .as_deref()
+                .map(|language| score_name(language, language_id)),
+            self.scheme
+                .as_deref()
+                .map(|scheme| score_scheme(scheme, uri)),
+            self.pattern
+                .as_deref()
+                .map(|pattern| score_pattern(pattern, matcher, uri)),
+        ])
+    }
+
+    pub fn matches(&self, uri: &Uri, language_id: &str) -> bool {
+        self.score(uri, language_id) > 0
+    }
+}
+
+/// Matching for `DocumentSelector`, which is a plain `Vec<DocumentFilter>`. The patterns of the
+/// filters are compiled on every call, see [`DocumentSelectorMatcher`] to match many documents.
+pub trait DocumentSelectorExt {
+    /// The highest score of the filters of this selector, `0` if none of them matches.
+    fn score(&self, uri: &Uri, language_id: &str) -> u32;
+
+    fn matches(&self, uri: &Uri, language_id: &str) -> bool {
+        self.score(uri, language_id) > 0
+    }
+}
+
+impl DocumentSelectorExt for DocumentSelector {
+    fn score(&self, uri: &Uri, language_id: &str) -> u32 {
+        self.iter()
+            .map(|filter| filter.score(uri, language_id))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// A `DocumentSelector` with the glob patterns of its filters compiled once.
+#[derive(Debug, Clone)]
+pub struct DocumentSelectorMatcher {
+    selector: DocumentSelector,
+    /// The compiled `pattern` of each filter, `None` if it is unset or invalid.
+    patterns: Vec<Option<GlobPatternMatcher>>,
+}
+
+impl DocumentSelectorMatcher {
+    pub fn new(selector: DocumentSelector) -> Self {
+        let patterns = selector
+            .iter()
+            .map(|filter| filter.pattern.as_deref().and_then(compile_pattern))
+            .collect();
+        DocumentSelectorMatcher { selector, patterns }
+    }
+
+    pub fn selector(&self) -> &DocumentSelector {
+        &self.selector
+    }
+
+    /// The highest score of the filters of the selector, `0` if none of them matches.
+    pub fn score(&self, uri: &Uri, language_id: &str) -> u32 {
+        self.selector
+            .iter()
+            .zip(&self.patterns)
+            .map(|(filter, matcher)| filter.score_compiled(matcher.as_ref(), uri, language_id))
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn matches(&self, uri: &Uri, language_id: &str) -> bool {
+        self.score(uri, language_id) > 0
+    }
+}
+
+impl PartialEq for DocumentSelectorMatcher {
+    fn eq(&self, other: &Self) -> bool {
+        self.selector == other.selector
+    }
+}
+
+impl From<DocumentSelector> for DocumentSelectorMatcher {
+    fn from(selector: DocumentSelector) -> Self {
+        DocumentSelectorMatcher::new(selector)
+    }
+}
+
+impl NotebookDocumentFilter {
+    /// The `(notebook_type, scheme, pattern)` properties of this filter.
+    fn properties(&self) -> (Option<&str>, Option<&str>, Option<&str>) {
+        match self {
+            NotebookDocumentFilter::ByType {
+                notebook_type,
+                scheme,
+                pattern,
+            } => (Some(notebook_type), scheme.as_deref(), pattern.as_deref()),
+            NotebookDocumentFilter::ByScheme {
+                notebook_type,
+                scheme,
+                pattern,
+            } => (notebook_type.as_deref(), Some(scheme), pattern.as_deref()),
+            NotebookDocumentFilter::ByPattern {
+                notebook_type,
+                scheme,
+                pattern,
+            } => (notebook_type.as_deref(), scheme.as_deref(), Some(pattern)),
+        }
+    }
+
+    /// How well this filter matches the notebook at `uri`, `0` if it does not match.
+    pub fn score(&self, uri: &Uri, notebook_type: &str) -> u32 {
+        let (expected_type, scheme, pattern) = self.properties();
+        combine([
+            expected_type.map(|expected| score_name(expected, notebook_type)),
+            scheme.map(|scheme| score_scheme(scheme, uri)),
+            pattern.map(|pattern| score_pattern(pattern, compile_pattern(pattern).as_ref(), uri)),
+        ])
+    }
+
+    pub fn matches(&self, uri: &Uri, notebook_type: &str) -> bool {
+        self.score(uri, notebook_type) > 0
+    }
+}
+
+impl Notebook {
+    /// How well the notebook at `uri` matches, `0` if it does not match. A string matches against
+    /// the notebook type.
+    pub fn score(&self, uri: &Uri, notebook_type: &str) -> u32 {
+        match self {
+            Notebook::String(expected) => score_name(expected, notebook_type).unwrap_or(0),
+            Notebook::NotebookDocumentFilter(filter) => filter.score(uri, notebook_type),
+        }
+    }
+
+    pub fn matches(&self, uri: &Uri, notebook_type: &str) -> bool {
+        self.score(uri, notebook_type) > 0
+    }
+}
+
+impl NotebookCellTextDocumentFilter {
+    /// How well a cell with `language_id` in the notebook at `notebook_uri` matches, `0` if it
+    /// does not match.
+    pub fn score(&self, notebook_uri: &Uri, notebook_type: &str, language_id: &str) -> u32 {
+        let notebook = self.notebook.score(notebook_uri, notebook_type);
+        if notebook == 0 {
+            return 0;
+        }
+        match &self.language {
+            Some(language) => score_name(language, language_id).map_or(0, |s| s.max(notebook)),
+            None => notebook,
+        }
+    }
+
+    pub fn matches(&self, notebook_uri: &Uri, notebook_type: &str, language_id: &str) -> bool {
+        self.score(notebook_uri, notebook_type, language_id) > 0
+    }
+}
+
+impl NotebookSelector {
+    /// Whether the notebook at `uri` is selected. Selectors without a notebook filter select every
+    /// notebook, subject to their cell languages.
+    pub fn matches_notebook(&self, uri: &Uri, notebook_type: &str) -> bool {
+        match self {
+            NotebookSelector::ByNotebook { notebook, .. }
+            | NotebookSelector::ByCells {
+                notebook: Some(notebook),
+                ..
+            } => notebook.matches(uri, notebook_type),
+            NotebookSelector::ByCells { notebook: None, .. } => true,
+        }
+    }
+
+    /// Whether cells with `language_id` of a selected notebook are synced.
+    pub fn matches_cell_language(&self, language_id: &str) -> bool {
+        let cells = match self {
+            NotebookSelector::ByNotebook { cells: None, .. } => return true,
+            NotebookSelector::ByNotebook {
+                cells: Some(cells), ..
+            }
+            | NotebookSelector::ByCells { cells, .. } => cells,
+        };
+        cells
+            .iter()
+            .any(|cell| score_name(&cell.language, language_id).is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uri(s: &str) -> Uri {
+        s.parse().unwrap()
+    }
+
+    fn filter(
+        language: Option<&str>,
+        scheme: Option<&str>,
+        pattern: Option<&str>,
+    ) -> DocumentFilter {
+        DocumentFilter {
+            language: language.map(String::from),
+            scheme: scheme.map(String::from),
+            pattern: pattern.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_document_filter_score() {
+        let package = uri("file:///project/package.json");
+        let json = filter(Some("json"), None, None);
+        assert_eq!(json.score(&package, "json"), 10);
+        assert_eq!(json.score(&package, "jsonc"), 0);
+        assert_eq!(filter(Some("*"), None, None).score(&package, "json"), 5);
+        assert_eq!(
+            filter(Some("*"), Some("file"), None).score(&package, "json"),
+            10
+        );
+        assert_eq!(
+            filter(Some("json"), Some("untitled"), None).score(&package, "json"),
+            0
+        );
+        assert_eq!(
+            filter(None, None, Some("**/package.json")).score(&package, "json"),
+            5
+        );
+        assert_eq!(
+            filter(None, None, Some("/project/package.json")).score(&package, "json"),
+            10
+        );
+        assert_eq!(filter(None, None, None).score(&package, "json"), 0);
+
+        let selector: DocumentSelector = vec![
+            filter(Some("typescript"), None, None),
+            filter(None, None, Some("**/*.json")),
+        ];
+        assert_eq!(selector.score(&package, "json"), 5);
+        assert!(selector.matches(&uri("untitled:a.ts"), "typescript"));
+        assert!(!selector.matches(&uri("file:///a.rs"), "rust"));
+
+        let matcher = DocumentSelectorMatcher::new(selector.clone());
+        assert_eq!(matcher.score(&package, "json"), 5);
+        assert!(matcher.matches(&uri("untitled:a.ts"), "typescript"));
+        assert!(!matcher.matches(&uri("file:///a.rs"), "rust"));
+        assert_eq!(matcher.selector(), &selector);
+
+        let invalid = DocumentSelectorMatcher::new(vec![filter(None, None, Some("/[a"))]);
+        assert!(!invalid.matches(&uri("file:///a"), "plaintext"));
+        assert!(invalid.matches(&uri("file:///%5Ba"), "plaintext"));
+    }
+
+    #[test]
+    fn test_notebook_filters() {
+        let notebook_uri = uri("file:///a/b.ipynb");
+        let by_pattern = NotebookDocumentFilter::ByPattern {
+            notebook_type: Some("jupyter-notebook".into()),
+            scheme: None,
+            pattern: "**/*.ipynb".into(),
+        };
+        assert_eq!(by_pattern.score(&notebook_uri, "jupyter-notebook"), 10);
+        assert!(!by_pattern.matches(&notebook_uri, "interactive"));
+
+        let cell_filter = NotebookCellTextDocumentFilter {
+            notebook: Notebook::String("*".into()),
+            language: Some("python".into()),
+        };
+        assert_eq!(
+            cell_filter.score(&notebook_uri, "jupyter-notebook", "python"),
+            10
+        );
+        assert!(!cell_filter.matches(&notebook_uri, "jupyter-notebook", "markdown"));
+
+        let selector = NotebookSelector::ByNotebook {
+            notebook: Notebook::NotebookDocumentFilter(by_pattern),
+            cells: None,
+        };
+        assert!(selector.matches_notebook(&notebook_uri, "jupyter-notebook"));
+        assert!(selector.matches_cell_language("markdown"));
+    }
+}
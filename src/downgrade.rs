@@ -0,0 +1,473 @@
+/*!
+
+Rewriting responses into the subset of the protocol a client supports.
+
+Servers can compute their responses in the richest form, with location links, hierarchical
+symbols, insert/replace edits, snippets and tags, and let a [`ResponseDowngrader`] rewrite them
+according to the client's capabilities before sending them.
+
+*/
+
+use crate::{
+    client_capabilities::ResolvedClientCapabilities, snippet::Snippet, ClientCapabilities,
+    CodeActionOrCommand, CompletionItem, CompletionItemTag, CompletionResponse, CompletionTextEdit,
+    Diagnostic, DocumentSymbol, DocumentSymbolResponse, GotoDefinitionResponse, InsertTextFormat,
+    Location, PublishDiagnosticsParams, SymbolInformation, SymbolKind, SymbolTag, TextEdit, Uri,
+    WorkspaceSymbolResponse,
+};
+
+/// Rewrites responses according to a client's `ClientCapabilities`.
+#[derive(Debug, Clone, Copy)]
+pub struct ResponseDowngrader<'a> {
+    capabilities: ResolvedClientCapabilities<'a>,
+}
+
+impl<'a> ResponseDowngrader<'a> {
+    pub fn new(capabilities: &'a ClientCapabilities) -> Self {
+        ResponseDowngrader {
+            capabilities: capabilities.resolved(),
+        }
+    }
+
+    fn goto(response: GotoDefinitionResponse, link_support: bool) -> GotoDefinitionResponse {
+        match response {
+            GotoDefinitionResponse::Link(links) if !link_support => GotoDefinitionResponse::Array(
+                links
+                    .into_iter()
+                    .map(|link| Location::new(link.target_uri, link.target_selection_range))
+                    .collect(),
+            ),
+            response => response,
+        }
+    }
+
+    /// Turns location links into locations of their `target_selection_range` if the client does
+    /// not support links for `textDocument/definition`.
+    pub fn goto_definition(&self, response: GotoDefinitionResponse) -> GotoDefinitionResponse {
+        Self::goto(response, self.capabilities.definition_link_support())
+    }
+
+    /// Like [`Self::goto_definition`], for `textDocument/declaration`.
+    pub fn goto_declaration(&self, response: GotoDefinitionResponse) -> GotoDefinitionResponse {
+        Self::goto(response, self.capabilities.declaration_link_support())
+    }
+
+    /// Like [`Self::goto_definition`], for `textDocument/typeDefinition`.
+    pub fn goto_type_definition(&self, response: GotoDefinitionResponse) -> GotoDefinitionResponse {
+        Self::goto(response, self.capabilities.type_definition_link_support())
+    }
+
+    /// Like [`Self::goto_definition`], for `textDocument/implementation`.
+    pub fn goto_implementation(&self, response: GotoDefinitionResponse) -> GotoDefinitionResponse {
+        Self::goto(response, self.capabilities.implementation_link_support())
+    }
+
+    /// Maps `kind` to one the client supports, falling back from kinds added in later versions of
+    /// the protocol to similar older ones.
+    fn symbol_kind(kind: SymbolKind, supported: &[SymbolKind]) -> SymbolKind {
+        if supported.contains(&kind) {
+            return kind;
+        }
+        match kind {
+            SymbolKind::OBJECT | SymbolKind::NULL | SymbolKind::TYPE_PARAMETER => {
+                SymbolKind::VARIABLE
+            }
+            SymbolKind::KEY => SymbolKind::PROPERTY,
+            SymbolKind::ENUM_MEMBER => SymbolKind::CONSTANT,
+            SymbolKind::STRUCT => SymbolKind::CLASS,
+            SymbolKind::EVENT => SymbolKind::FIELD,
+            SymbolKind::OPERATOR => SymbolKind::FUNCTION,
+            kind => kind,
+        }
+    }
+
+    /// Keeps the `tags` in `supported`, and drops them entirely if none remain.
+    fn tags<T: PartialEq>(tags: Option<Vec<T>>, supported: &[T]) -> Option<Vec<T>> {
+        let mut tags = tags?;
+        tags.retain(|tag| supported.contains(tag));
+        if tags.is_empty() {
+            None
+        } else {
+            Some(tags)
+        }
+    }
+
+    #[allow(deprecated)]
+    fn flatten_document_symbol(
+        &self,
+        uri: &Uri,
+        symbol: DocumentSymbol,
+        container_name: Option<String>,
+        flat: &mut Vec<SymbolInformation>,
+    ) {
+        flat.push(SymbolInformation {
+            name: symbol.name.clone(),
+            kind: Self::symbol_kind(symbol.kind, self.capabilities.document_symbol_kinds()),
+            tags: Self::tags(symbol.tags, self.capabilities.document_symbol_tags()),
+            deprecated: symbol.deprecated,
+            location: Location::new(uri.clone(), symbol.range),
+            container_name,
+        });
+        for child in symbol.children.into_iter().flatten() {
+            self.flatten_document_symbol(uri, child, Some(symbol.name.clone()), flat);
+        }
+    }
+
+    fn document_symbol(&self, mut symbol: DocumentSymbol) -> DocumentSymbol {
+        symbol.kind = Self::symbol_kind(symbol.kind, self.capabilities.document_symbol_kinds());
+        symbol.tags = Self::tags(symbol.tags, self.capabilities.document_symbol_tags());
+        symbol.children = symbol.children.map(|children| {
+            children
+                .into_iter()
+                .map(|child| self.document_symbol(child))
+                .collect()
+        });
+        symbol
+    }
+
+    /// Flattens `DocumentSymbol`s of the document at `uri` into `SymbolInformation` if the client
+    /// does not support hierarchical symbols, and maps symbol kinds and tags to supported ones.
+    pub fn document_symbols(
+        &self,
+        uri: &Uri,
+        response: DocumentSymbolResponse,
+    ) -> DocumentSymbolResponse {
+        match response {
+            DocumentSymbolResponse::Nested(symbols)
+                if !self.capabilities.hierarchical_document_symbols() =>
+            {
+                let mut flat = Vec::new();
+                for symbol in symbols {
+                    self.flatten_document_symbol(uri, symbol, None, &mut flat);
+                }
+                DocumentSymbolResponse::Flat(flat)
+            }
+            DocumentSymbolResponse::Nested(symbols) => DocumentSymbolResponse::Nested(
+                symbols
+                    .into_iter()
+                    .map(|symbol| self.document_symbol(symbol))
+                    .collect(),
+            ),
+            DocumentSymbolResponse::Flat(symbols) => DocumentSymbolResponse::Flat(
+                symbols
+                    .into_iter()
+                    .map(|mut symbol| {
+                        symbol.kind = Self::symbol_kind(
+                            symbol.kind,
+                            self.capabilities.document_symbol_kinds(),
+                        );
+                        symbol.tags =
+                            Self::tags(symbol.tags, self.capabilities.document_symbol_tags());
+                        symbol
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Maps symbol kinds and tags of a `workspace/symbol` response to supported ones.
+    pub fn workspace_symbols(&self, response: WorkspaceSymbolResponse) -> WorkspaceSymbolResponse {
+        let kinds = self.capabilities.workspace_symbol_kinds();
+        let tags: &[SymbolTag] = self.capabilities.workspace_symbol_tags();
+        match response {
+            WorkspaceSymbolResponse::Flat(symbols) => WorkspaceSymbolResponse::Flat(
+                symbols
+                    .into_iter()
+                    .map(|mut symbol| {
+                        symbol.kind = Self::symbol_kind(symbol.kind, kinds);
+                        symbol.tags = Self::tags(symbol.tags, tags);
+                        symbol
+                    })
+                    .collect(),
+            ),
+            WorkspaceSymbolResponse::Nested(symbols) => WorkspaceSymbolResponse::Nested(
+                symbols
+                    .into_iter()
+                    .map(|mut symbol| {
+                        symbol.kind = Self::symbol_kind(symbol.kind, kinds);
+                        symbol.tags = Self::tags(symbol.tags, tags);
+                        symbol
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Rewrites a completion item for the client:
+    ///
+    /// * an `InsertReplaceEdit` becomes a `TextEdit` of its `insert` range,
+    /// * snippets become plain text,
+    /// * unsupported tags are dropped; a `Deprecated` tag falls back to `deprecated`,
+    /// * an unsupported kind is dropped.
+    #[allow(deprecated)]
+    pub fn completion_item(&self, mut item: CompletionItem) -> CompletionItem {
+        if !self.capabilities.completion_insert_replace_support() {
+            if let Some(CompletionTextEdit::InsertAndReplace(edit)) = item.text_edit {
+                item.text_edit = Some(CompletionTextEdit::Edit(TextEdit::new(
+                    edit.insert,
+                    edit.new_text,
+                )));
+            }
+        }
+
+        if item.insert_text_format == Some(InsertTextFormat::SNIPPET)
+            && !self.capabilities.supports_snippets()
+        {
+            let plain = |text: String| match Snippet::parse(&text) {
+                Ok(snippet) => snippet.to_plain_text(),
+                Err(_) => text,
+            };
+            item.insert_text = item.insert_text.map(plain);
+            item.text_edit = item.text_edit.map(|edit| match edit {
+                CompletionTextEdit::Edit(mut edit) => {
+                    edit.new_text = plain(edit.new_text);
+                    CompletionTextEdit::Edit(edit)
+                }
+                CompletionTextEdit::InsertAndReplace(mut edit) => {
+                    edit.new_text = plain(edit.new_text);
+                    CompletionTextEdit::InsertAndReplace(edit)
+                }
+            });
+            item.insert_text_format = Some(InsertTextFormat::PLAIN_TEXT);
+        }
+
+        let supported_tags = self.capabilities.completion_item_tags();
+        let deprecated = item
+            .tags
+            .as_ref()
+            .is_some_and(|tags| tags.contains(&CompletionItemTag::DEPRECATED));
+        if deprecated
+            && !supported_tags.contains(&CompletionItemTag::DEPRECATED)
+            && self.capabilities.completion_deprecated_support()
+        {
+            item.deprecated = Some(true);
+        }
+        item.tags = Self::tags(item.tags, supported_tags);
+
+        if let Some(kind) = item.kind {
+            if !self.capabilities.completion_item_kinds().contains(&kind) {
+                item.kind = None;
+            }
+        }
+        item
+    }
+
+    /// Rewrites every item of a completion response, see [`Self::completion_item`].
+    pub fn completion(&self, response: CompletionResponse) -> CompletionResponse {
+        match response {
+            CompletionResponse::Array(items) => CompletionResponse::Array(
+                items
+                    .into_iter()
+                    .map(|item| self.completion_item(item))
+                    .collect(),
+            ),
+            CompletionResponse::List(mut list) => {
+                list.items = list
+                    .items
+                    .into_iter()
+                    .map(|item| self.completion_item(item))
+                    .collect();
+                CompletionResponse::List(list)
+            }
+        }
+    }
+
+    /// Drops the tags and properties of `diagnostic` the client does not support.
+    pub fn diagnostic(&self, mut diagnostic: Diagnostic) -> Diagnostic {
+        diagnostic.tags = Self::tags(
+            diagnostic.tags,
+            self.capabilities.diagnostic_tag_value_set(),
+        );
+        if !self.capabilities.diagnostic_related_information() {
+            diagnostic.related_information = None;
+        }
+        if !self.capabilities.diagnostic_code_description_support() {
+            diagnostic.code_description = None;
+        }
+        if !self.capabilities.diagnostic_data_support() {
+            diagnostic.data = None;
+        }
+        diagnostic
+    }
+
+    /// Rewrites every diagnostic, see [`Self::diagnostic`], and drops the version if the client
+    /// does not support it.
+    pub fn publish_diagnostics(
+        &self,
+        mut params: PublishDiagnosticsParams,
+    ) -> PublishDiagnosticsParams {
+        params.diagnostics = params
+            .diagnostics
+            .into_iter()
+            .map(|diagnostic| self.diagnostic(diagnostic))
+            .collect();
+        if !self.capabilities.diagnostic_version_support() {
+            params.version = None;
+        }
+        params
+    }
+
+    /// Rewrites code actions for the client. Without literal support code actions are replaced
+    /// by their command, and dropped if they have none. Disabled actions are dropped if the client
+    /// can not show them, and unsupported properties are removed.
+    pub fn code_actions(&self, actions: Vec<CodeActionOrCommand>) -> Vec<CodeActionOrCommand> {
+        let literals = self.capabilities.supports_code_action_literals();
+        actions
+            .into_iter()
+            .filter_map(|action| match action {
+                CodeActionOrCommand::CodeAction(action) if !literals => {
+                    action.command.map(CodeActionOrCommand::Command)
+                }
+                CodeActionOrCommand::CodeAction(mut action) => {
+                    if action.disabled.is_some()
+                        && !self.capabilities.code_action_disabled_support()
+                    {
+                        return None;
+                    }
+                    if !self.capabilities.code_action_is_preferred_support() {
+                        action.is_preferred = None;
+                    }
+                    if !self.capabilities.code_action_data_support() {
+                        action.data = None;
+                    }
+                    action.diagnostics = action.diagnostics.map(|diagnostics| {
+                        diagnostics
+                            .into_iter()
+                            .map(|diagnostic| self.diagnostic(diagnostic))
+                            .collect()
+                    });
+                    Some(CodeActionOrCommand::CodeAction(action))
+                }
+                command => Some(command),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CompletionClientCapabilities, CompletionItemCapability, DiagnosticTag,
+        DocumentSymbolClientCapabilities, InsertReplaceEdit, LocationLink, Position,
+        PublishDiagnosticsClientCapabilities, Range, TagSupport, TextDocumentClientCapabilities,
+    };
+
+    fn range(line: u32) -> Range {
+        Range::new(Position::new(line, 0), Position::new(line, 1))
+    }
+
+    #[test]
+    fn test_downgrade_for_minimal_client() {
+        let capabilities = ClientCapabilities::default();
+        let downgrader = ResponseDowngrader::new(&capabilities);
+        let uri: Uri = "file:///a.rs".parse().unwrap();
+
+        let links = GotoDefinitionResponse::Link(vec![LocationLink {
+            origin_selection_range: None,
+            target_uri: uri.clone(),
+            target_range: range(0),
+            target_selection_range: range(1),
+        }]);
+        assert_eq!(
+            downgrader.goto_definition(links),
+            GotoDefinitionResponse::Array(vec![Location::new(uri.clone(), range(1))])
+        );
+
+        #[allow(deprecated)]
+        let symbol = |name: &str, kind, children| DocumentSymbol {
+            name: name.into(),
+            detail: None,
+            kind,
+            tags: None,
+            deprecated: None,
+            range: range(0),
+            selection_range: range(0),
+            children,
+        };
+        let nested = DocumentSymbolResponse::Nested(vec![symbol(
+            "S",
+            SymbolKind::STRUCT,
+            Some(vec![symbol("f", SymbolKind::FIELD, None)]),
+        )]);
+        let flat = match downgrader.document_symbols(&uri, nested) {
+            DocumentSymbolResponse::Flat(flat) => flat,
+            nested => panic!("expected flat symbols, got {:?}", nested),
+        };
+        assert_eq!(
+            flat.iter()
+                .map(|s| (&s.name[..], s.kind, s.container_name.as_deref()))
+                .collect::<Vec<_>>(),
+            [
+                ("S", SymbolKind::CLASS, None),
+                ("f", SymbolKind::FIELD, Some("S"))
+            ]
+        );
+
+        let item = downgrader.completion_item(CompletionItem {
+            label: "push".into(),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            text_edit: Some(CompletionTextEdit::InsertAndReplace(InsertReplaceEdit {
+                new_text: "push(${1:value})$0".into(),
+                insert: range(0),
+                replace: range(1),
+            })),
+            tags: Some(vec![CompletionItemTag::DEPRECATED]),
+            ..CompletionItem::default()
+        });
+        assert_eq!(
+            item.text_edit,
+            Some(CompletionTextEdit::Edit(TextEdit::new(
+                range(0),
+                "push(value)".into()
+            )))
+        );
+        assert_eq!(item.insert_text_format, Some(InsertTextFormat::PLAIN_TEXT));
+        assert_eq!(item.tags, None);
+    }
+
+    #[test]
+    fn test_keep_supported_features() {
+        let capabilities = ClientCapabilities {
+            text_document: Some(TextDocumentClientCapabilities {
+                completion: Some(CompletionClientCapabilities {
+                    completion_item: Some(CompletionItemCapability {
+                        insert_replace_support: Some(true),
+                        ..CompletionItemCapability::default()
+                    }),
+                    ..CompletionClientCapabilities::default()
+                }),
+                document_symbol: Some(DocumentSymbolClientCapabilities {
+                    hierarchical_document_symbol_support: Some(true),
+                    ..DocumentSymbolClientCapabilities::default()
+                }),
+                publish_diagnostics: Some(PublishDiagnosticsClientCapabilities {
+                    tag_support: Some(TagSupport {
+                        value_set: vec![DiagnosticTag::UNNECESSARY],
+                    }),
+                    ..PublishDiagnosticsClientCapabilities::default()
+                }),
+                ..TextDocumentClientCapabilities::default()
+            }),
+            ..ClientCapabilities::default()
+        };
+        let downgrader = ResponseDowngrader::new(&capabilities);
+
+        let edit = CompletionTextEdit::InsertAndReplace(InsertReplaceEdit {
+            new_text: "x".into(),
+            insert: range(0),
+            replace: range(1),
+        });
+        let item = downgrader.completion_item(CompletionItem {
+            text_edit: Some(edit.clone()),
+            ..CompletionItem::default()
+        });
+        assert_eq!(item.text_edit, Some(edit));
+
+        let diagnostic = downgrader.diagnostic(Diagnostic {
+            tags: Some(vec![DiagnosticTag::DEPRECATED, DiagnosticTag::UNNECESSARY]),
+            ..Diagnostic::default()
+        });
+        assert_eq!(diagnostic.tags, Some(vec![DiagnosticTag::UNNECESSARY]));
+    }
+}
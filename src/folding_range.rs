@@ -4,6 +4,8 @@ use crate::{
 };
 use serde::{Deserialize, Serialize};
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct FoldingRangeParams {
     /// The text document.
@@ -16,7 +18,9 @@ pub struct FoldingRangeParams {
     pub partial_result_params: PartialResultParams,
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum FoldingRangeProviderCapability {
     Simple(bool),
@@ -24,6 +28,18 @@ pub enum FoldingRangeProviderCapability {
     Options(StaticTextDocumentColorProviderOptions),
 }
 
+impl<'de> Deserialize<'de> for FoldingRangeProviderCapability {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::deserialize_registration_options(deserializer, Self::Options, |value| match value {
+            serde_json::Value::Bool(simple) => Ok(Self::Simple(simple)),
+            value => FoldingProviderOptions::deserialize(value).map(Self::FoldingProvider),
+        })
+    }
+}
+
 impl From<StaticTextDocumentColorProviderOptions> for FoldingRangeProviderCapability {
     fn from(from: StaticTextDocumentColorProviderOptions) -> Self {
         Self::Options(from)
@@ -43,9 +59,13 @@ impl From<bool> for FoldingRangeProviderCapability {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct FoldingProviderOptions {}
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct FoldingRangeKindCapability {
     /// The folding range kind values the client supports. When this
@@ -57,6 +77,8 @@ pub struct FoldingRangeKindCapability {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct FoldingRangeCapability {
     /// If set, the client signals that it supports setting collapsedText on
@@ -68,6 +90,8 @@ pub struct FoldingRangeCapability {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct FoldingRangeClientCapabilities {
     /// Whether implementation supports dynamic registration for folding range providers. If this is set to `true`
@@ -101,6 +125,8 @@ pub struct FoldingRangeClientCapabilities {
 
 /// Enum of known range kinds
 #[derive(Debug, Eq, PartialEq, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum FoldingRangeKind {
     /// Folding range for a comment
@@ -113,6 +139,8 @@ pub enum FoldingRangeKind {
 
 /// Represents a folding range.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct FoldingRange {
     /// The zero-based line number from where the folded range starts.
@@ -143,3 +171,24 @@ pub struct FoldingRange {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub collapsed_text: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::test_deserialization, DocumentFilter};
+
+    #[test]
+    fn test_registration_options_deserialization() {
+        test_deserialization(
+            r#"{"documentSelector":[{"language":"rust"}],"id":"x"}"#,
+            &FoldingRangeProviderCapability::Options(StaticTextDocumentColorProviderOptions {
+                document_selector: Some(vec![DocumentFilter {
+                    language: Some("rust".to_string()),
+                    scheme: None,
+                    pattern: None,
+                }]),
+                id: Some("x".to_string()),
+            }),
+        );
+    }
+}
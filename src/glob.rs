@@ -0,0 +1,625 @@
+/*!
+
+Matching of the glob patterns used by `GlobPattern`, `RelativePattern` and `FileOperationPattern`.
+
+The supported syntax is the one described in the specification:
+
+- `*` to match any number of characters in a path segment
+- `?` to match on one character in a path segment
+- `**` to match any number of path segments, including none
+- `{}` to group conditions (e.g. `*.{ts,js}` matches TypeScript and JavaScript files)
+- `[]` to declare a range of characters to match in a path segment
+- `[!...]` to negate a range of characters to match in a path segment
+
+As in VS Code, `*` also matches an empty string, so `*.ts` matches `.ts`.
+
+*/
+
+use std::fmt;
+
+use crate::{
+    FileOperationFilter, FileOperationPattern, FileOperationPatternKind, GlobPattern,
+    NormalizedUri, OneOf, RelativePattern, Uri,
+};
+
+/// An error raised when a glob pattern can not be compiled.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum GlobError {
+    /// A `[` without a matching `]`.
+    UnclosedClass(usize),
+    /// A `{` without a matching `}`.
+    UnclosedGroup(usize),
+}
+
+impl fmt::Display for GlobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GlobError::UnclosedClass(offset) => write!(f, "unclosed `[` at offset {}", offset),
+            GlobError::UnclosedGroup(offset) => write!(f, "unclosed `{{` at offset {}", offset),
+        }
+    }
+}
+
+impl std::error::Error for GlobError {}
+
+#[derive(Debug, Clone)]
+enum Token {
+    Literal(char),
+    /// A `/` separating path segments.
+    Separator,
+    /// `?`, or a character matched by `*`, which are never separators.
+    AnyChar,
+    /// A character matched by `**`, including separators.
+    Any,
+    /// A class matches a separator only when it lists it.
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+impl Token {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Token::Literal(literal) => *literal == c,
+            Token::Separator => c == '/',
+            Token::AnyChar => c != '/',
+            Token::Any => true,
+            Token::Class { negated, ranges } => {
+                let listed = ranges.iter().any(|&(start, end)| start <= c && c <= end);
+                if c == '/' {
+                    listed && !negated
+                } else {
+                    listed != *negated
+                }
+            }
+        }
+    }
+}
+
+/// The syntax tree of a pattern. `{}` groups are kept as alternatives rather than expanded, so
+/// that nested or repeated groups do not multiply the size of the compiled pattern.
+#[derive(Debug)]
+enum Node {
+    Token(Token),
+    /// `*`, matching any number of characters in a path segment.
+    AnyChars,
+    /// `**`, matching any number of path segments when it is a whole segment, and behaving like
+    /// `*` otherwise.
+    AnySegments,
+    Group(Vec<Vec<Node>>),
+}
+
+/// An instruction of the automaton a pattern compiles to.
+#[derive(Debug, Clone)]
+enum Inst {
+    /// Consumes one character matched by the token.
+    Char(Token),
+    Split(usize, usize),
+    Jump(usize),
+    /// Matches at the start of a path segment, without consuming anything.
+    SegmentStart,
+    /// Matches at the end of a path segment, without consuming anything.
+    SegmentEnd,
+    Match,
+}
+
+/// A compiled glob pattern, matched against `/` separated paths.
+///
+/// Matching simulates all the ways the pattern can match at once, so it takes time proportional to
+/// the length of the path times the length of the pattern, whatever the pattern.
+#[derive(Debug, Clone)]
+pub struct Glob {
+    pattern: String,
+    ignore_case: bool,
+    program: Vec<Inst>,
+}
+
+impl Glob {
+    pub fn new(pattern: &str) -> Result<Glob, GlobError> {
+        Glob::with_options(pattern, false)
+    }
+
+    /// Compiles `pattern`, optionally matching without regard to case.
+    pub fn with_options(pattern: &str, ignore_case: bool) -> Result<Glob, GlobError> {
+        let mut parser = Parser {
+            chars: pattern.char_indices().peekable(),
+            ignore_case,
+        };
+        let nodes = parser.sequence(false)?;
+        let mut compiler = Compiler {
+            program: Vec::new(),
+        };
+        compiler.sequence(&nodes);
+        compiler.program.push(Inst::Match);
+        Ok(Glob {
+            pattern: pattern.to_string(),
+            ignore_case,
+            program: compiler.program,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn ignore_case(&self) -> bool {
+        self.ignore_case
+    }
+
+    /// Matches `path`. Both `/` and `\` separate path segments.
+    pub fn is_match(&self, path: &str) -> bool {
+        let path: Vec<char> = path
+            .chars()
+            .map(|c| match c {
+                '\\' => '/',
+                c if self.ignore_case => fold_case(c),
+                c => c,
+            })
+            .collect();
+        let mut current = States::new(self.program.len());
+        let mut next = States::new(self.program.len());
+        self.add_state(&mut current, 0, &path, 0);
+        for (pos, &c) in path.iter().enumerate() {
+            next.clear();
+            for &pc in &current.list {
+                if let Inst::Char(token) = &self.program[pc] {
+                    if token.matches(c) {
+                        self.add_state(&mut next, pc + 1, &path, pos + 1);
+                    }
+                }
+            }
+            if next.list.is_empty() {
+                return false;
+            }
+            std::mem::swap(&mut current, &mut next);
+        }
+        current
+            .list
+            .iter()
+            .any(|&pc| matches!(self.program[pc], Inst::Match))
+    }
+
+    /// Adds `pc` and the states reachable from it without consuming a character to `states`.
+    fn add_state(&self, states: &mut States, pc: usize, path: &[char], pos: usize) {
+        let mut stack = vec![pc];
+        while let Some(pc) = stack.pop() {
+            if std::mem::replace(&mut states.seen[pc], true) {
+                continue;
+            }
+            match self.program[pc] {
+                Inst::Split(a, b) => stack.extend([b, a]),
+                Inst::Jump(a) => stack.push(a),
+                Inst::SegmentStart => {
+                    if pos == 0 || path[pos - 1] == '/' {
+                        stack.push(pc + 1);
+                    }
+                }
+                Inst::SegmentEnd => {
+                    if pos == path.len() || path[pos] == '/' {
+                        stack.push(pc + 1);
+                    }
+                }
+                Inst::Char(_) | Inst::Match => states.list.push(pc),
+            }
+        }
+    }
+}
+
+/// A set of states of the automaton.
+struct States {
+    seen: Vec<bool>,
+    list: Vec<usize>,
+}
+
+impl States {
+    fn new(len: usize) -> Self {
+        States {
+            seen: vec![false; len],
+            list: Vec::new(),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.seen.iter_mut().for_each(|seen| *seen = false);
+        self.list.clear();
+    }
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    ignore_case: bool,
+}
+
+impl Parser<'_> {
+    fn fold(&self, c: char) -> char {
+        if self.ignore_case {
+            fold_case(c)
+        } else {
+            c
+        }
+    }
+
+    /// Parses up to the end of the pattern, or of the current alternative in a group.
+    fn sequence(&mut self, in_group: bool) -> Result<Vec<Node>, GlobError> {
+        let mut nodes = Vec::new();
+        while let Some(&(i, c)) = self.chars.peek() {
+            if in_group && (c == ',' || c == '}') {
+                break;
+            }
+            self.chars.next();
+            nodes.push(match c {
+                '/' => Node::Token(Token::Separator),
+                '?' => Node::Token(Token::AnyChar),
+                '*' => {
+                    let mut stars = 1;
+                    while self.chars.next_if(|&(_, c)| c == '*').is_some() {
+                        stars += 1;
+                    }
+                    if stars == 2 {
+                        Node::AnySegments
+                    } else {
+                        Node::AnyChars
+                    }
+                }
+                '[' => Node::Token(self.class(i)?),
+                '{' => Node::Group(self.group(i)?),
+                c => Node::Token(Token::Literal(self.fold(c))),
+            });
+        }
+        Ok(nodes)
+    }
+
+    fn group(&mut self, start: usize) -> Result<Vec<Vec<Node>>, GlobError> {
+        let mut alternatives = Vec::new();
+        loop {
+            alternatives.push(self.sequence(true)?);
+            match self.chars.next() {
+                Some((_, ',')) => (),
+                Some((_, '}')) => return Ok(alternatives),
+                _ => return Err(GlobError::UnclosedGroup(start)),
+            }
+        }
+    }
+
+    fn class(&mut self, start: usize) -> Result<Token, GlobError> {
+        let negated = self.chars.next_if(|&(_, c)| c == '!').is_some();
+        let mut ranges = Vec::new();
+        let mut first = true;
+        while let Some((_, c)) = self.chars.next() {
+            if c == ']' && !first {
+                return Ok(Token::Class { negated, ranges });
+            }
+            first = false;
+            if self.chars.peek().map(|&(_, c)| c) == Some('-') {
+                let mut lookahead = self.chars.clone();
+                lookahead.next();
+                match lookahead.next() {
+                    Some((_, end)) if end != ']' => {
+                        self.chars = lookahead;
+                        ranges.push((self.fold(c), self.fold(end)));
+                        continue;
+                    }
+                    _ => (),
+                }
+            }
+            ranges.push((self.fold(c), self.fold(c)));
+        }
+        Err(GlobError::UnclosedClass(start))
+    }
+}
+
+struct Compiler {
+    program: Vec<Inst>,
+}
+
+impl Compiler {
+    fn push(&mut self, inst: Inst) -> usize {
+        self.program.push(inst);
+        self.program.len() - 1
+    }
+
+    fn patch(&mut self, pc: usize, target: usize) {
+        match &mut self.program[pc] {
+            Inst::Split(_, b) => *b = target,
+            Inst::Jump(a) => *a = target,
+            _ => unreachable!(),
+        }
+    }
+
+    fn sequence(&mut self, nodes: &[Node]) {
+        let mut i = 0;
+        while i < nodes.len() {
+            match &nodes[i] {
+                Node::Token(Token::Separator)
+                    if i + 2 == nodes.len() && matches!(nodes[i + 1], Node::AnySegments) =>
+                {
+                    // A trailing `/**` also matches no segment at all, so `a/**` matches `a`.
+                    let split = self.push(Inst::Split(self.program.len() + 1, 0));
+                    self.push(Inst::Char(Token::Separator));
+                    self.any_segments(false);
+                    let jump = self.push(Inst::Jump(0));
+                    self.patch(split, self.program.len());
+                    self.push(Inst::SegmentEnd);
+                    self.patch(jump, self.program.len());
+                    i += 1;
+                }
+                Node::Token(token) => {
+                    self.push(Inst::Char(token.clone()));
+                }
+                Node::AnyChars => self.repeat(Token::AnyChar),
+                Node::AnySegments => {
+                    // `**/` also matches no segment at all, so it takes the separator with it.
+                    let separator = matches!(nodes.get(i + 1), Some(Node::Token(Token::Separator)));
+                    self.any_segments(separator);
+                    if separator {
+                        i += 1;
+                    }
+                }
+                Node::Group(alternatives) => {
+                    let mut jumps = Vec::new();
+                    for (n, alternative) in alternatives.iter().enumerate() {
+                        let split = (n + 1 < alternatives.len())
+                            .then(|| self.push(Inst::Split(self.program.len() + 1, 0)));
+                        self.sequence(alternative);
+                        jumps.push(self.push(Inst::Jump(0)));
+                        if let Some(split) = split {
+                            self.patch(split, self.program.len());
+                        }
+                    }
+                    for jump in jumps {
+                        self.patch(jump, self.program.len());
+                    }
+                }
+            }
+            i += 1;
+        }
+    }
+
+    /// Matches any number of characters matched by `token`.
+    fn repeat(&mut self, token: Token) {
+        let split = self.push(Inst::Split(self.program.len() + 1, 0));
+        self.push(Inst::Char(token));
+        self.push(Inst::Jump(split));
+        self.patch(split, self.program.len());
+    }
+
+    /// Compiles `**`, followed by a separator if `separator` is set. It matches like `*`, or, at
+    /// the start of a segment, any number of whole segments.
+    fn any_segments(&mut self, separator: bool) {
+        let split = self.push(Inst::Split(self.program.len() + 1, 0));
+        self.repeat(Token::AnyChar);
+        if separator {
+            self.push(Inst::Char(Token::Separator));
+        }
+        let jump = self.push(Inst::Jump(0));
+        self.patch(split, self.program.len());
+        self.push(Inst::SegmentStart);
+        if separator {
+            let split = self.push(Inst::Split(self.program.len() + 1, 0));
+            self.repeat(Token::Any);
+            self.push(Inst::Char(Token::Separator));
+            self.patch(split, self.program.len());
+        } else {
+            self.repeat(Token::Any);
+            self.push(Inst::SegmentEnd);
+        }
+        self.patch(jump, self.program.len());
+    }
+}
+
+fn fold_case(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Returns the decoded path of `uri`.
+fn decoded_path(uri: &Uri) -> String {
+    uri.path()
+        .as_estr()
+        .decode()
+        .into_string_lossy()
+        .into_owned()
+}
+
+/// A compiled `GlobPattern`, as used by `FileSystemWatcher`.
+///
+/// String patterns are matched against the decoded path of a `Uri`. Relative patterns are matched
+/// against the part of the path below their base URI.
+#[derive(Debug, Clone)]
+pub struct GlobPatternMatcher {
+    base: Option<NormalizedUri>,
+    glob: Glob,
+}
+
+impl GlobPatternMatcher {
+    pub fn new(pattern: &GlobPattern) -> Result<Self, GlobError> {
+        match pattern {
+            GlobPattern::String(pattern) => Ok(GlobPatternMatcher {
+                base: None,
+                glob: Glob::new(pattern)?,
+            }),
+            GlobPattern::Relative(pattern) => Self::new_relative(pattern),
+        }
+    }
+
+    pub fn new_relative(pattern: &RelativePattern) -> Result<Self, GlobError> {
+        let base = match &pattern.base_uri {
+            OneOf::Left(folder) => folder.uri.clone(),
+            OneOf::Right(uri) => uri.clone(),
+        };
+        Ok(GlobPatternMatcher {
+            base: Some(NormalizedUri::new(base)),
+            glob: Glob::new(&pattern.pattern)?,
+        })
+    }
+
+    pub fn is_match(&self, uri: &Uri) -> bool {
+        match &self.base {
+            Some(base) => uri
+                .strip_normalized_prefix(base)
+                .is_some_and(|path| self.glob.is_match(&path)),
+            None => self.glob.is_match(&decoded_path(uri)),
+        }
+    }
+}
+
+/// A compiled `FileOperationFilter` or `FileOperationPattern`.
+#[derive(Debug, Clone)]
+pub struct FileOperationMatcher {
+    scheme: Option<String>,
+    glob: Glob,
+    kind: Option<FileOperationPatternKind>,
+}
+
+impl FileOperationMatcher {
+    pub fn new(pattern: &FileOperationPattern) -> Result<Self, GlobError> {
+        let ignore_case = pattern
+            .options
+            .as_ref()
+            .and_then(|options| options.ignore_case)
+            .unwrap_or(false);
+        Ok(FileOperationMatcher {
+            scheme: None,
+            glob: Glob::with_options(&pattern.glob, ignore_case)?,
+            kind: pattern.matches.clone(),
+        })
+    }
+
+    pub fn from_filter(filter: &FileOperationFilter) -> Result<Self, GlobError> {
+        Ok(FileOperationMatcher {
+            scheme: filter.scheme.clone(),
+            ..Self::new(&filter.pattern)?
+        })
+    }
+
+    /// Matches the file or folder at `uri`. `kind` tells whether it is a file or a folder; if it is
+    /// not known, patterns restricted to one kind match either.
+    pub fn is_match(&self, uri: &Uri, kind: Option<FileOperationPatternKind>) -> bool {
+        if let Some(scheme) = &self.scheme {
+            if !uri
+                .scheme()
+                .is_some_and(|s| s.as_str().eq_ignore_ascii_case(scheme))
+            {
+                return false;
+            }
+        }
+        if let (Some(expected), Some(kind)) = (&self.kind, &kind) {
+            if expected != kind {
+                return false;
+            }
+        }
+        self.glob.is_match(&decoded_path(uri))
+    }
+
+    /// Matches a URI given as a string, as found in `FileCreate`, `FileRename` and `FileDelete`.
+    /// Strings that are not valid URIs never match.
+    pub fn is_match_str(&self, uri: &str, kind: Option<FileOperationPatternKind>) -> bool {
+        uri.parse::<Uri>()
+            .is_ok_and(|uri| self.is_match(&uri, kind))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileOperationPatternOptions, WorkspaceFolder};
+
+    fn uri(s: &str) -> Uri {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_glob() {
+        let glob = Glob::new("**/*.{ts,js}").unwrap();
+        assert!(glob.is_match("a/b/c.ts"));
+        assert!(glob.is_match("c.js"));
+        assert!(glob.is_match("/abs/c.js"));
+        assert!(!glob.is_match("a/c.rs"));
+        assert!(!glob.is_match("a/c.ts/d"));
+
+        let glob = Glob::new("src/example.[0-9]").unwrap();
+        assert!(glob.is_match("src/example.5"));
+        assert!(!glob.is_match("src/example.a"));
+        assert!(!glob.is_match("src/sub/example.5"));
+
+        let glob = Glob::new("example.[!0-9]?").unwrap();
+        assert!(glob.is_match("example.ab"));
+        assert!(!glob.is_match("example.1b"));
+        assert!(!glob.is_match("example.a"));
+
+        let glob = Glob::new("{src,test/**}/*.rs").unwrap();
+        assert!(glob.is_match("src/lib.rs"));
+        assert!(glob.is_match("test/a/b/lib.rs"));
+        assert!(!glob.is_match("bench/lib.rs"));
+
+        let glob = Glob::new("a/**/b").unwrap();
+        assert!(glob.is_match("a/b"));
+        assert!(glob.is_match("a/x/y/b"));
+
+        assert!(Glob::with_options("*.TXT", true)
+            .unwrap()
+            .is_match("Readme.txt"));
+        assert!(!Glob::new("*.TXT").unwrap().is_match("Readme.txt"));
+
+        let glob = Glob::new("a/[/]/**").unwrap();
+        assert!(glob.is_match("a///b"));
+        assert!(!glob.is_match("a/x/b"));
+
+        assert_eq!(Glob::new("a[b").unwrap_err(), GlobError::UnclosedClass(1));
+        assert_eq!(Glob::new("{a,b").unwrap_err(), GlobError::UnclosedGroup(0));
+    }
+
+    #[test]
+    fn test_glob_complexity() {
+        // Backtracking over stars, or expanding the groups, would not finish in reasonable time.
+        let glob = Glob::new(&format!("{}b", "*a".repeat(20))).unwrap();
+        assert!(!glob.is_match(&"a".repeat(200)));
+        assert!(glob.is_match(&format!("{}b", "a".repeat(200))));
+
+        let glob = Glob::new(&"{a,b}".repeat(64)).unwrap();
+        assert!(glob.is_match(&"ab".repeat(32)));
+        assert!(!glob.is_match(&"ab".repeat(31)));
+    }
+
+    #[test]
+    fn test_relative_pattern() {
+        let matcher = GlobPatternMatcher::new(&GlobPattern::Relative(RelativePattern {
+            base_uri: OneOf::Left(WorkspaceFolder {
+                uri: uri("file:///work/space/"),
+                name: "space".into(),
+            }),
+            pattern: "src/**/*.rs".into(),
+        }))
+        .unwrap();
+        assert!(matcher.is_match(&uri("file:///work/space/src/a/b.rs")));
+        assert!(matcher.is_match(&uri("file:///work/space/src/my%20file.rs")));
+        assert!(!matcher.is_match(&uri("file:///work/other/src/b.rs")));
+        assert!(!matcher.is_match(&uri("untitled:///work/space/src/b.rs")));
+
+        let matcher =
+            GlobPatternMatcher::new(&GlobPattern::String("**/Cargo.toml".into())).unwrap();
+        assert!(matcher.is_match(&uri("file:///work/space/Cargo.toml")));
+    }
+
+    #[test]
+    fn test_file_operation_pattern() {
+        let matcher = FileOperationMatcher::from_filter(&FileOperationFilter {
+            scheme: Some("file".into()),
+            pattern: FileOperationPattern {
+                glob: "**/*.RS".into(),
+                matches: Some(FileOperationPatternKind::File),
+                options: Some(FileOperationPatternOptions {
+                    ignore_case: Some(true),
+                }),
+            },
+        })
+        .unwrap();
+        assert!(matcher.is_match(&uri("file:///a/b.rs"), Some(FileOperationPatternKind::File)));
+        assert!(matcher.is_match(&uri("file:///a/b.rs"), None));
+        assert!(!matcher.is_match(
+            &uri("file:///a/b.rs"),
+            Some(FileOperationPatternKind::Folder)
+        ));
+        assert!(!matcher.is_match_str("untitled:b.rs", None));
+    }
+}
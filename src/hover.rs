@@ -6,6 +6,8 @@ use crate::{
 };
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct HoverClientCapabilities {
     /// Whether completion supports dynamic registration.
@@ -20,6 +22,8 @@ pub struct HoverClientCapabilities {
 
 /// Hover options.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct HoverOptions {
     #[serde(flatten)]
@@ -27,6 +31,8 @@ pub struct HoverOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct HoverRegistrationOptions {
     #[serde(flatten)]
@@ -37,6 +43,8 @@ pub struct HoverRegistrationOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum HoverProviderCapability {
     Simple(bool),
@@ -56,6 +64,8 @@ impl From<bool> for HoverProviderCapability {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct HoverParams {
     #[serde(flatten)]
@@ -67,6 +77,8 @@ pub struct HoverParams {
 
 /// The result of a hover request.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Hover {
     /// The hover's content
     pub contents: HoverContents,
@@ -78,9 +90,31 @@ pub struct Hover {
 
 /// Hover contents could be single entry or multiple entries.
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum HoverContents {
-    Scalar(MarkedString),
+    // `Array` is tried first, as a `MarkedString::LanguageString` can also be read from a
+    // two-element array.
     Array(Vec<MarkedString>),
+    Scalar(MarkedString),
     Markup(MarkupContent),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_deserialization;
+
+    #[test]
+    fn test_hover_contents_deserialization() {
+        // Not a `MarkedString::LanguageString` read from an array.
+        test_deserialization(
+            r#"["rust","fn main() {}"]"#,
+            &HoverContents::Array(vec![
+                MarkedString::String("rust".to_string()),
+                MarkedString::String("fn main() {}".to_string()),
+            ]),
+        );
+    }
+}
@@ -5,7 +5,9 @@ use crate::{
 };
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(untagged)]
 pub enum InlayHintServerCapabilities {
@@ -13,10 +15,23 @@ pub enum InlayHintServerCapabilities {
     RegistrationOptions(InlayHintRegistrationOptions),
 }
 
+impl<'de> Deserialize<'de> for InlayHintServerCapabilities {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::deserialize_registration_options(deserializer, Self::RegistrationOptions, |value| {
+            InlayHintOptions::deserialize(value).map(Self::Options)
+        })
+    }
+}
+
 /// Inlay hint client capabilities.
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct InlayHintClientCapabilities {
     /// Whether inlay hints support dynamic registration.
@@ -33,6 +48,8 @@ pub struct InlayHintClientCapabilities {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct InlayHintOptions {
     #[serde(flatten)]
@@ -48,6 +65,8 @@ pub struct InlayHintOptions {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct InlayHintRegistrationOptions {
     #[serde(flatten)]
@@ -64,6 +83,8 @@ pub struct InlayHintRegistrationOptions {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct InlayHintParams {
     #[serde(flatten)]
@@ -79,7 +100,9 @@ pub struct InlayHintParams {
 /// Inlay hint information.
 ///
 /// @since 3.17.0
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct InlayHint {
     /// The position of this hint.
@@ -133,10 +156,13 @@ pub struct InlayHint {
     /// A data entry field that is preserved on a inlay hint between
     /// a `textDocument/inlayHint` and a `inlayHint/resolve` request.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::option_value))]
     pub data: Option<LSPAny>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum InlayHintLabel {
     String(String),
@@ -157,7 +183,9 @@ impl From<Vec<InlayHintLabelPart>> for InlayHintLabel {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum InlayHintTooltip {
     String(String),
@@ -180,7 +208,9 @@ impl From<MarkupContent> for InlayHintTooltip {
 
 /// An inlay hint label part allows for interactive and composite labels
 /// of inlay hints.
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct InlayHintLabelPart {
     /// The value of this label part.
@@ -215,6 +245,8 @@ pub struct InlayHintLabelPart {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum InlayHintLabelPartTooltip {
     String(String),
@@ -239,6 +271,7 @@ impl From<MarkupContent> for InlayHintLabelPartTooltip {
 ///
 /// @since 3.17.0
 #[derive(Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(transparent)]
 pub struct InlayHintKind(i32);
 lsp_enum! {
@@ -255,6 +288,8 @@ impl InlayHintKind {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct InlayHintResolveClientCapabilities {
     /// The properties that a client can resolve lazily.
@@ -265,6 +300,8 @@ pub struct InlayHintResolveClientCapabilities {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct InlayHintWorkspaceClientCapabilities {
     /// Whether the client implementation supports a refresh request sent from
@@ -279,3 +316,29 @@ pub struct InlayHintWorkspaceClientCapabilities {
 }
 
 // TODO(sno2): add tests once stabilized
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::test_deserialization, DocumentFilter};
+
+    #[test]
+    fn test_registration_options_deserialization() {
+        test_deserialization(
+            r#"{"documentSelector":[{"language":"rust"}],"id":"x"}"#,
+            &InlayHintServerCapabilities::RegistrationOptions(InlayHintRegistrationOptions {
+                inlay_hint_options: InlayHintOptions::default(),
+                text_document_registration_options: TextDocumentRegistrationOptions {
+                    document_selector: Some(vec![DocumentFilter {
+                        language: Some("rust".to_string()),
+                        scheme: None,
+                        pattern: None,
+                    }]),
+                },
+                static_registration_options: StaticRegistrationOptions {
+                    id: Some("x".to_string()),
+                },
+            }),
+        );
+    }
+}
@@ -7,17 +7,32 @@ use serde::{Deserialize, Serialize};
 
 pub type InlineValueClientCapabilities = DynamicRegistrationClientCapabilities;
 
-#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum InlineValueServerCapabilities {
     Options(InlineValueOptions),
     RegistrationOptions(InlineValueRegistrationOptions),
 }
 
+impl<'de> Deserialize<'de> for InlineValueServerCapabilities {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::deserialize_registration_options(deserializer, Self::RegistrationOptions, |value| {
+            InlineValueOptions::deserialize(value).map(Self::Options)
+        })
+    }
+}
+
 /// Inline value options used during static registration.
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct InlineValueOptions {
     #[serde(flatten)]
     pub work_done_progress_options: WorkDoneProgressOptions,
@@ -27,6 +42,8 @@ pub struct InlineValueOptions {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct InlineValueRegistrationOptions {
     #[serde(flatten)]
     pub inline_value_options: InlineValueOptions,
@@ -42,6 +59,8 @@ pub struct InlineValueRegistrationOptions {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct InlineValueParams {
     #[serde(flatten)]
@@ -60,6 +79,8 @@ pub struct InlineValueParams {
 
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct InlineValueContext {
     /// The stack frame (as a DAP Id) where the execution has stopped.
@@ -75,6 +96,8 @@ pub struct InlineValueContext {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct InlineValueText {
     /// The document range for which the inline value applies.
     pub range: Range,
@@ -92,6 +115,8 @@ pub struct InlineValueText {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct InlineValueVariableLookup {
     /// The document range for which the inline value applies.
@@ -116,6 +141,8 @@ pub struct InlineValueVariableLookup {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct InlineValueEvaluatableExpression {
     /// The document range for which the inline value applies.
@@ -132,10 +159,13 @@ pub struct InlineValueEvaluatableExpression {
 /// - directly as a text value (class InlineValueText).
 /// - as a name to use for a variable lookup (class InlineValueVariableLookup)
 /// - as an evaluatable expression (class InlineValueEvaluatableExpression)
+///
 /// The InlineValue types combines all inline value types into one type.
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum InlineValue {
     Text(InlineValueText),
@@ -166,6 +196,8 @@ impl From<InlineValueEvaluatableExpression> for InlineValue {
 
 /// Client workspace capabilities specific to inline values.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 ///
 /// @since 3.17.0
 #[serde(rename_all = "camelCase")]
@@ -184,8 +216,8 @@ pub struct InlineValueWorkspaceClientCapabilities {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tests::test_serialization;
-    use crate::Position;
+    use crate::tests::{test_deserialization, test_serialization};
+    use crate::{DocumentFilter, Position};
 
     #[test]
     fn inline_values() {
@@ -214,4 +246,23 @@ mod tests {
             r#"{"range":{"start":{"line":2,"character":0},"end":{"line":2,"character":4}}}"#,
         );
     }
+    #[test]
+    fn test_registration_options_deserialization() {
+        test_deserialization(
+            r#"{"documentSelector":[{"language":"rust"}],"id":"x"}"#,
+            &InlineValueServerCapabilities::RegistrationOptions(InlineValueRegistrationOptions {
+                inline_value_options: InlineValueOptions::default(),
+                text_document_registration_options: TextDocumentRegistrationOptions {
+                    document_selector: Some(vec![DocumentFilter {
+                        language: Some("rust".to_string()),
+                        scheme: None,
+                        pattern: None,
+                    }]),
+                },
+                static_registration_options: StaticRegistrationOptions {
+                    id: Some("x".to_string()),
+                },
+            }),
+        );
+    }
 }
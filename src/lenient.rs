@@ -0,0 +1,496 @@
+/*!
+
+Deserialization that repairs common client quirks instead of failing.
+
+Some clients send messages that do not follow the protocol exactly: `null` for arrays, an empty
+`rootUri`, enum numbers as strings or URIs that do not parse. The functions of this module
+deserialize such messages anyway and report every repair as a [`LenientWarning`]:
+
+- `null` where an array is expected is read as an empty array,
+- an empty string where a value is expected is read as absent,
+- a string holding a number where a number is expected is read as that number,
+- any other value that can not be deserialized is dropped: an array element is removed from its
+  array, and a field from its object.
+
+[`initialize_params`] and [`client_capabilities`] additionally drop the symbol and completion item
+kinds this crate does not know from the value sets of the client capabilities, and
+[`initialize_params`] reads an empty `rootUri` or `rootPath` as absent.
+
+*/
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use serde_path_to_error::Segment;
+
+use crate::{notification::Notification, request::Request, ClientCapabilities, InitializeParams};
+
+/// A deserialized value with the repairs that were needed to deserialize it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Lenient<T> {
+    pub value: T,
+    pub warnings: Vec<LenientWarning>,
+}
+
+impl<T> Lenient<T> {
+    pub fn is_exact(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+/// How a value of the input was repaired.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Coercion {
+    /// `null` was read as an empty array.
+    NullAsEmpty,
+    /// An empty string was read as an absent value.
+    EmptyStringAsAbsent,
+    /// A string was read as the number it holds.
+    NumberFromString,
+    /// The value was dropped because it could not be deserialized, with the error message.
+    Dropped(String),
+    /// The value is not one of the enum values this crate knows and was dropped.
+    UnknownEnumValue,
+}
+
+/// A repaired value of the input and its path, such as `workspaceFolders[1]`.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct LenientWarning {
+    pub path: String,
+    pub coercion: Coercion,
+}
+
+impl fmt::Display for LenientWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.coercion {
+            Coercion::NullAsEmpty => write!(f, "`{}`: read `null` as empty", self.path),
+            Coercion::EmptyStringAsAbsent => {
+                write!(f, "`{}`: read empty string as absent", self.path)
+            }
+            Coercion::NumberFromString => write!(f, "`{}`: read string as number", self.path),
+            Coercion::Dropped(error) => write!(f, "`{}`: dropped, {}", self.path, error),
+            Coercion::UnknownEnumValue => write!(f, "`{}`: dropped unknown value", self.path),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+fn format_path(steps: &[Step]) -> String {
+    let mut path = String::new();
+    for step in steps {
+        match step {
+            Step::Key(key) => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(key);
+            }
+            Step::Index(index) => path.push_str(&format!("[{}]", index)),
+        }
+    }
+    path
+}
+
+/// Whether `path` is `ancestor` or a path inside it.
+fn is_within(path: &str, ancestor: &str) -> bool {
+    path.strip_prefix(ancestor)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '[']))
+}
+
+/// The indices in the input of the array elements dropped so far, by the path of their array, so
+/// that the paths of warnings refer to the input rather than to the repaired value.
+#[derive(Debug, Default)]
+struct Dropped(HashMap<String, Vec<usize>>);
+
+impl Dropped {
+    /// The index in the input of the element now at `index` of the array at `array`.
+    fn original_index(&self, array: &str, mut index: usize) -> usize {
+        for &dropped in self.0.get(array).into_iter().flatten() {
+            if dropped <= index {
+                index += 1;
+            }
+        }
+        index
+    }
+
+    fn original_steps(&self, steps: &[Step]) -> Vec<Step> {
+        let mut original = Vec::new();
+        for step in steps {
+            let step = match step {
+                Step::Key(key) => Step::Key(key.clone()),
+                Step::Index(index) => {
+                    Step::Index(self.original_index(&format_path(&original), *index))
+                }
+            };
+            original.push(step);
+        }
+        original
+    }
+
+    /// Records the element at the original `steps`, if it is one.
+    fn insert(&mut self, steps: &[Step]) {
+        if let Some((Step::Index(index), array)) = steps.split_last() {
+            let dropped = self.0.entry(format_path(array)).or_default();
+            let position = dropped.partition_point(|&dropped| dropped < *index);
+            dropped.insert(position, *index);
+        }
+    }
+}
+
+/// The steps of `path` that lead to a value of `root`. Segments the input can not be navigated
+/// along, such as untagged enum variants, end the path.
+fn resolve(root: &Value, path: &serde_path_to_error::Path) -> Vec<Step> {
+    let mut steps = Vec::new();
+    let mut value = root;
+    for segment in path.iter() {
+        let (step, next) = match (segment, value) {
+            (Segment::Map { key }, Value::Object(map)) => match map.get(key) {
+                Some(next) => (Step::Key(key.clone()), next),
+                None => break,
+            },
+            (Segment::Seq { index }, Value::Array(array)) => match array.get(*index) {
+                Some(next) => (Step::Index(*index), next),
+                None => break,
+            },
+            _ => break,
+        };
+        steps.push(step);
+        value = next;
+    }
+    steps
+}
+
+fn get_mut<'a>(mut value: &'a mut Value, steps: &[Step]) -> Option<&'a mut Value> {
+    for step in steps {
+        value = match (step, value) {
+            (Step::Key(key), Value::Object(map)) => map.get_mut(key)?,
+            (Step::Index(index), Value::Array(array)) => array.get_mut(*index)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+/// Replaces `value` with an equivalent one that is more likely to deserialize.
+fn coerce(value: &mut Value) -> Option<Coercion> {
+    let (coerced, coercion) = match value {
+        Value::Null => (Value::Array(Vec::new()), Coercion::NullAsEmpty),
+        Value::String(s) if s.is_empty() => (Value::Null, Coercion::EmptyStringAsAbsent),
+        Value::String(s) => (
+            serde_json::from_str::<serde_json::Number>(s.trim())
+                .ok()
+                .map(Value::Number)?,
+            Coercion::NumberFromString,
+        ),
+        _ => return None,
+    };
+    *value = coerced;
+    Some(coercion)
+}
+
+/// Removes the innermost array element on `steps`, or else the field at `steps`. Returns the path
+/// of the removed value.
+fn drop_value(root: &mut Value, steps: &[Step]) -> Option<Vec<Step>> {
+    let position = steps
+        .iter()
+        .rposition(|step| matches!(step, Step::Index(_)))
+        .or_else(|| steps.len().checked_sub(1))?;
+    match (&steps[position], get_mut(root, &steps[..position])?) {
+        (Step::Index(index), Value::Array(array)) => {
+            array.remove(*index);
+        }
+        (Step::Key(key), Value::Object(map)) => {
+            map.remove(key);
+        }
+        _ => return None,
+    }
+    Some(steps[..=position].to_vec())
+}
+
+/// Deserializes `value`, repairing the values that can not be deserialized.
+///
+/// Fails if the input can not be repaired, for example if it is not an object at all.
+pub fn from_value<T: DeserializeOwned>(value: Value) -> Result<Lenient<T>, serde_json::Error> {
+    deserialize(value).map(|(lenient, _)| lenient)
+}
+
+fn deserialize<T: DeserializeOwned>(
+    mut value: Value,
+) -> Result<(Lenient<T>, Dropped), serde_json::Error> {
+    let mut warnings: Vec<LenientWarning> = Vec::new();
+    let mut dropped = Dropped::default();
+    // Paths that were already coerced once, to drop them if they still fail.
+    let mut coerced = HashSet::new();
+    loop {
+        let error = match serde_path_to_error::deserialize::<_, T>(&value) {
+            Ok(deserialized) => {
+                let lenient = Lenient {
+                    value: deserialized,
+                    warnings,
+                };
+                return Ok((lenient, dropped));
+            }
+            Err(error) => error,
+        };
+        let steps = resolve(&value, error.path());
+        let path = format_path(&dropped.original_steps(&steps));
+        if !steps.is_empty() && !coerced.contains(&path) {
+            if let Some(coercion) = get_mut(&mut value, &steps).and_then(coerce) {
+                coerced.insert(path.clone());
+                warnings.push(LenientWarning { path, coercion });
+                continue;
+            }
+        }
+        let removed = match drop_value(&mut value, &steps) {
+            Some(removed) => dropped.original_steps(&removed),
+            None => return Err(error.into_inner()),
+        };
+        dropped.insert(&removed);
+        let path = format_path(&removed);
+        // Repairs of the dropped value no longer apply.
+        warnings.retain(|warning| !is_within(&warning.path, &path));
+        warnings.push(LenientWarning {
+            path,
+            coercion: Coercion::Dropped(error.into_inner().to_string()),
+        });
+    }
+}
+
+/// Like [`from_value`], for JSON text.
+pub fn from_str<T: DeserializeOwned>(s: &str) -> Result<Lenient<T>, serde_json::Error> {
+    from_value(serde_json::from_str(s)?)
+}
+
+/// Deserializes the params of the request `R`.
+pub fn request_params<R: Request>(params: Value) -> Result<Lenient<R::Params>, serde_json::Error> {
+    from_value(params)
+}
+
+/// Deserializes the result of the request `R`.
+pub fn request_result<R: Request>(result: Value) -> Result<Lenient<R::Result>, serde_json::Error> {
+    from_value(result)
+}
+
+/// Deserializes the params of the notification `N`.
+pub fn notification_params<N: Notification>(
+    params: Value,
+) -> Result<Lenient<N::Params>, serde_json::Error> {
+    from_value(params)
+}
+
+/// Removes the values `is_known` rejects from `value_set`.
+fn retain_known<T>(
+    value_set: Option<&mut Vec<T>>,
+    is_known: impl Fn(&T) -> bool,
+    path: &str,
+    dropped: &Dropped,
+    warnings: &mut Vec<LenientWarning>,
+) {
+    if let Some(value_set) = value_set {
+        let mut index = 0;
+        value_set.retain(|value| {
+            let known = is_known(value);
+            if !known {
+                warnings.push(LenientWarning {
+                    path: format!("{}[{}]", path, dropped.original_index(path, index)),
+                    coercion: Coercion::UnknownEnumValue,
+                });
+            }
+            index += 1;
+            known
+        });
+    }
+}
+
+fn retain_known_kinds(
+    capabilities: &mut ClientCapabilities,
+    prefix: &str,
+    dropped: &Dropped,
+    warnings: &mut Vec<LenientWarning>,
+) {
+    retain_known(
+        capabilities
+            .workspace
+            .as_mut()
+            .and_then(|workspace| workspace.symbol.as_mut())
+            .and_then(|symbol| symbol.symbol_kind.as_mut())
+            .and_then(|kind| kind.value_set.as_mut()),
+        |kind| kind.is_known(),
+        &format!("{}workspace.symbol.symbolKind.valueSet", prefix),
+        dropped,
+        warnings,
+    );
+    let text_document = match capabilities.text_document.as_mut() {
+        Some(text_document) => text_document,
+        None => return,
+    };
+    retain_known(
+        text_document
+            .completion
+            .as_mut()
+            .and_then(|completion| completion.completion_item_kind.as_mut())
+            .and_then(|kind| kind.value_set.as_mut()),
+        |kind| kind.is_known(),
+        &format!(
+            "{}textDocument.completion.completionItemKind.valueSet",
+            prefix
+        ),
+        dropped,
+        warnings,
+    );
+    retain_known(
+        text_document
+            .document_symbol
+            .as_mut()
+            .and_then(|symbol| symbol.symbol_kind.as_mut())
+            .and_then(|kind| kind.value_set.as_mut()),
+        |kind| kind.is_known(),
+        &format!("{}textDocument.documentSymbol.symbolKind.valueSet", prefix),
+        dropped,
+        warnings,
+    );
+}
+
+/// Deserializes client capabilities, dropping unknown kinds from their value sets.
+pub fn client_capabilities(value: Value) -> Result<Lenient<ClientCapabilities>, serde_json::Error> {
+    let (mut lenient, dropped) = deserialize::<ClientCapabilities>(value)?;
+    retain_known_kinds(&mut lenient.value, "", &dropped, &mut lenient.warnings);
+    Ok(lenient)
+}
+
+/// Deserializes the params of `initialize`, dropping unknown kinds from the value sets of the
+/// client capabilities.
+pub fn initialize_params(
+    mut params: Value,
+) -> Result<Lenient<InitializeParams>, serde_json::Error> {
+    // An empty string parses as a relative URI, but clients without a workspace send it to mean
+    // that there is no root.
+    let mut warnings = Vec::new();
+    for key in ["rootUri", "rootPath"] {
+        if let Some(root) = params.get_mut(key).filter(|root| root.as_str() == Some("")) {
+            *root = Value::Null;
+            warnings.push(LenientWarning {
+                path: key.to_string(),
+                coercion: Coercion::EmptyStringAsAbsent,
+            });
+        }
+    }
+    let (mut lenient, dropped) = deserialize::<InitializeParams>(params)?;
+    warnings.append(&mut lenient.warnings);
+    lenient.warnings = warnings;
+    retain_known_kinds(
+        &mut lenient.value.capabilities,
+        "capabilities.",
+        &dropped,
+        &mut lenient.warnings,
+    );
+    Ok(lenient)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{notification::DidChangeTextDocument, SymbolKind};
+    use serde_json::json;
+
+    #[test]
+    fn test_coercions() {
+        let params = json!({
+            "textDocument": { "uri": "file:///a.rs", "version": "3" },
+            "contentChanges": null,
+        });
+        let lenient = notification_params::<DidChangeTextDocument>(params).unwrap();
+        assert_eq!(lenient.value.text_document.version, 3);
+        assert!(lenient.value.content_changes.is_empty());
+        assert_eq!(
+            lenient.warnings,
+            [
+                LenientWarning {
+                    path: "contentChanges".into(),
+                    coercion: Coercion::NullAsEmpty,
+                },
+                LenientWarning {
+                    path: "textDocument.version".into(),
+                    coercion: Coercion::NumberFromString,
+                },
+            ]
+        );
+        assert!(from_value::<crate::Position>(json!([])).is_err());
+
+        // The coercion of a value that is dropped anyway is not reported.
+        let params = json!({
+            "textDocument": { "uri": "file:///a.rs", "version": 1 },
+            "contentChanges": [
+                { "range": { "start": { "line": "", "character": 0 } }, "text": "" },
+                { "text": "x" },
+            ],
+        });
+        let lenient = notification_params::<DidChangeTextDocument>(params).unwrap();
+        assert_eq!(lenient.value.content_changes.len(), 1);
+        assert_eq!(lenient.warnings.len(), 1);
+        assert_eq!(lenient.warnings[0].path, "contentChanges[0]");
+    }
+
+    #[test]
+    fn test_initialize_params() {
+        let params = json!({
+            "processId": 1,
+            "rootUri": "",
+            "capabilities": {
+                "workspace": {
+                    "symbol": { "symbolKind": { "valueSet": [1, "5", {}, 99, 26] } },
+                },
+            },
+            "workspaceFolders": [
+                { "uri": "not a uri", "name": "broken" },
+                { "uri": "file:///project", "name": "project" },
+                { "name": 7 },
+            ],
+        });
+        let lenient = initialize_params(params).unwrap();
+        #[allow(deprecated)]
+        let root_uri = &lenient.value.root_uri;
+        assert_eq!(root_uri, &None);
+        let folders = lenient.value.workspace_folders.as_ref().unwrap();
+        assert_eq!(folders.len(), 1);
+        assert_eq!(folders[0].name, "project");
+        assert_eq!(
+            lenient
+                .value
+                .capabilities
+                .resolved()
+                .workspace_symbol_kinds(),
+            [
+                SymbolKind::FILE,
+                SymbolKind::CLASS,
+                SymbolKind::TYPE_PARAMETER
+            ]
+        );
+        let paths: Vec<_> = lenient
+            .warnings
+            .iter()
+            .map(|warning| warning.path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "rootUri",
+                "capabilities.workspace.symbol.symbolKind.valueSet[1]",
+                "capabilities.workspace.symbol.symbolKind.valueSet[2]",
+                "workspaceFolders[0]",
+                "workspaceFolders[2]",
+                "capabilities.workspace.symbol.symbolKind.valueSet[3]",
+            ]
+        );
+        assert_eq!(lenient.warnings[5].coercion, Coercion::UnknownEnumValue);
+    }
+}
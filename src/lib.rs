@@ -15,9 +15,12 @@ use std::{collections::HashMap, fmt::Debug};
 use serde::{de, de::Error, Deserialize, Serialize};
 use serde_json::Value;
 
-pub use uri::Uri;
+pub use uri::{FilePathError, NormalizedUri, PathStyle, Uri};
 mod uri;
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary_helpers;
+
 // Large enough to contain any enumeration name defined in this crate
 type PascalCaseBuf = [u8; 32];
 const fn fmt_pascal_case_const(name: &str) -> (PascalCaseBuf, usize) {
@@ -67,6 +70,33 @@ macro_rules! lsp_enum {
             )*
         }
 
+        impl $typ {
+            /// Whether this is one of the values defined by the protocol.
+            #[allow(dead_code)]
+            pub(crate) fn is_known(&self) -> bool {
+                matches!(*self, $(Self::$name)|*)
+            }
+        }
+
+        /// Any integer, as the protocol allows values beyond the known ones, which are listed as
+        /// examples and named in the description.
+        #[cfg(feature = "schemars")]
+        impl schemars::JsonSchema for $typ {
+            fn schema_name() -> std::borrow::Cow<'static, str> {
+                stringify!($typ).into()
+            }
+
+            fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+                let known: &[String] = &[$(format!("{} ({:?})", Self::$name.0, Self::$name)),*];
+                schemars::json_schema!({
+                    "type": "integer",
+                    "format": "int32",
+                    "examples": [$(Self::$name.0),*],
+                    "description": format!("Known values: {}.", known.join(", ")),
+                })
+            }
+        }
+
         impl std::fmt::Debug for $typ {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 match *self {
@@ -195,12 +225,35 @@ pub use workspace_symbols::*;
 
 pub mod lsif;
 
+pub mod borrowed;
+pub mod capabilities_builder;
+pub mod capabilities_merge;
+pub mod client_capabilities;
+pub mod document_selector;
+pub mod downgrade;
+pub mod glob;
+#[cfg(feature = "lenient")]
+pub mod lenient;
+pub mod markup;
+pub mod notebook_store;
+pub mod position_mapping;
+pub mod registration_registry;
+pub mod response_merge;
+pub mod semantic_tokens_builder;
+pub mod semantic_tokens_delta;
+pub mod semantic_tokens_normalize;
+pub mod snippet;
+pub mod strict;
+pub mod text_document;
+
 mod trace;
 pub use trace::*;
 
 /* ----------------- Auxiliary types ----------------- */
 
 #[derive(Debug, Eq, Hash, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum NumberOrString {
     Number(i32),
@@ -210,6 +263,8 @@ pub enum NumberOrString {
 /* ----------------- Cancel support ----------------- */
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct CancelParams {
     /// The request id to cancel.
     pub id: NumberOrString,
@@ -237,6 +292,8 @@ pub type LSPArray = Vec<serde_json::Value>;
 #[derive(
     Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Default, Deserialize, Serialize, Hash,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Position {
     /// Line position in a document (zero-based).
     pub line: u32,
@@ -257,6 +314,8 @@ impl Position {
 /// A range in a text document expressed as (zero-based) start and end positions.
 /// A range is comparable to a selection in an editor. Therefore the end position is exclusive.
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Default, Deserialize, Serialize, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Range {
     /// The range's start position.
     pub start: Position,
@@ -272,6 +331,8 @@ impl Range {
 
 /// Represents a location inside a resource, such as a line inside a text file.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Location {
     pub uri: Uri,
     pub range: Range,
@@ -285,6 +346,8 @@ impl Location {
 
 /// Represents a link between a source and a target location.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct LocationLink {
     /// Span of the origin of this link.
@@ -309,7 +372,12 @@ pub struct LocationLink {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Hash, PartialOrd, Clone, Deserialize, Serialize)]
-pub struct PositionEncodingKind(std::borrow::Cow<'static, str>);
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PositionEncodingKind(
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::cow_str))]
+    std::borrow::Cow<'static, str>,
+);
 
 impl PositionEncodingKind {
     /// Character offsets count UTF-8 code units.
@@ -352,6 +420,8 @@ impl From<&'static str> for PositionEncodingKind {
 /// Represents a diagnostic, such as a compiler error or warning.
 /// Diagnostic objects are only valid in the scope of a resource.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Diagnostic {
     /// The range at which the message applies.
@@ -394,10 +464,13 @@ pub struct Diagnostic {
     ///
     /// @since 3.16.0
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::option_value))]
     pub data: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CodeDescription {
     pub href: Uri,
@@ -443,6 +516,7 @@ impl Diagnostic {
 
 /// The protocol currently supports the following diagnostic severities:
 #[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(transparent)]
 pub struct DiagnosticSeverity(i32);
 lsp_enum! {
@@ -462,6 +536,8 @@ impl DiagnosticSeverity {
 /// should be used to point to code locations that cause or related to a
 /// diagnostics, e.g when duplicating a symbol in a scope.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DiagnosticRelatedInformation {
     /// The location of this related diagnostic information.
     pub location: Location,
@@ -472,6 +548,7 @@ pub struct DiagnosticRelatedInformation {
 
 /// The diagnostic tags.
 #[derive(Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(transparent)]
 pub struct DiagnosticTag(i32);
 lsp_enum! {
@@ -493,6 +570,8 @@ impl DiagnosticTag {
 /// Alternatively the tool extension code could handle the command.
 /// The protocol currently doesn’t specify a set of well-known commands.
 #[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Command {
     /// Title of the command, like `save`.
     pub title: String,
@@ -501,6 +580,7 @@ pub struct Command {
     /// Arguments that the command handler should be
     /// invoked with.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::option_vec_value))]
     pub arguments: Option<Vec<Value>>,
 }
 
@@ -520,6 +600,8 @@ impl Command {
 /// Execution wise text edits should applied from the bottom to the top of the text document. Overlapping text edits
 /// are not supported.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct TextEdit {
     /// The range of the text document to be manipulated. To insert
@@ -546,6 +628,8 @@ pub type ChangeAnnotationIdentifier = String;
 ///
 /// @since 3.16.0
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct AnnotatedTextEdit {
     #[serde(flatten)]
@@ -561,6 +645,8 @@ pub struct AnnotatedTextEdit {
 /// applied move the document to version Si+1. So the creator of a `TextDocumentEdit` doesn't need to
 /// sort the array or do any kind of ordering. However the edits must be non overlapping.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct TextDocumentEdit {
     /// The text document to change.
@@ -570,13 +656,40 @@ pub struct TextDocumentEdit {
     ///
     /// @since 3.16.0 - support for AnnotatedTextEdit. This is guarded by the
     /// client capability `workspace.workspaceEdit.changeAnnotationSupport`
+    #[serde(deserialize_with = "deserialize_text_edits")]
     pub edits: Vec<OneOf<TextEdit, AnnotatedTextEdit>>,
 }
 
+/// Every `AnnotatedTextEdit` is also a valid `TextEdit`, which `OneOf` would try first, so the
+/// annotated variant is tried first here.
+fn deserialize_text_edits<'de, D>(
+    deserializer: D,
+) -> Result<Vec<OneOf<TextEdit, AnnotatedTextEdit>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Edit {
+        Annotated(AnnotatedTextEdit),
+        Plain(TextEdit),
+    }
+
+    Ok(Vec::<Edit>::deserialize(deserializer)?
+        .into_iter()
+        .map(|edit| match edit {
+            Edit::Annotated(edit) => OneOf::Right(edit),
+            Edit::Plain(edit) => OneOf::Left(edit),
+        })
+        .collect())
+}
+
 /// Additional information that describes document changes.
 ///
 /// @since 3.16.0
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ChangeAnnotation {
     /// A human-readable string describing the actual change. The string
@@ -595,6 +708,8 @@ pub struct ChangeAnnotation {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ChangeAnnotationWorkspaceEditClientCapabilities {
     /// Whether the client groups edits with equal labels into tree nodes,
@@ -606,6 +721,8 @@ pub struct ChangeAnnotationWorkspaceEditClientCapabilities {
 
 /// Options to create a file.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CreateFileOptions {
     /// Overwrite existing file. Overwrite wins over `ignoreIfExists`
@@ -618,6 +735,8 @@ pub struct CreateFileOptions {
 
 /// Create file operation
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CreateFile {
     /// The resource to create.
@@ -635,6 +754,8 @@ pub struct CreateFile {
 
 /// Rename file options
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct RenameFileOptions {
     /// Overwrite target if existing. Overwrite wins over `ignoreIfExists`
@@ -647,6 +768,8 @@ pub struct RenameFileOptions {
 
 /// Rename file operation
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct RenameFile {
     /// The old (existing) location.
@@ -666,6 +789,8 @@ pub struct RenameFile {
 
 /// Delete file options
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DeleteFileOptions {
     /// Delete the content recursively if a folder is denoted.
@@ -684,6 +809,8 @@ pub struct DeleteFileOptions {
 
 /// Delete file operation
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DeleteFile {
     /// The file to delete.
@@ -698,6 +825,8 @@ pub struct DeleteFile {
 /// If the client can handle versioned document edits and if `documentChanges` are present,
 /// the latter are preferred over `changes`.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceEdit {
     /// Holds changes to existing resources.
@@ -731,6 +860,8 @@ pub struct WorkspaceEdit {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum DocumentChanges {
     Edits(Vec<TextDocumentEdit>),
@@ -753,6 +884,8 @@ pub enum DocumentChanges {
 // }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged, rename_all = "lowercase")]
 pub enum DocumentChangeOperation {
     Op(ResourceOp),
@@ -760,6 +893,8 @@ pub enum DocumentChangeOperation {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(tag = "kind", rename_all = "lowercase")]
 pub enum ResourceOp {
     Create(CreateFile),
@@ -770,12 +905,16 @@ pub enum ResourceOp {
 pub type DidChangeConfigurationClientCapabilities = DynamicRegistrationClientCapabilities;
 
 #[derive(Debug, Default, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ConfigurationParams {
     pub items: Vec<ConfigurationItem>,
 }
 
 #[derive(Debug, Default, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ConfigurationItem {
     /// The scope to get the configuration section for.
@@ -788,6 +927,8 @@ pub struct ConfigurationItem {
 }
 
 impl WorkspaceEdit {
+    // `Uri` caches parsed data in a `Cell` that does not take part in its `Hash` and `Eq`.
+    #[allow(clippy::mutable_key_type)]
     pub fn new(changes: HashMap<Uri, Vec<TextEdit>>) -> WorkspaceEdit {
         WorkspaceEdit {
             changes: Some(changes),
@@ -799,6 +940,8 @@ impl WorkspaceEdit {
 
 /// Text documents are identified using a URI. On the protocol level, URIs are passed as strings.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct TextDocumentIdentifier {
     // !!!!!! Note:
     // In the spec VersionedTextDocumentIdentifier extends TextDocumentIdentifier
@@ -816,6 +959,8 @@ impl TextDocumentIdentifier {
 
 /// An item to transfer a text document from the client to the server.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct TextDocumentItem {
     /// The text document's URI.
@@ -845,6 +990,8 @@ impl TextDocumentItem {
 
 /// An identifier to denote a specific version of a text document. This information usually flows from the client to the server.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VersionedTextDocumentIdentifier {
     // This field was "mixed-in" from TextDocumentIdentifier
     /// The text document's URI.
@@ -865,6 +1012,8 @@ impl VersionedTextDocumentIdentifier {
 
 /// An identifier which optionally denotes a specific version of a text document. This information usually flows from the server to the client
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct OptionalVersionedTextDocumentIdentifier {
     // This field was "mixed-in" from TextDocumentIdentifier
     /// The text document's URI.
@@ -893,6 +1042,8 @@ impl OptionalVersionedTextDocumentIdentifier {
 
 /// A parameter literal used in requests to pass a text document and a position inside that document.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct TextDocumentPositionParams {
     // !!!!!! Note:
@@ -925,6 +1076,8 @@ impl TextDocumentPositionParams {
 /// { language: 'typescript', scheme: 'file' }
 /// { language: 'json', pattern: '**/package.json' }
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DocumentFilter {
     /// A language id, like `typescript`.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -945,6 +1098,8 @@ pub type DocumentSelector = Vec<DocumentFilter>;
 // ========================= Actual Protocol =========================
 
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct InitializeParams {
     /// The process Id of the parent process that started
@@ -967,6 +1122,7 @@ pub struct InitializeParams {
 
     /// User provided initialization options.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::option_value))]
     pub initialization_options: Option<Value>,
 
     /// The capabilities provided by the client (editor or tool)
@@ -1006,6 +1162,8 @@ pub struct InitializeParams {
 }
 
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ClientInfo {
     /// The name of the client as defined by the client.
     pub name: String,
@@ -1015,9 +1173,13 @@ pub struct ClientInfo {
 }
 
 #[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct InitializedParams {}
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct GenericRegistrationOptions {
     #[serde(flatten)]
     pub text_document_registration_options: TextDocumentRegistrationOptions,
@@ -1030,12 +1192,16 @@ pub struct GenericRegistrationOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct GenericOptions {
     #[serde(flatten)]
     pub work_done_progress_options: WorkDoneProgressOptions,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct GenericParams {
     #[serde(flatten)]
     pub text_document_position_params: TextDocumentPositionParams,
@@ -1048,6 +1214,8 @@ pub struct GenericParams {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DynamicRegistrationClientCapabilities {
     /// This capability supports dynamic registration.
@@ -1056,6 +1224,8 @@ pub struct DynamicRegistrationClientCapabilities {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct GotoCapability {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1067,6 +1237,8 @@ pub struct GotoCapability {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceEditClientCapabilities {
     /// The client supports versioned document changes in `WorkspaceEdit`s
@@ -1100,6 +1272,8 @@ pub struct WorkspaceEditClientCapabilities {
 }
 
 #[derive(Debug, Eq, PartialEq, Deserialize, Serialize, Copy, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum ResourceOperationKind {
     Create,
@@ -1108,6 +1282,8 @@ pub enum ResourceOperationKind {
 }
 
 #[derive(Debug, Eq, PartialEq, Deserialize, Serialize, Copy, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum FailureHandlingKind {
     Abort,
@@ -1118,6 +1294,7 @@ pub enum FailureHandlingKind {
 
 /// A symbol kind.
 #[derive(Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(transparent)]
 pub struct SymbolKind(i32);
 lsp_enum! {
@@ -1153,6 +1330,8 @@ impl SymbolKind {
 
 /// Specific capabilities for the `SymbolKind` in the `workspace/symbol` request.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SymbolKindCapability {
     /// The symbol kind values the client supports. When this
@@ -1168,6 +1347,8 @@ pub struct SymbolKindCapability {
 
 /// Workspace specific client capabilities.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceClientCapabilities {
     /// The client supports applying batch edits to the workspace by supporting
@@ -1244,6 +1425,8 @@ pub struct WorkspaceClientCapabilities {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct TextDocumentSyncClientCapabilities {
     /// Whether text document synchronization supports dynamic registration.
@@ -1266,6 +1449,8 @@ pub struct TextDocumentSyncClientCapabilities {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct PublishDiagnosticsClientCapabilities {
     /// Whether the clients accepts diagnostics with related information.
@@ -1304,6 +1489,9 @@ pub struct PublishDiagnosticsClientCapabilities {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "TagSupportFor{T}"))]
 #[serde(rename_all = "camelCase")]
 pub struct TagSupport<T> {
     /// The tags supported by the client.
@@ -1334,6 +1522,8 @@ impl<T> TagSupport<T> {
 
 /// Text document specific client capabilities.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct TextDocumentClientCapabilities {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1480,6 +1670,8 @@ pub struct TextDocumentClientCapabilities {
 
 /// Where ClientCapabilities are currently empty:
 #[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ClientCapabilities {
     /// Workspace specific client capabilities.
@@ -1513,10 +1705,13 @@ pub struct ClientCapabilities {
 
     /// Experimental client capabilities.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::option_value))]
     pub experimental: Option<Value>,
 }
 
 #[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct GeneralClientCapabilities {
     /// Client capabilities specific to regular expressions.
@@ -1567,6 +1762,8 @@ pub struct GeneralClientCapabilities {
 ///
 /// @since 3.17.0
 #[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct StaleRequestSupportClientCapabilities {
     /// The client will actively cancel the request.
@@ -1579,6 +1776,8 @@ pub struct StaleRequestSupportClientCapabilities {
 }
 
 #[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct RegularExpressionsClientCapabilities {
     /// The engine's name.
@@ -1590,6 +1789,8 @@ pub struct RegularExpressionsClientCapabilities {
 }
 
 #[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct MarkdownClientCapabilities {
     /// The name of the parser.
@@ -1608,6 +1809,8 @@ pub struct MarkdownClientCapabilities {
 }
 
 #[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct InitializeResult {
     /// The capabilities the language server provides.
@@ -1626,6 +1829,8 @@ pub struct InitializeResult {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ServerInfo {
     /// The name of the server as defined by the server.
     pub name: String,
@@ -1635,6 +1840,8 @@ pub struct ServerInfo {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct InitializeError {
     /// Indicates whether the client execute the following retry logic:
     ///
@@ -1648,6 +1855,7 @@ pub struct InitializeError {
 
 /// Defines how the host (editor) should sync document changes to the language server.
 #[derive(Eq, PartialEq, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(transparent)]
 pub struct TextDocumentSyncKind(i32);
 lsp_enum! {
@@ -1668,6 +1876,8 @@ pub type ExecuteCommandClientCapabilities = DynamicRegistrationClientCapabilitie
 
 /// Execute command options.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExecuteCommandOptions {
     /// The commands to be executed on the server
     pub commands: Vec<String>,
@@ -1678,6 +1888,8 @@ pub struct ExecuteCommandOptions {
 
 /// Save options.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SaveOptions {
     /// The client is supposed to include the content on save.
@@ -1686,6 +1898,8 @@ pub struct SaveOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum TextDocumentSyncSaveOptions {
     Supported(bool),
@@ -1705,6 +1919,8 @@ impl From<bool> for TextDocumentSyncSaveOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct TextDocumentSyncOptions {
     /// Open and close notifications are sent to the server.
@@ -1730,6 +1946,9 @@ pub struct TextDocumentSyncOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "OneOf{A}Or{B}"))]
 #[serde(untagged)]
 pub enum OneOf<A, B> {
     Left(A),
@@ -1737,6 +1956,8 @@ pub enum OneOf<A, B> {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum TextDocumentSyncCapability {
     Kind(TextDocumentSyncKind),
@@ -1756,6 +1977,8 @@ impl From<TextDocumentSyncKind> for TextDocumentSyncCapability {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum ImplementationProviderCapability {
     Simple(bool),
@@ -1775,6 +1998,8 @@ impl From<bool> for ImplementationProviderCapability {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum TypeDefinitionProviderCapability {
     Simple(bool),
@@ -1794,6 +2019,8 @@ impl From<bool> for TypeDefinitionProviderCapability {
 }
 
 #[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ServerCapabilities {
     /// The position encoding the server picked from the encodings offered
@@ -1815,7 +2042,11 @@ pub struct ServerCapabilities {
     /// Defines how notebook documents are synced.
     ///
     /// @since 3.17.0
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        deserialize_with = "deserialize_notebook_document_sync",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub notebook_document_sync:
         Option<OneOf<NotebookDocumentSyncOptions, NotebookDocumentSyncRegistrationOptions>>,
 
@@ -1956,10 +2187,69 @@ pub struct ServerCapabilities {
 
     /// Experimental server capabilities.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::option_value))]
     pub experimental: Option<Value>,
 }
 
+/// `NotebookDocumentSyncOptions` would drop the `id` of registration options, and the registration
+/// options would match any options, so they are told apart by their `id`.
+fn deserialize_notebook_document_sync<'de, D>(
+    deserializer: D,
+) -> Result<
+    Option<OneOf<NotebookDocumentSyncOptions, NotebookDocumentSyncRegistrationOptions>>,
+    D::Error,
+>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(transparent)]
+    struct Sync(
+        #[serde(deserialize_with = "deserialize_sync")]
+        OneOf<NotebookDocumentSyncOptions, NotebookDocumentSyncRegistrationOptions>,
+    );
+
+    fn deserialize_sync<'de, D>(
+        deserializer: D,
+    ) -> Result<OneOf<NotebookDocumentSyncOptions, NotebookDocumentSyncRegistrationOptions>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserialize_registration_options(deserializer, OneOf::Right, |value| {
+            NotebookDocumentSyncOptions::deserialize(value).map(OneOf::Left)
+        })
+    }
+
+    Ok(Option::<Sync>::deserialize(deserializer)?.map(|Sync(sync)| sync))
+}
+
+/// Reads capabilities that are either registration options, or options read by `options`. Every
+/// field of registration options is optional, so they are only read when the value has an `id` or
+/// a `documentSelector`, which options do not have.
+pub(crate) fn deserialize_registration_options<'de, D, R, T>(
+    deserializer: D,
+    registration_options: fn(R) -> T,
+    options: impl FnOnce(serde_json::Value) -> serde_json::Result<T>,
+) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    R: serde::de::DeserializeOwned,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    let is_registration = value
+        .as_object()
+        .is_some_and(|object| object.contains_key("id") || object.contains_key("documentSelector"));
+    if is_registration {
+        R::deserialize(value).map(registration_options)
+    } else {
+        options(value)
+    }
+    .map_err(serde::de::Error::custom)
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceServerCapabilities {
     /// The server supports workspace folder.
@@ -1972,6 +2262,8 @@ pub struct WorkspaceServerCapabilities {
 
 /// General parameters to to register for a capability.
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Registration {
     /// The id used to register the request. The id can be used to deregister
@@ -1983,10 +2275,13 @@ pub struct Registration {
 
     /// Options necessary for the registration.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::option_value))]
     pub register_options: Option<Value>,
 }
 
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct RegistrationParams {
     pub registrations: Vec<Registration>,
 }
@@ -1994,6 +2289,8 @@ pub struct RegistrationParams {
 /// Since most of the registration options require to specify a document selector there is a base
 /// interface that can be used.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct TextDocumentRegistrationOptions {
     /// A document selector to identify the scope of the registration. If set to null
@@ -2002,6 +2299,8 @@ pub struct TextDocumentRegistrationOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum DeclarationCapability {
     Simple(bool),
@@ -2010,6 +2309,8 @@ pub enum DeclarationCapability {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DeclarationRegistrationOptions {
     #[serde(flatten)]
@@ -2023,6 +2324,8 @@ pub struct DeclarationRegistrationOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DeclarationOptions {
     #[serde(flatten)]
@@ -2030,6 +2333,8 @@ pub struct DeclarationOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct StaticRegistrationOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -2037,6 +2342,8 @@ pub struct StaticRegistrationOptions {
 }
 
 #[derive(Debug, Default, Eq, PartialEq, Clone, Deserialize, Serialize, Copy)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct WorkDoneProgressOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -2044,6 +2351,8 @@ pub struct WorkDoneProgressOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DocumentFormattingOptions {
     #[serde(flatten)]
@@ -2051,6 +2360,8 @@ pub struct DocumentFormattingOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DocumentRangeFormattingOptions {
     #[serde(flatten)]
@@ -2058,6 +2369,8 @@ pub struct DocumentRangeFormattingOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DefinitionOptions {
     #[serde(flatten)]
@@ -2065,6 +2378,8 @@ pub struct DefinitionOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DocumentSymbolOptions {
     /// A human-readable string that is shown when multiple outlines trees are
@@ -2079,6 +2394,8 @@ pub struct DocumentSymbolOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ReferencesOptions {
     #[serde(flatten)]
@@ -2086,6 +2403,8 @@ pub struct ReferencesOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DocumentHighlightOptions {
     #[serde(flatten)]
@@ -2093,6 +2412,8 @@ pub struct DocumentHighlightOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceSymbolOptions {
     #[serde(flatten)]
@@ -2107,6 +2428,8 @@ pub struct WorkspaceSymbolOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct StaticTextDocumentRegistrationOptions {
     /// A document selector to identify the scope of the registration. If set to null
@@ -2119,6 +2442,8 @@ pub struct StaticTextDocumentRegistrationOptions {
 
 /// General parameters to unregister a capability.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Unregistration {
     /// The id used to unregister the request or notification. Usually an id
     /// provided during the register request.
@@ -2129,17 +2454,24 @@ pub struct Unregistration {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct UnregistrationParams {
     pub unregisterations: Vec<Unregistration>,
 }
 
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DidChangeConfigurationParams {
     /// The actual changed settings
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::value))]
     pub settings: Value,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DidOpenTextDocumentParams {
     /// The document that was opened.
@@ -2147,6 +2479,8 @@ pub struct DidOpenTextDocumentParams {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DidChangeTextDocumentParams {
     /// The document that did change. The version number points
@@ -2160,6 +2494,8 @@ pub struct DidChangeTextDocumentParams {
 /// An event describing a change to a text document. If range and rangeLength are omitted
 /// the new text is considered to be the full content of the document.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct TextDocumentContentChangeEvent {
     /// The range of the document that changed.
@@ -2180,6 +2516,8 @@ pub struct TextDocumentContentChangeEvent {
 ///
 /// Extends TextDocumentRegistrationOptions
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct TextDocumentChangeRegistrationOptions {
     /// A document selector to identify the scope of the registration. If set to null
@@ -2193,6 +2531,8 @@ pub struct TextDocumentChangeRegistrationOptions {
 
 /// The parameters send in a will save text document notification.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct WillSaveTextDocumentParams {
     /// The document that will be saved.
@@ -2204,6 +2544,7 @@ pub struct WillSaveTextDocumentParams {
 
 /// Represents reasons why a text document is saved.
 #[derive(Copy, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(transparent)]
 pub struct TextDocumentSaveReason(i32);
 lsp_enum! {
@@ -2221,6 +2562,8 @@ impl TextDocumentSaveReason {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DidCloseTextDocumentParams {
     /// The document that was closed.
@@ -2228,6 +2571,8 @@ pub struct DidCloseTextDocumentParams {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DidSaveTextDocumentParams {
     /// The document that was saved.
@@ -2240,6 +2585,8 @@ pub struct DidSaveTextDocumentParams {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct TextDocumentSaveRegistrationOptions {
     /// The client is supposed to include the content on save.
@@ -2251,6 +2598,8 @@ pub struct TextDocumentSaveRegistrationOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DidChangeWatchedFilesClientCapabilities {
     /// Did change watched files notification supports dynamic registration.
@@ -2268,6 +2617,8 @@ pub struct DidChangeWatchedFilesClientCapabilities {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DidChangeWatchedFilesParams {
     /// The actual file events.
     pub changes: Vec<FileEvent>,
@@ -2275,6 +2626,7 @@ pub struct DidChangeWatchedFilesParams {
 
 /// The file event type.
 #[derive(Eq, PartialEq, Hash, Copy, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(transparent)]
 pub struct FileChangeType(i32);
 lsp_enum! {
@@ -2292,6 +2644,8 @@ impl FileChangeType {
 
 /// An event describing a file change.
 #[derive(Debug, Eq, Hash, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct FileEvent {
     /// The file's URI.
     pub uri: Uri,
@@ -2309,12 +2663,16 @@ impl FileEvent {
 
 /// Describe options to be used when registered for text document change events.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DidChangeWatchedFilesRegistrationOptions {
     /// The watchers to register.
     pub watchers: Vec<FileSystemWatcher>,
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct FileSystemWatcher {
     /// The glob pattern to watch. See {@link GlobPattern glob pattern}
@@ -2333,6 +2691,8 @@ pub struct FileSystemWatcher {
 ///
 /// @since 3.17.0
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum GlobPattern {
     String(Pattern),
@@ -2359,6 +2719,8 @@ impl From<RelativePattern> for GlobPattern {
 ///
 /// @since 3.17.0
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct RelativePattern {
     /// A workspace folder or a base URI to which this pattern will be matched
@@ -2417,7 +2779,32 @@ impl serde::Serialize for WatchKind {
     }
 }
 
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for WatchKind {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "WatchKind".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "A bit set of `Create` (1), `Change` (2) and `Delete` (4).",
+            "type": "integer",
+            "minimum": 0,
+            "maximum": WatchKind::all().bits(),
+        })
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for WatchKind {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(WatchKind::from_bits_truncate(u8::arbitrary(u)?))
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PublishDiagnosticsParams {
     /// The URI for which diagnostic information is reported.
     pub uri: Uri,
@@ -2445,6 +2832,8 @@ impl PublishDiagnosticsParams {
 }
 
 #[derive(Debug, Eq, PartialEq, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum Documentation {
     String(String),
@@ -2462,6 +2851,8 @@ pub enum Documentation {
 /// VALUE
 /// ```
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum MarkedString {
     String(String),
@@ -2469,6 +2860,8 @@ pub enum MarkedString {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct LanguageString {
     pub language: String,
     pub value: String,
@@ -2488,6 +2881,8 @@ impl MarkedString {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct GotoDefinitionParams {
     #[serde(flatten)]
@@ -2502,6 +2897,8 @@ pub struct GotoDefinitionParams {
 
 /// GotoDefinition response can be single location, or multiple Locations or a link.
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum GotoDefinitionResponse {
     Scalar(Location),
@@ -2528,11 +2925,14 @@ impl From<Vec<LocationLink>> for GotoDefinitionResponse {
 }
 
 #[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExecuteCommandParams {
     /// The identifier of the actual command handler.
     pub command: String,
     /// Arguments that the command should be invoked with.
     #[serde(default)]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::vec_value))]
     pub arguments: Vec<Value>,
 
     #[serde(flatten)]
@@ -2541,6 +2941,8 @@ pub struct ExecuteCommandParams {
 
 /// Execute command registration options.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExecuteCommandRegistrationOptions {
     /// The commands to be executed on the server
     pub commands: Vec<String>,
@@ -2550,6 +2952,8 @@ pub struct ExecuteCommandRegistrationOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ApplyWorkspaceEditParams {
     /// An optional label of the workspace edit. This label is
@@ -2563,6 +2967,8 @@ pub struct ApplyWorkspaceEditParams {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ApplyWorkspaceEditResponse {
     /// Indicates whether the edit was applied or not.
@@ -2588,6 +2994,8 @@ pub struct ApplyWorkspaceEditResponse {
 /// Please note that `MarkupKinds` must not start with a `$`. This kinds
 /// are reserved for internal usage.
 #[derive(Debug, Eq, PartialEq, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum MarkupKind {
     /// Plain text is supported as a content format
@@ -2620,6 +3028,8 @@ pub enum MarkupKind {
 /// Please *Note* that clients might sanitize the return markdown. A client could decide to
 /// remove HTML from the markdown to avoid script execution.
 #[derive(Debug, Eq, PartialEq, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MarkupContent {
     pub kind: MarkupKind,
     pub value: String,
@@ -2627,6 +3037,8 @@ pub struct MarkupContent {
 
 /// A parameter literal used to pass a partial result token.
 #[derive(Debug, Eq, PartialEq, Default, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct PartialResultParams {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -2637,6 +3049,7 @@ pub struct PartialResultParams {
 ///
 /// @since 3.16.0
 #[derive(Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(transparent)]
 pub struct SymbolTag(i32);
 lsp_enum! {
@@ -2749,6 +3162,79 @@ mod tests {
         serde_json::from_str::<InitializeParams>(r#"{ "capabilities": {} }"#).unwrap();
     }
 
+    #[test]
+    fn test_registration_options_capabilities() {
+        test_serialization(
+            &DiagnosticServerCapabilities::Options(DiagnosticOptions {
+                inter_file_dependencies: true,
+                ..Default::default()
+            }),
+            r#"{"interFileDependencies":true,"workspaceDiagnostics":false}"#,
+        );
+        test_serialization(
+            &ColorProviderCapability::ColorProvider(ColorProviderOptions {}),
+            "{}",
+        );
+        test_serialization(
+            &ColorProviderCapability::Options(StaticTextDocumentColorProviderOptions {
+                document_selector: None,
+                id: Some("color".to_string()),
+            }),
+            r#"{"documentSelector":null,"id":"color"}"#,
+        );
+        test_serialization(
+            &InlayHintServerCapabilities::RegistrationOptions(InlayHintRegistrationOptions {
+                inlay_hint_options: InlayHintOptions::default(),
+                text_document_registration_options: TextDocumentRegistrationOptions {
+                    document_selector: None,
+                },
+                static_registration_options: StaticRegistrationOptions::default(),
+            }),
+            r#"{"documentSelector":null}"#,
+        );
+        test_serialization(
+            &ServerCapabilities {
+                notebook_document_sync: Some(OneOf::Left(NotebookDocumentSyncOptions {
+                    notebook_selector: vec![],
+                    save: None,
+                })),
+                ..ServerCapabilities::default()
+            },
+            r#"{"notebookDocumentSync":{"notebookSelector":[]}}"#,
+        );
+        test_serialization(
+            &ServerCapabilities {
+                notebook_document_sync: Some(OneOf::Right(
+                    NotebookDocumentSyncRegistrationOptions {
+                        notebook_selector: vec![],
+                        save: None,
+                        id: Some("notebooks".to_string()),
+                    },
+                )),
+                ..ServerCapabilities::default()
+            },
+            r#"{"notebookDocumentSync":{"notebookSelector":[],"id":"notebooks"}}"#,
+        );
+    }
+
+    #[test]
+    fn test_text_document_edit_deserialization() {
+        let range = Range::new(Position::new(0, 0), Position::new(0, 1));
+        test_deserialization(
+            r#"{"textDocument":{"uri":"file:///a.rs","version":null},"edits":[{"range":{"start":{"line":0,"character":0},"end":{"line":0,"character":1}},"newText":"x","annotationId":"rename"}]}"#,
+            &TextDocumentEdit {
+                text_document: OptionalVersionedTextDocumentIdentifier {
+                    uri: "file:///a.rs".parse().unwrap(),
+                    version: None,
+                },
+                edits: vec![OneOf::Right(AnnotatedTextEdit {
+                    text_edit: TextEdit::new(range, "x".to_string()),
+                    annotation_id: "rename".to_string(),
+                })],
+            },
+        );
+    }
+
     #[test]
     fn test_watch_kind() {
         test_serialization(&WatchKind::Create, "1");
@@ -2759,6 +3245,23 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_lsp_enum_schema() {
+        let schema = serde_json::to_value(schemars::schema_for!(DiagnosticSeverity)).unwrap();
+        assert_eq!(schema["type"], "integer");
+        assert_eq!(schema["format"], "int32");
+        assert_eq!(schema["examples"], serde_json::json!([1, 2, 3, 4]));
+        assert_eq!(
+            schema["description"],
+            "Known values: 1 (Error), 2 (Warning), 3 (Information), 4 (Hint)."
+        );
+        assert!(schema.get("oneOf").is_none());
+
+        let schema = serde_json::to_value(schemars::schema_for!(WatchKind)).unwrap();
+        assert_eq!(schema["maximum"], 7);
+    }
+
     #[test]
     fn test_resource_operation_kind() {
         test_serialization(
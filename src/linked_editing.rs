@@ -9,6 +9,8 @@ use crate::{
 pub type LinkedEditingRangeClientCapabilities = DynamicRegistrationClientCapabilities;
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct LinkedEditingRangeOptions {
     #[serde(flatten)]
@@ -16,6 +18,8 @@ pub struct LinkedEditingRangeOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct LinkedEditingRangeRegistrationOptions {
     #[serde(flatten)]
@@ -28,7 +32,9 @@ pub struct LinkedEditingRangeRegistrationOptions {
     pub static_registration_options: StaticRegistrationOptions,
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum LinkedEditingRangeServerCapabilities {
     Simple(bool),
@@ -36,7 +42,23 @@ pub enum LinkedEditingRangeServerCapabilities {
     RegistrationOptions(LinkedEditingRangeRegistrationOptions),
 }
 
+impl<'de> Deserialize<'de> for LinkedEditingRangeServerCapabilities {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::deserialize_registration_options(deserializer, Self::RegistrationOptions, |value| {
+            match value {
+                serde_json::Value::Bool(simple) => Ok(Self::Simple(simple)),
+                value => LinkedEditingRangeOptions::deserialize(value).map(Self::Options),
+            }
+        })
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct LinkedEditingRangeParams {
     #[serde(flatten)]
@@ -47,6 +69,8 @@ pub struct LinkedEditingRangeParams {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct LinkedEditingRanges {
     /// A list of ranges that can be renamed together. The ranges must have
@@ -59,3 +83,31 @@ pub struct LinkedEditingRanges {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub word_pattern: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::test_deserialization, DocumentFilter};
+
+    #[test]
+    fn test_registration_options_deserialization() {
+        test_deserialization(
+            r#"{"documentSelector":[{"language":"rust"}],"id":"x"}"#,
+            &LinkedEditingRangeServerCapabilities::RegistrationOptions(
+                LinkedEditingRangeRegistrationOptions {
+                    text_document_registration_options: TextDocumentRegistrationOptions {
+                        document_selector: Some(vec![DocumentFilter {
+                            language: Some("rust".to_string()),
+                            scheme: None,
+                            pattern: None,
+                        }]),
+                    },
+                    linked_editing_range_options: LinkedEditingRangeOptions::default(),
+                    static_registration_options: StaticRegistrationOptions {
+                        id: Some("x".to_string()),
+                    },
+                },
+            ),
+        );
+    }
+}
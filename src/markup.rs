@@ -0,0 +1,614 @@
+/*!
+
+Building `MarkupContent` for hovers, documentation and tooltips.
+
+A [`MarkupBuilder`] collects headings, paragraphs, code and links, and renders them as markdown
+with all text escaped, or as plain text for clients that do not support markdown.
+
+The legacy `MarkedString` hover content converts to and from `MarkupContent`, and
+[`HoverContents::normalize`] turns any hover into a single `MarkupContent`.
+
+*/
+
+use crate::{
+    HoverContents, LanguageString, MarkdownClientCapabilities, MarkedString, MarkupContent,
+    MarkupKind,
+};
+
+/// Picks the kind of `MarkupContent` to send from a client's preferred formats, like
+/// `HoverClientCapabilities.content_format`. Clients that do not state a preference get plain
+/// text.
+pub fn preferred_markup_kind(formats: Option<&[MarkupKind]>) -> MarkupKind {
+    formats
+        .and_then(|formats| formats.first())
+        .cloned()
+        .unwrap_or(MarkupKind::PlainText)
+}
+
+/// Escapes `text` so markdown renders it literally.
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\r', '\n']);
+        let newline = &line[content.len()..];
+        let trimmed = content.trim_start_matches([' ', '\t']);
+        if trimmed.is_empty() {
+            // Blank lines separate paragraphs as in plain text.
+            escaped.push_str(line);
+            continue;
+        }
+        // Four columns of indentation would start a code block, and two spaces before a line
+        // ending a line break, so both are written as character references.
+        let indentation = &content[..content.len() - trimmed.len()];
+        if indentation.contains('\t') || indentation.len() >= 4 {
+            for c in indentation.chars() {
+                escaped.push_str(if c == '\t' { "&#9;" } else { "&#32;" });
+            }
+        } else {
+            escaped.push_str(indentation);
+        }
+        let body = trimmed.trim_end_matches(' ');
+        escape_line(&mut escaped, body);
+        let trailing = &trimmed[body.len()..];
+        if !newline.is_empty() && trailing.len() >= 2 {
+            escaped.push_str(&"&#32;".repeat(trailing.len()));
+        } else {
+            escaped.push_str(trailing);
+        }
+        escaped.push_str(newline);
+    }
+    escaped
+}
+
+fn escape_line(out: &mut String, line: &str) {
+    // Characters that only have a meaning at the start of a line.
+    if line.starts_with(['-', '+', '=']) {
+        out.push('\\');
+    } else if line.starts_with(|c: char| c.is_ascii_digit()) {
+        let digits = line
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(line.len());
+        if line[digits..].starts_with(['.', ')']) {
+            out.push_str(&line[..digits]);
+            out.push('\\');
+            out.push_str(&line[digits..digits + 1]);
+            escape_inline(out, line[digits + 1..].chars());
+            return;
+        }
+    }
+    escape_inline(out, line.chars());
+}
+
+fn escape_inline(out: &mut String, chars: impl Iterator<Item = char>) {
+    for c in chars {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '~' | '#' | '&' | '!'
+        ) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+}
+
+/// Returns a run of backticks longer than any run in `text`, at least `min` long.
+fn backtick_fence(text: &str, min: usize) -> String {
+    let mut longest = 0;
+    let mut current = 0;
+    for c in text.chars() {
+        if c == '`' {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    "`".repeat(min.max(longest + 1))
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+enum Inline {
+    Text(String),
+    Code(String),
+    Bold(String),
+    Italic(String),
+    Link { text: String, target: String },
+    Tag { tag: String, text: String },
+    LineBreak,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+enum Block {
+    Heading(u8, String),
+    Paragraph(Vec<Inline>),
+    Fence { language: String, code: String },
+    Rule,
+}
+
+/// Builds `MarkupContent` from structured parts.
+///
+/// Inline parts like [`Self::text`] and [`Self::code`] are added to the current paragraph, which
+/// [`Self::paragraph`] ends. All text is escaped when rendered as markdown.
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct MarkupBuilder {
+    blocks: Vec<Block>,
+}
+
+impl MarkupBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push_inline(mut self, inline: Inline) -> Self {
+        match self.blocks.last_mut() {
+            Some(Block::Paragraph(inlines)) => inlines.push(inline),
+            _ => self.blocks.push(Block::Paragraph(vec![inline])),
+        }
+        self
+    }
+
+    fn push_block(mut self, block: Block) -> Self {
+        // Drop an empty paragraph left by `paragraph()`.
+        if self.blocks.last() == Some(&Block::Paragraph(Vec::new())) {
+            self.blocks.pop();
+        }
+        self.blocks.push(block);
+        self
+    }
+
+    /// Adds a heading of `level`, from 1 to 6.
+    pub fn heading(self, level: u8, text: impl Into<String>) -> Self {
+        self.push_block(Block::Heading(level.clamp(1, 6), text.into()))
+    }
+
+    /// Adds text to the current paragraph.
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.push_inline(Inline::Text(text.into()))
+    }
+
+    /// Adds inline code to the current paragraph.
+    pub fn code(self, code: impl Into<String>) -> Self {
+        self.push_inline(Inline::Code(code.into()))
+    }
+
+    pub fn bold(self, text: impl Into<String>) -> Self {
+        self.push_inline(Inline::Bold(text.into()))
+    }
+
+    pub fn italic(self, text: impl Into<String>) -> Self {
+        self.push_inline(Inline::Italic(text.into()))
+    }
+
+    /// Adds a link to `target` to the current paragraph.
+    pub fn link(self, text: impl Into<String>, target: impl Into<String>) -> Self {
+        self.push_inline(Inline::Link {
+            text: text.into(),
+            target: target.into(),
+        })
+    }
+
+    /// Adds `text` wrapped in the HTML element `tag`, like `kbd`. The element is only rendered if
+    /// the client lists `tag` in `MarkdownClientCapabilities.allowed_tags`, otherwise just the
+    /// text is.
+    pub fn html(self, tag: impl Into<String>, text: impl Into<String>) -> Self {
+        self.push_inline(Inline::Tag {
+            tag: tag.into(),
+            text: text.into(),
+        })
+    }
+
+    /// Starts a new line within the current paragraph.
+    pub fn line_break(self) -> Self {
+        self.push_inline(Inline::LineBreak)
+    }
+
+    /// Ends the current paragraph.
+    pub fn paragraph(self) -> Self {
+        self.push_block(Block::Paragraph(Vec::new()))
+    }
+
+    /// Adds a fenced code block. `language` may be empty.
+    pub fn code_block(self, language: impl Into<String>, code: impl Into<String>) -> Self {
+        self.push_block(Block::Fence {
+            language: language.into(),
+            code: code.into(),
+        })
+    }
+
+    /// Adds a horizontal rule.
+    pub fn rule(self) -> Self {
+        self.push_block(Block::Rule)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks
+            .iter()
+            .all(|block| block == &Block::Paragraph(Vec::new()))
+    }
+
+    /// Renders the content in the first of the client's preferred `formats`, see
+    /// [`preferred_markup_kind`].
+    pub fn build(
+        &self,
+        formats: Option<&[MarkupKind]>,
+        markdown: Option<&MarkdownClientCapabilities>,
+    ) -> MarkupContent {
+        self.render(preferred_markup_kind(formats), markdown)
+    }
+
+    /// Renders the content as `kind`. `markdown` describes the client's markdown parser, and is
+    /// only used to decide which HTML elements may be rendered.
+    pub fn render(
+        &self,
+        kind: MarkupKind,
+        markdown: Option<&MarkdownClientCapabilities>,
+    ) -> MarkupContent {
+        let value = match kind {
+            MarkupKind::Markdown => {
+                let allowed_tags = markdown
+                    .and_then(|markdown| markdown.allowed_tags.as_deref())
+                    .unwrap_or(&[]);
+                self.render_markdown(allowed_tags)
+            }
+            MarkupKind::PlainText => self.render_plaintext(),
+        };
+        MarkupContent { kind, value }
+    }
+
+    fn render_markdown(&self, allowed_tags: &[String]) -> String {
+        let blocks: Vec<String> = self
+            .blocks
+            .iter()
+            .filter(|block| **block != Block::Paragraph(Vec::new()))
+            .map(|block| match block {
+                Block::Heading(level, text) => format!(
+                    "{} {}",
+                    "#".repeat(*level as usize),
+                    escape_markdown(text).replace('\n', " ")
+                ),
+                Block::Paragraph(inlines) => inlines
+                    .iter()
+                    .map(|inline| render_inline_markdown(inline, allowed_tags))
+                    .collect(),
+                Block::Fence { language, code } => {
+                    let fence = backtick_fence(code, 3);
+                    let newline = if code.ends_with('\n') { "" } else { "\n" };
+                    format!("{}{}\n{}{}{}", fence, language, code, newline, fence)
+                }
+                Block::Rule => "---".to_string(),
+            })
+            .collect();
+        blocks.join("\n\n")
+    }
+
+    fn render_plaintext(&self) -> String {
+        let blocks: Vec<String> = self
+            .blocks
+            .iter()
+            .filter(|block| **block != Block::Paragraph(Vec::new()))
+            .map(|block| match block {
+                Block::Heading(_, text) => text.clone(),
+                Block::Paragraph(inlines) => inlines.iter().map(render_inline_plaintext).collect(),
+                Block::Fence { code, .. } => code.trim_end_matches('\n').to_string(),
+                Block::Rule => "---".to_string(),
+            })
+            .collect();
+        blocks.join("\n\n")
+    }
+}
+
+fn render_inline_markdown(inline: &Inline, allowed_tags: &[String]) -> String {
+    match inline {
+        Inline::Text(text) => escape_markdown(text),
+        Inline::Code(code) => {
+            let fence = backtick_fence(code, 1);
+            // Code starting or ending with a backtick needs padding to be told apart from the fence.
+            let pad = if code.starts_with('`') || code.ends_with('`') {
+                " "
+            } else {
+                ""
+            };
+            format!("{}{}{}{}{}", fence, pad, code, pad, fence)
+        }
+        Inline::Bold(text) => format!("**{}**", escape_markdown(text)),
+        Inline::Italic(text) => format!("*{}*", escape_markdown(text)),
+        Inline::Link { text, target } => {
+            // A destination in angle brackets can hold anything but line endings and unescaped
+            // angle brackets.
+            let mut destination = String::with_capacity(target.len() + 2);
+            for c in target.chars() {
+                match c {
+                    '<' | '>' | '\\' => {
+                        destination.push('\\');
+                        destination.push(c);
+                    }
+                    '\n' => destination.push_str("%0A"),
+                    '\r' => destination.push_str("%0D"),
+                    c => destination.push(c),
+                }
+            }
+            format!("[{}](<{}>)", escape_markdown(text), destination)
+        }
+        Inline::Tag { tag, text } => {
+            if allowed_tags
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(tag))
+            {
+                format!("<{}>{}</{}>", tag, escape_markdown(text), tag)
+            } else {
+                escape_markdown(text)
+            }
+        }
+        Inline::LineBreak => "\\\n".to_string(),
+    }
+}
+
+fn render_inline_plaintext(inline: &Inline) -> String {
+    match inline {
+        Inline::Text(text)
+        | Inline::Code(text)
+        | Inline::Bold(text)
+        | Inline::Italic(text)
+        | Inline::Tag { text, .. } => text.clone(),
+        Inline::Link { text, target } if text == target || text.is_empty() => target.clone(),
+        Inline::Link { text, target } => format!("{} ({})", text, target),
+        Inline::LineBreak => "\n".to_string(),
+    }
+}
+
+/// Renders `code` as a fenced code block. The code is always followed by a newline before the
+/// closing fence, so [`parse_code_fence`] recovers it exactly.
+fn code_fence(language: &str, code: &str) -> String {
+    let fence = backtick_fence(code, 3);
+    format!("{}{}\n{}\n{}", fence, language, code, fence)
+}
+
+/// Recognises markdown consisting of a single fenced code block with a language, as produced by
+/// [`code_fence`].
+fn parse_code_fence(markdown: &str) -> Option<LanguageString> {
+    let fence_len = markdown.find(|c| c != '`')?;
+    if fence_len < 3 {
+        return None;
+    }
+    let fence = &markdown[..fence_len];
+    let (language, rest) = markdown[fence_len..].split_once('\n')?;
+    if language.is_empty() || language.contains(|c: char| c == '`' || c.is_whitespace()) {
+        return None;
+    }
+    let code = rest.strip_suffix(fence)?.strip_suffix('\n')?;
+    // The code must not contain a line that would close the fence early.
+    let closes_early = code
+        .lines()
+        .any(|line| line.trim_start().starts_with(fence));
+    if closes_early {
+        return None;
+    }
+    Some(LanguageString {
+        language: language.to_string(),
+        value: code.to_string(),
+    })
+}
+
+impl MarkedString {
+    /// Converts this legacy hover content into `MarkupContent`, of `kind` where possible.
+    ///
+    /// A `LanguageString` becomes a fenced code block in markdown and its bare code in plain
+    /// text. A markdown string is always returned as `MarkupKind::Markdown`, as markdown can not
+    /// be stripped without losing content, so callers that require plain text must check the
+    /// returned kind.
+    pub fn to_markup(&self, kind: MarkupKind) -> MarkupContent {
+        match (self, kind) {
+            (MarkedString::String(value), _) => MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: value.clone(),
+            },
+            (MarkedString::LanguageString(code), MarkupKind::Markdown) => MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: code_fence(&code.language, &code.value),
+            },
+            (MarkedString::LanguageString(code), MarkupKind::PlainText) => MarkupContent {
+                kind: MarkupKind::PlainText,
+                value: code.value.clone(),
+            },
+        }
+    }
+}
+
+impl From<MarkedString> for MarkupContent {
+    fn from(marked: MarkedString) -> Self {
+        marked.to_markup(MarkupKind::Markdown)
+    }
+}
+
+impl From<LanguageString> for MarkupContent {
+    fn from(code: LanguageString) -> Self {
+        MarkedString::LanguageString(code).into()
+    }
+}
+
+impl From<MarkupContent> for MarkedString {
+    /// Markdown consisting of a single fenced code block with a language becomes a
+    /// `LanguageString`, other markdown a markdown string. Plain text is escaped.
+    fn from(markup: MarkupContent) -> Self {
+        match markup.kind {
+            MarkupKind::Markdown => match parse_code_fence(&markup.value) {
+                Some(code) => MarkedString::LanguageString(code),
+                None => MarkedString::String(markup.value),
+            },
+            MarkupKind::PlainText => MarkedString::String(escape_markdown(&markup.value)),
+        }
+    }
+}
+
+/// Separates the parts of a `HoverContents::Array` once they are joined into one
+/// `MarkupContent`.
+const HOVER_SEPARATOR: &str = "\n\n";
+
+impl HoverContents {
+    /// Returns the contents as a single `MarkupContent`.
+    ///
+    /// `MarkupContent` is returned as is. Legacy `MarkedString`s are converted to markdown, and the
+    /// parts of an array are separated by blank lines.
+    pub fn normalize(self) -> MarkupContent {
+        match self {
+            HoverContents::Markup(markup) => markup,
+            HoverContents::Scalar(marked) => marked.into(),
+            HoverContents::Array(marked) => {
+                let parts: Vec<String> = marked
+                    .into_iter()
+                    .map(|marked| MarkupContent::from(marked).value)
+                    .collect();
+                MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: parts.join(HOVER_SEPARATOR),
+                }
+            }
+        }
+    }
+
+    /// Returns the contents in the legacy `MarkedString` form, for clients that do not support
+    /// `MarkupContent`.
+    pub fn into_legacy(self) -> HoverContents {
+        match self {
+            HoverContents::Markup(markup) => HoverContents::Scalar(markup.into()),
+            legacy => legacy,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builder() -> MarkupBuilder {
+        MarkupBuilder::new()
+            .heading(3, "fn *main*")
+            .code_block("rust", "fn main() {}")
+            .paragraph()
+            .text("Runs the ")
+            .code("`app`")
+            .text(", see ")
+            .link("the docs", "https://example.com/a b")
+            .text(". Press ")
+            .html("kbd", "F1")
+            .rule()
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let markdown = MarkdownClientCapabilities {
+            parser: "marked".into(),
+            version: None,
+            allowed_tags: Some(vec!["kbd".into()]),
+        };
+        assert_eq!(
+            builder().build(Some(&[MarkupKind::Markdown]), Some(&markdown)),
+            MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: "### fn \\*main\\*\n\n```rust\nfn main() {}\n```\n\nRuns the `` `app` ``, \
+                    see [the docs](<https://example.com/a b>). Press <kbd>F1</kbd>\n\n---"
+                    .into(),
+            }
+        );
+        assert!(builder()
+            .render(MarkupKind::Markdown, None)
+            .value
+            .contains("Press F1"));
+    }
+
+    #[test]
+    fn test_render_plaintext() {
+        assert_eq!(
+            builder().build(None, None),
+            MarkupContent {
+                kind: MarkupKind::PlainText,
+                value: "fn *main*\n\nfn main() {}\n\nRuns the `app`, see the docs \
+                    (https://example.com/a b). Press F1\n\n---"
+                    .into(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_escape_markdown() {
+        assert_eq!(
+            escape_markdown("1. a_b <br> [x](y)\n  - item\n2) c"),
+            "1\\. a\\_b \\<br\\> \\[x\\](y)\n  \\- item\n2\\) c"
+        );
+    }
+
+    #[test]
+    fn test_link_destination() {
+        assert_eq!(
+            MarkupBuilder::new()
+                .link("x", "https://example.com/a (b)\n<c>\\d")
+                .render(MarkupKind::Markdown, None)
+                .value,
+            "[x](<https://example.com/a (b)%0A\\<c\\>\\\\d>)"
+        );
+    }
+
+    #[test]
+    fn test_escape_markdown_whitespace() {
+        assert_eq!(
+            escape_markdown("Usage:\n    run *x*\n\tstop  \nend  "),
+            "Usage:\n&#32;&#32;&#32;&#32;run \\*x\\*\n&#9;stop&#32;&#32;\nend  "
+        );
+        assert_eq!(escape_markdown("a\n   \nb"), "a\n   \nb");
+    }
+    #[test]
+    fn test_marked_string_conversions() {
+        let code = MarkedString::from_language_code("rust".into(), "let s = \"```\";\n".into());
+        let markup = MarkupContent::from(code.clone());
+        assert_eq!(markup.value, "````rust\nlet s = \"```\";\n\n````");
+        assert_eq!(MarkedString::from(markup), code);
+        assert_eq!(
+            code.to_markup(MarkupKind::PlainText).value,
+            "let s = \"```\";\n"
+        );
+
+        let markdown = MarkedString::from_markdown("*a*".into());
+        assert_eq!(
+            markdown.to_markup(MarkupKind::PlainText),
+            MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: "*a*".into(),
+            }
+        );
+        assert_eq!(
+            MarkedString::from(MarkupContent::from(markdown.clone())),
+            markdown
+        );
+        assert_eq!(
+            MarkedString::from(MarkupContent {
+                kind: MarkupKind::PlainText,
+                value: "*a*".into(),
+            }),
+            MarkedString::from_markdown("\\*a\\*".into())
+        );
+    }
+
+    #[test]
+    fn test_normalize_hover_contents() {
+        let contents = HoverContents::Array(vec![
+            MarkedString::from_language_code("rust".into(), "fn f()".into()),
+            MarkedString::from_markdown("Docs".into()),
+        ]);
+        assert_eq!(
+            contents.normalize(),
+            MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: "```rust\nfn f()\n```\n\nDocs".into(),
+            }
+        );
+        let plain = MarkupContent {
+            kind: MarkupKind::PlainText,
+            value: "a".into(),
+        };
+        assert_eq!(HoverContents::Markup(plain.clone()).normalize(), plain);
+        assert_eq!(
+            HoverContents::Markup(plain).into_legacy(),
+            HoverContents::Scalar(MarkedString::from_markdown("a".into()))
+        );
+    }
+}
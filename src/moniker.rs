@@ -7,20 +7,37 @@ use crate::{
 
 pub type MonikerClientCapabilities = DynamicRegistrationClientCapabilities;
 
-#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum MonikerServerCapabilities {
     Options(MonikerOptions),
     RegistrationOptions(MonikerRegistrationOptions),
 }
 
+impl<'de> Deserialize<'de> for MonikerServerCapabilities {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::deserialize_registration_options(deserializer, Self::RegistrationOptions, |value| {
+            MonikerOptions::deserialize(value).map(Self::Options)
+        })
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MonikerOptions {
     #[serde(flatten)]
     pub work_done_progress_options: WorkDoneProgressOptions,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct MonikerRegistrationOptions {
     #[serde(flatten)]
@@ -32,6 +49,8 @@ pub struct MonikerRegistrationOptions {
 
 /// Moniker uniqueness level to define scope of the moniker.
 #[derive(Debug, Eq, PartialEq, Deserialize, Serialize, Copy, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum UniquenessLevel {
     /// The moniker is only unique inside a document
@@ -48,6 +67,8 @@ pub enum UniquenessLevel {
 
 /// The moniker kind.
 #[derive(Debug, Eq, PartialEq, Deserialize, Serialize, Copy, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum MonikerKind {
     /// The moniker represent a symbol that is imported into a project
@@ -60,6 +81,8 @@ pub enum MonikerKind {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct MonikerParams {
     #[serde(flatten)]
@@ -74,6 +97,8 @@ pub struct MonikerParams {
 
 /// Moniker definition to match LSIF 0.5 moniker definition.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Moniker {
     /// The scheme of the moniker. For example tsc or .Net
@@ -90,3 +115,28 @@ pub struct Moniker {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub kind: Option<MonikerKind>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::test_deserialization, DocumentFilter};
+
+    #[test]
+    fn test_registration_options_deserialization() {
+        test_deserialization(
+            r#"{"documentSelector":[{"language":"rust"}],"id":"x"}"#,
+            &MonikerServerCapabilities::RegistrationOptions(MonikerRegistrationOptions {
+                text_document_registration_options: TextDocumentRegistrationOptions {
+                    document_selector: Some(vec![DocumentFilter {
+                        language: Some("rust".to_string()),
+                        scheme: None,
+                        pattern: None,
+                    }]),
+                },
+                moniker_options: MonikerOptions {
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                },
+            }),
+        );
+    }
+}
@@ -9,6 +9,8 @@ pub use notification_params::*;
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct NotebookDocument {
     /// The notebook document's URI.
@@ -21,6 +23,7 @@ pub struct NotebookDocument {
     /// Additional metadata stored with the notebook
     /// document.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::option_object))]
     pub metadata: Option<LSPObject>,
     /// The cells of a notebook.
     pub cells: Vec<NotebookCell>,
@@ -34,6 +37,8 @@ pub struct NotebookDocument {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct NotebookCell {
     /// The cell's kind
@@ -42,6 +47,7 @@ pub struct NotebookCell {
     pub document: Uri,
     /// Additional metadata stored with the cell.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::option_object))]
     pub metadata: Option<LSPObject>,
     /// Additional execution summary information
     /// if supported by the client.
@@ -50,6 +56,8 @@ pub struct NotebookCell {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ExecutionSummary {
     /// A strict monotonically increasing value
@@ -63,6 +71,7 @@ pub struct ExecutionSummary {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Serialize_repr, Deserialize_repr)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u8)]
 pub enum NotebookCellKind {
     /// A markup-cell is formatted source that is used for display.
@@ -71,10 +80,29 @@ pub enum NotebookCellKind {
     Code = 2,
 }
 
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for NotebookCellKind {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "NotebookCellKind".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "integer",
+            "oneOf": [
+                { "const": NotebookCellKind::Markup as u8, "title": "Markup" },
+                { "const": NotebookCellKind::Code as u8, "title": "Code" },
+            ],
+        })
+    }
+}
+
 /// Capabilities specific to the notebook document support.
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct NotebookDocumentClientCapabilities {
     /// Capabilities specific to notebook document synchronization
@@ -87,6 +115,8 @@ pub struct NotebookDocumentClientCapabilities {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct NotebookDocumentSyncClientCapabilities {
     /// Whether implementation supports dynamic registration. If this is
@@ -115,6 +145,8 @@ pub struct NotebookDocumentSyncClientCapabilities {
 ///
 ///  @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct NotebookDocumentSyncOptions {
     /// The notebooks to be synced
@@ -129,6 +161,8 @@ pub struct NotebookDocumentSyncOptions {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct NotebookDocumentSyncRegistrationOptions {
     /// The notebooks to be synced
@@ -148,6 +182,8 @@ pub struct NotebookDocumentSyncRegistrationOptions {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct NotebookCellTextDocumentFilter {
     /// A filter that matches against the notebook
@@ -164,6 +200,8 @@ pub struct NotebookCellTextDocumentFilter {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase", untagged)]
 pub enum NotebookSelector {
     ByNotebook {
@@ -187,12 +225,16 @@ pub enum NotebookSelector {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct NotebookCellSelector {
     pub language: String,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum Notebook {
     String(String),
@@ -204,10 +246,13 @@ pub enum Notebook {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase", untagged)]
 pub enum NotebookDocumentFilter {
     ByType {
         /// The type of the enclosing notebook.
+        #[serde(rename = "notebookType")]
         notebook_type: String,
         /// A Uri [scheme](#Uri.scheme), like `file` or `untitled`.
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -218,6 +263,7 @@ pub enum NotebookDocumentFilter {
     },
     ByScheme {
         /// The type of the enclosing notebook.
+        #[serde(rename = "notebookType")]
         #[serde(skip_serializing_if = "Option::is_none")]
         notebook_type: Option<String>,
         /// A Uri [scheme](#Uri.scheme), like `file` or `untitled`.
@@ -228,6 +274,7 @@ pub enum NotebookDocumentFilter {
     },
     ByPattern {
         /// The type of the enclosing notebook.
+        #[serde(rename = "notebookType")]
         #[serde(skip_serializing_if = "Option::is_none")]
         notebook_type: Option<String>,
         /// A Uri [scheme](#Uri.scheme), like `file` or `untitled`.
@@ -252,6 +299,8 @@ mod notification_params {
     ///
     /// @since 3.17.0
     #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
     #[serde(rename_all = "camelCase")]
     pub struct DidOpenNotebookDocumentParams {
         /// The notebook document that got opened.
@@ -265,6 +314,8 @@ mod notification_params {
     ///
     /// @since 3.17.0
     #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
     #[serde(rename_all = "camelCase")]
     pub struct DidChangeNotebookDocumentParams {
         /// The notebook document that did change. The version number points
@@ -289,6 +340,8 @@ mod notification_params {
     ///
     /// @since 3.17.0
     #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
     #[serde(rename_all = "camelCase")]
     pub struct VersionedNotebookDocumentIdentifier {
         /// The version number of this notebook document.
@@ -301,10 +354,13 @@ mod notification_params {
     ///
     /// @since 3.17.0
     #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
     #[serde(rename_all = "camelCase")]
     pub struct NotebookDocumentChangeEvent {
         /// The changed meta data if any.
         #[serde(skip_serializing_if = "Option::is_none")]
+        #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::option_object))]
         pub metadata: Option<LSPObject>,
 
         /// Changes to cells
@@ -313,6 +369,8 @@ mod notification_params {
     }
 
     #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
     #[serde(rename_all = "camelCase")]
     pub struct NotebookDocumentCellChange {
         /// Changes to the cell structure to add or
@@ -331,6 +389,8 @@ mod notification_params {
     }
 
     #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
     #[serde(rename_all = "camelCase")]
     pub struct NotebookDocumentChangeTextContent {
         pub document: VersionedTextDocumentIdentifier,
@@ -338,6 +398,8 @@ mod notification_params {
     }
 
     #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
     #[serde(rename_all = "camelCase")]
     pub struct NotebookDocumentCellChangeStructure {
         /// The change to the cell array.
@@ -355,6 +417,8 @@ mod notification_params {
     ///
     /// @since 3.17.0
     #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
     #[serde(rename_all = "camelCase")]
     pub struct NotebookCellArrayChange {
         /// The start offset of the cell that changed.
@@ -372,6 +436,8 @@ mod notification_params {
     ///
     /// @since 3.17.0
     #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
     #[serde(rename_all = "camelCase")]
     pub struct DidSaveNotebookDocumentParams {
         /// The notebook document that got saved.
@@ -382,6 +448,8 @@ mod notification_params {
     ///
     /// @since 3.17.0
     #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
     #[serde(rename_all = "camelCase")]
     pub struct NotebookDocumentIdentifier {
         /// The notebook document's URI.
@@ -392,6 +460,8 @@ mod notification_params {
     ///
     /// @since 3.17.0
     #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
     #[serde(rename_all = "camelCase")]
     pub struct DidCloseNotebookDocumentParams {
         /// The notebook document that got closed.
@@ -402,3 +472,21 @@ mod notification_params {
         pub cell_text_documents: Vec<TextDocumentIdentifier>,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_deserialization;
+
+    #[test]
+    fn test_notebook_document_filter_deserialization() {
+        test_deserialization(
+            r#"{"notebookType":"jupyter-notebook","pattern":"**/*.ipynb"}"#,
+            &NotebookDocumentFilter::ByType {
+                notebook_type: "jupyter-notebook".to_string(),
+                scheme: None,
+                pattern: Some("**/*.ipynb".to_string()),
+            },
+        );
+    }
+}
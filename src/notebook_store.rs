@@ -0,0 +1,609 @@
+/*!
+
+An in-memory model of the notebooks a client has opened, kept in sync through the
+`notebookDocument/didOpen`, `didChange` and `didClose` notifications.
+
+*/
+
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    text_document::{LineIndex, TextDocument},
+    DidChangeNotebookDocumentParams, DidCloseNotebookDocumentParams, DidOpenNotebookDocumentParams,
+    NotebookCell, NotebookCellKind, NotebookDocument, NotebookDocumentCellChange, Position,
+    PositionEncodingKind, Range, Uri,
+};
+
+/// An error raised when a notebook change can not be applied to the store.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum NotebookStoreError {
+    /// The notebook has not been opened.
+    UnknownNotebook(Uri),
+    /// The cell text document is not part of the notebook.
+    UnknownCell(Uri),
+    /// A cell array change refers to cells past the end of the notebook.
+    InvalidCellArrayChange {
+        start: u32,
+        delete_count: u32,
+        cell_count: usize,
+    },
+}
+
+impl fmt::Display for NotebookStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotebookStoreError::UnknownNotebook(uri) => {
+                write!(f, "notebook `{}` is not open", uri.as_str())
+            }
+            NotebookStoreError::UnknownCell(uri) => {
+                write!(f, "cell document `{}` is not open", uri.as_str())
+            }
+            NotebookStoreError::InvalidCellArrayChange {
+                start,
+                delete_count,
+                cell_count,
+            } => write!(
+                f,
+                "can not delete {} cells at {} from a notebook with {} cells",
+                delete_count, start, cell_count
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NotebookStoreError {}
+
+/// A notebook together with the text documents of its cells.
+#[derive(Debug, Clone)]
+pub struct OpenNotebook {
+    encoding: PositionEncodingKind,
+    notebook: NotebookDocument,
+    documents: HashMap<Uri, TextDocument>,
+}
+
+impl OpenNotebook {
+    pub fn notebook(&self) -> &NotebookDocument {
+        &self.notebook
+    }
+
+    /// The cells of the notebook, in order, each with its text document if it has been opened.
+    pub fn cells(&self) -> impl Iterator<Item = (&NotebookCell, Option<&TextDocument>)> {
+        self.notebook
+            .cells
+            .iter()
+            .map(move |cell| (cell, self.documents.get(&cell.document)))
+    }
+
+    /// The text document of the cell identified by `uri`.
+    pub fn cell_document(&self, uri: &Uri) -> Option<&TextDocument> {
+        self.documents.get(uri)
+    }
+
+    /// Concatenates the text of all code cells into one virtual document.
+    pub fn concatenated_document(&self) -> ConcatenatedDocument {
+        self.concatenated_document_with(|cell| cell.kind == NotebookCellKind::Code)
+    }
+
+    /// Concatenates the text of the cells accepted by `filter` into one virtual document.
+    ///
+    /// Every cell starts on a new line, so a position in a cell maps to the virtual document by
+    /// offsetting its line only.
+    pub fn concatenated_document_with(
+        &self,
+        mut filter: impl FnMut(&NotebookCell) -> bool,
+    ) -> ConcatenatedDocument {
+        let mut text = String::new();
+        let mut cells = Vec::new();
+        let mut start_line = 0;
+        for (cell, document) in self.cells() {
+            let document = match document {
+                Some(document) if filter(cell) => document,
+                _ => continue,
+            };
+            let line_count = document.line_index().line_count();
+            let ends_with_newline = document.text().ends_with(['\n', '\r']);
+            cells.push(CellSpan {
+                uri: cell.document.clone(),
+                start_line,
+                line_count,
+                ends_with_newline,
+            });
+            text.push_str(document.text());
+            if ends_with_newline {
+                // The cell already ends with a line terminator, its empty last line is shared with
+                // the separator.
+                cells.last_mut().unwrap().line_count -= 1;
+                if document.text().ends_with('\r') {
+                    // Keep a following cell starting with `\n` from merging into a `\r\n`.
+                    text.push('\n');
+                }
+            } else {
+                text.push('\n');
+            }
+            start_line += cells.last().unwrap().line_count;
+        }
+        let line_index = LineIndex::new(&text, self.encoding.clone());
+        ConcatenatedDocument {
+            text,
+            line_index,
+            cells,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct CellSpan {
+    uri: Uri,
+    start_line: u32,
+    line_count: u32,
+    /// The empty line after the final line terminator of the cell is not counted, but a position
+    /// on it maps to the start of the next line of the virtual document.
+    ends_with_newline: bool,
+}
+
+impl CellSpan {
+    /// The position in the cell of `position`, which must be on a line of the cell or at the end
+    /// of the cell.
+    fn position(&self, position: Position) -> Option<Position> {
+        let line = position.line.checked_sub(self.start_line)?;
+        let at_end = line == self.line_count && self.ends_with_newline && position.character == 0;
+        (line < self.line_count || at_end).then(|| Position::new(line, position.character))
+    }
+}
+
+/// The text of several notebook cells concatenated into a single document, for servers that
+/// analyse a whole notebook at once.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ConcatenatedDocument {
+    text: String,
+    line_index: LineIndex,
+    cells: Vec<CellSpan>,
+}
+
+impl ConcatenatedDocument {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn line_index(&self) -> &LineIndex {
+        &self.line_index
+    }
+
+    /// The cell documents that make up this document, in order.
+    pub fn cell_uris(&self) -> impl Iterator<Item = &Uri> {
+        self.cells.iter().map(|cell| &cell.uri)
+    }
+
+    /// The range of the virtual document covered by the cell identified by `uri`.
+    pub fn cell_range(&self, uri: &Uri) -> Option<Range> {
+        let cell = self.cells.iter().find(|cell| cell.uri == *uri)?;
+        let last_line = cell.start_line + cell.line_count - 1;
+        Some(Range::new(
+            Position::new(cell.start_line, 0),
+            Position::new(last_line, self.line_index.line_len(last_line).unwrap_or(0)),
+        ))
+    }
+
+    /// Maps a position in the cell identified by `uri` to the virtual document.
+    pub fn to_concatenated(&self, uri: &Uri, position: Position) -> Option<Position> {
+        let cell = self.cells.iter().find(|cell| cell.uri == *uri)?;
+        let at_end =
+            position.line == cell.line_count && cell.ends_with_newline && position.character == 0;
+        if position.line >= cell.line_count && !at_end {
+            return None;
+        }
+        Some(Position::new(
+            cell.start_line + position.line,
+            position.character,
+        ))
+    }
+
+    /// Maps a position in the virtual document back to the cell it belongs to.
+    pub fn to_cell(&self, position: Position) -> Option<(&Uri, Position)> {
+        let i = match self
+            .cells
+            .binary_search_by_key(&position.line, |cell| cell.start_line)
+        {
+            Ok(i) => i,
+            Err(0) => return None,
+            Err(next) => next - 1,
+        };
+        let cell = &self.cells[i];
+        let line = position.line - cell.start_line;
+        if line >= cell.line_count {
+            return None;
+        }
+        Some((&cell.uri, Position::new(line, position.character)))
+    }
+
+    /// Maps a range in the virtual document back to a cell. Returns `None` if the range spans
+    /// more than one cell. A range may end at the start of the line after a cell ending with a line
+    /// terminator.
+    pub fn range_to_cell(&self, range: Range) -> Option<(&Uri, Range)> {
+        let (uri, start) = self.to_cell(range.start)?;
+        let cell = self.cells.iter().find(|cell| cell.uri == *uri)?;
+        let end = cell.position(range.end)?;
+        Some((uri, Range::new(start, end)))
+    }
+}
+
+/// Checks that `cells` can be applied to `notebook`: the cell array change is in bounds, and the
+/// cells and documents it refers to exist once the cell structure has changed.
+fn validate_cell_change(
+    notebook: &OpenNotebook,
+    cells: &NotebookDocumentCellChange,
+) -> Result<(), NotebookStoreError> {
+    let mut cell_uris: Vec<&Uri> = notebook
+        .notebook
+        .cells
+        .iter()
+        .map(|cell| &cell.document)
+        .collect();
+    let mut closed = Vec::new();
+    let mut opened = Vec::new();
+    if let Some(structure) = &cells.structure {
+        let array = &structure.array;
+        let cell_count = cell_uris.len();
+        let start = array.start as usize;
+        let end = start.saturating_add(array.delete_count as usize);
+        if end > cell_count {
+            return Err(NotebookStoreError::InvalidCellArrayChange {
+                start: array.start,
+                delete_count: array.delete_count,
+                cell_count,
+            });
+        }
+        cell_uris.splice(
+            start..end,
+            array.cells.iter().flatten().map(|cell| &cell.document),
+        );
+        closed.extend(
+            structure
+                .did_close
+                .iter()
+                .flatten()
+                .map(|closed| &closed.uri),
+        );
+        opened.extend(structure.did_open.iter().flatten().map(|item| &item.uri));
+    }
+    if let Some(data) = cells
+        .data
+        .iter()
+        .flatten()
+        .find(|data| !cell_uris.contains(&&data.document))
+    {
+        return Err(NotebookStoreError::UnknownCell(data.document.clone()));
+    }
+    let is_open = |uri: &Uri| {
+        opened.contains(&uri) || (notebook.documents.contains_key(uri) && !closed.contains(&uri))
+    };
+    if let Some(text_content) = cells
+        .text_content
+        .iter()
+        .flatten()
+        .find(|text_content| !is_open(&text_content.document.uri))
+    {
+        return Err(NotebookStoreError::UnknownCell(
+            text_content.document.uri.clone(),
+        ));
+    }
+    Ok(())
+}
+
+/// Keeps track of the notebooks opened by the client.
+#[derive(Debug, Clone)]
+pub struct NotebookStore {
+    encoding: PositionEncodingKind,
+    notebooks: HashMap<Uri, OpenNotebook>,
+    /// Maps each cell text document to the notebook containing it.
+    cell_notebooks: HashMap<Uri, Uri>,
+}
+
+impl Default for NotebookStore {
+    fn default() -> Self {
+        NotebookStore::new(PositionEncodingKind::UTF16)
+    }
+}
+
+impl NotebookStore {
+    /// Creates an empty store. `encoding` is the negotiated position encoding used for the cell
+    /// documents.
+    pub fn new(encoding: PositionEncodingKind) -> NotebookStore {
+        NotebookStore {
+            encoding,
+            notebooks: HashMap::new(),
+            cell_notebooks: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, uri: &Uri) -> Option<&OpenNotebook> {
+        self.notebooks.get(uri)
+    }
+
+    /// Returns the notebook containing the cell text document identified by `uri`.
+    pub fn notebook_for_cell(&self, uri: &Uri) -> Option<&OpenNotebook> {
+        self.notebooks.get(self.cell_notebooks.get(uri)?)
+    }
+
+    /// Returns the text document of a cell in any open notebook.
+    pub fn cell_document(&self, uri: &Uri) -> Option<&TextDocument> {
+        self.notebook_for_cell(uri)?.cell_document(uri)
+    }
+
+    pub fn notebooks(&self) -> impl Iterator<Item = &OpenNotebook> {
+        self.notebooks.values()
+    }
+
+    /// Handles a `notebookDocument/didOpen` notification. Opening a notebook that is already open
+    /// replaces it.
+    pub fn open(&mut self, params: DidOpenNotebookDocumentParams) {
+        let uri = params.notebook_document.uri.clone();
+        self.remove(&uri);
+        let mut notebook = OpenNotebook {
+            encoding: self.encoding.clone(),
+            notebook: params.notebook_document,
+            documents: HashMap::new(),
+        };
+        for item in params.cell_text_documents {
+            self.cell_notebooks.insert(item.uri.clone(), uri.clone());
+            notebook.documents.insert(
+                item.uri.clone(),
+                TextDocument::from_item(item, self.encoding.clone()),
+            );
+        }
+        self.notebooks.insert(uri, notebook);
+    }
+
+    /// Handles a `notebookDocument/didChange` notification.
+    ///
+    /// The changes are applied in the order the specification describes them: metadata, cell
+    /// structure, cell data and finally the text content of the cells. The whole change is
+    /// validated first, so on error the notebook is left unchanged.
+    pub fn change(
+        &mut self,
+        params: DidChangeNotebookDocumentParams,
+    ) -> Result<(), NotebookStoreError> {
+        let uri = params.notebook_document.uri;
+        let notebook = self
+            .notebooks
+            .get_mut(&uri)
+            .ok_or_else(|| NotebookStoreError::UnknownNotebook(uri.clone()))?;
+        let change = params.change;
+        if let Some(cells) = &change.cells {
+            validate_cell_change(notebook, cells)?;
+        }
+
+        notebook.notebook.version = params.notebook_document.version;
+        if let Some(metadata) = change.metadata {
+            notebook.notebook.metadata = Some(metadata);
+        }
+        let cells = match change.cells {
+            Some(cells) => cells,
+            None => return Ok(()),
+        };
+
+        if let Some(structure) = cells.structure {
+            let array = structure.array;
+            let start = array.start as usize;
+            let end = start + array.delete_count as usize;
+            notebook
+                .notebook
+                .cells
+                .splice(start..end, array.cells.unwrap_or_default());
+
+            for closed in structure.did_close.into_iter().flatten() {
+                notebook.documents.remove(&closed.uri);
+                self.cell_notebooks.remove(&closed.uri);
+            }
+            for item in structure.did_open.into_iter().flatten() {
+                self.cell_notebooks.insert(item.uri.clone(), uri.clone());
+                notebook.documents.insert(
+                    item.uri.clone(),
+                    TextDocument::from_item(item, self.encoding.clone()),
+                );
+            }
+        }
+
+        for data in cells.data.into_iter().flatten() {
+            if let Some(cell) = notebook
+                .notebook
+                .cells
+                .iter_mut()
+                .find(|cell| cell.document == data.document)
+            {
+                *cell = data;
+            }
+        }
+
+        for text_content in cells.text_content.into_iter().flatten() {
+            if let Some(document) = notebook.documents.get_mut(&text_content.document.uri) {
+                document.apply_changes(text_content.document.version, &text_content.changes);
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles a `notebookDocument/didClose` notification, returning the closed notebook.
+    pub fn close(&mut self, params: DidCloseNotebookDocumentParams) -> Option<OpenNotebook> {
+        self.remove(&params.notebook_document.uri)
+    }
+
+    fn remove(&mut self, uri: &Uri) -> Option<OpenNotebook> {
+        let notebook = self.notebooks.remove(uri)?;
+        for cell in notebook.documents.keys() {
+            self.cell_notebooks.remove(cell);
+        }
+        Some(notebook)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        NotebookCellArrayChange, NotebookDocumentCellChange, NotebookDocumentCellChangeStructure,
+        NotebookDocumentChangeEvent, NotebookDocumentChangeTextContent,
+        TextDocumentContentChangeEvent, TextDocumentIdentifier, TextDocumentItem,
+        VersionedNotebookDocumentIdentifier, VersionedTextDocumentIdentifier,
+    };
+
+    fn uri(s: &str) -> Uri {
+        s.parse().unwrap()
+    }
+
+    fn cell(document: &str) -> NotebookCell {
+        NotebookCell {
+            kind: NotebookCellKind::Code,
+            document: uri(document),
+            metadata: None,
+            execution_summary: None,
+        }
+    }
+
+    fn item(document: &str, text: &str) -> TextDocumentItem {
+        TextDocumentItem::new(uri(document), "python".into(), 1, text.into())
+    }
+
+    fn open_store() -> NotebookStore {
+        let mut store = NotebookStore::default();
+        store.open(DidOpenNotebookDocumentParams {
+            notebook_document: NotebookDocument {
+                uri: uri("file:///nb.ipynb"),
+                notebook_type: "jupyter-notebook".into(),
+                version: 1,
+                metadata: None,
+                cells: vec![cell("cell:a"), cell("cell:b")],
+            },
+            cell_text_documents: vec![item("cell:a", "import os\nx = 1"), item("cell:b", "y\n")],
+        });
+        store
+    }
+
+    fn change(cells: NotebookDocumentCellChange) -> DidChangeNotebookDocumentParams {
+        DidChangeNotebookDocumentParams {
+            notebook_document: VersionedNotebookDocumentIdentifier {
+                version: 2,
+                uri: uri("file:///nb.ipynb"),
+            },
+            change: NotebookDocumentChangeEvent {
+                metadata: None,
+                cells: Some(cells),
+            },
+        }
+    }
+
+    #[test]
+    fn test_apply_structure_and_text_changes() {
+        let mut store = open_store();
+        store
+            .change(change(NotebookDocumentCellChange {
+                structure: Some(NotebookDocumentCellChangeStructure {
+                    array: NotebookCellArrayChange {
+                        start: 0,
+                        delete_count: 1,
+                        cells: Some(vec![cell("cell:c")]),
+                    },
+                    did_open: Some(vec![item("cell:c", "z = 2")]),
+                    did_close: Some(vec![TextDocumentIdentifier::new(uri("cell:a"))]),
+                }),
+                data: None,
+                text_content: Some(vec![NotebookDocumentChangeTextContent {
+                    document: VersionedTextDocumentIdentifier::new(uri("cell:b"), 2),
+                    changes: vec![TextDocumentContentChangeEvent {
+                        range: Some(Range::new(Position::new(0, 0), Position::new(0, 1))),
+                        range_length: None,
+                        text: "w".into(),
+                    }],
+                }]),
+            }))
+            .unwrap();
+
+        let notebook = store.get(&uri("file:///nb.ipynb")).unwrap();
+        assert_eq!(notebook.notebook().version, 2);
+        let cells: Vec<_> = notebook
+            .cells()
+            .map(|(cell, document)| (cell.document.as_str(), document.map(|d| d.text())))
+            .collect();
+        assert_eq!(cells, [("cell:c", Some("z = 2")), ("cell:b", Some("w\n"))]);
+        assert!(store.cell_document(&uri("cell:a")).is_none());
+        assert_eq!(store.cell_document(&uri("cell:b")).unwrap().version(), 2);
+
+        assert_eq!(
+            store.change(change(NotebookDocumentCellChange {
+                structure: Some(NotebookDocumentCellChangeStructure {
+                    array: NotebookCellArrayChange {
+                        start: 1,
+                        delete_count: 5,
+                        cells: None,
+                    },
+                    did_open: None,
+                    did_close: None,
+                }),
+                data: None,
+                text_content: None,
+            })),
+            Err(NotebookStoreError::InvalidCellArrayChange {
+                start: 1,
+                delete_count: 5,
+                cell_count: 2
+            })
+        );
+
+        // A change referring to a cell that is not there leaves the notebook untouched.
+        assert_eq!(
+            store.change(change(NotebookDocumentCellChange {
+                structure: Some(NotebookDocumentCellChangeStructure {
+                    array: NotebookCellArrayChange {
+                        start: 0,
+                        delete_count: 1,
+                        cells: None,
+                    },
+                    did_open: None,
+                    did_close: None,
+                }),
+                data: Some(vec![cell("cell:c")]),
+                text_content: None,
+            })),
+            Err(NotebookStoreError::UnknownCell(uri("cell:c")))
+        );
+        let notebook = store.get(&uri("file:///nb.ipynb")).unwrap();
+        assert_eq!(notebook.notebook().cells.len(), 2);
+    }
+
+    #[test]
+    fn test_concatenated_document() {
+        let store = open_store();
+        let document = store
+            .get(&uri("file:///nb.ipynb"))
+            .unwrap()
+            .concatenated_document();
+        assert_eq!(document.text(), "import os\nx = 1\ny\n");
+
+        let (a, b) = (uri("cell:a"), uri("cell:b"));
+        assert_eq!(
+            document.to_concatenated(&b, Position::new(0, 1)),
+            Some(Position::new(2, 1))
+        );
+        assert_eq!(
+            document.to_concatenated(&b, Position::new(1, 0)),
+            Some(Position::new(3, 0))
+        );
+        assert_eq!(document.to_concatenated(&b, Position::new(1, 1)), None);
+        assert_eq!(
+            document.range_to_cell(Range::new(Position::new(2, 0), Position::new(3, 0))),
+            Some((&b, Range::new(Position::new(0, 0), Position::new(1, 0))))
+        );
+        assert_eq!(
+            document.to_cell(Position::new(1, 4)),
+            Some((&a, Position::new(1, 4)))
+        );
+        assert_eq!(
+            document.to_cell(Position::new(2, 0)),
+            Some((&b, Position::new(0, 0)))
+        );
+        assert_eq!(
+            document.cell_range(&a),
+            Some(Range::new(Position::new(0, 0), Position::new(1, 5)))
+        );
+    }
+}
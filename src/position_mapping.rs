@@ -0,0 +1,499 @@
+/*!
+
+Mapping of positions and ranges through document edits.
+
+When a document changes while results for an older version are still being computed (or are
+already displayed), the ranges in those results can be shifted through the edits instead of
+being thrown away. A [`PositionMapper`] records a sequence of edits and maps values that were
+computed against the old document onto the new one.
+
+*/
+
+use crate::{
+    Diagnostic, FoldingRange, InlayHint, InlayHintKind, Position, PositionEncodingKind, Range,
+    TextDocumentContentChangeEvent, TextEdit,
+};
+
+/// Decides where a position ends up when text is inserted exactly at it.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+pub enum Bias {
+    /// The position sticks to the text on its left and stays in front of the inserted text.
+    #[default]
+    Left,
+    /// The position sticks to the text on its right and moves behind the inserted text.
+    Right,
+}
+
+/// The result of mapping a value through a [`PositionMapper`].
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Mapped<T> {
+    /// The mapped value.
+    pub value: T,
+    /// Whether the text the value referred to was deleted by one of the edits. A deleted value is
+    /// still mapped to the nearest sensible location, but callers will usually want to drop it.
+    pub deleted: bool,
+}
+
+impl<T> Mapped<T> {
+    /// Returns the mapped value, or `None` if it was deleted.
+    pub fn into_option(self) -> Option<T> {
+        if self.deleted {
+            None
+        } else {
+            Some(self.value)
+        }
+    }
+}
+
+/// A single replacement, expressed in the coordinates of the document it applies to.
+#[derive(Debug, Clone)]
+struct Step {
+    start: Position,
+    end: Position,
+    /// The end of the inserted text in the document after this step.
+    new_end: Position,
+}
+
+/// Records a sequence of edits and maps positions from the document before the edits to the
+/// document after them.
+///
+/// Edits are recorded in the order they were applied to the document, exactly like the
+/// `content_changes` of a `textDocument/didChange` notification.
+#[derive(Debug, Clone)]
+pub struct PositionMapper {
+    encoding: PositionEncodingKind,
+    steps: Vec<Step>,
+}
+
+impl Default for PositionMapper {
+    fn default() -> Self {
+        PositionMapper::new(PositionEncodingKind::UTF16)
+    }
+}
+
+impl PositionMapper {
+    /// Creates an empty mapper. `encoding` is the negotiated position encoding, used to measure
+    /// the inserted text.
+    pub fn new(encoding: PositionEncodingKind) -> PositionMapper {
+        PositionMapper {
+            encoding,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Creates a mapper from the content changes of a `textDocument/didChange` notification.
+    pub fn from_content_changes<'a>(
+        encoding: PositionEncodingKind,
+        changes: impl IntoIterator<Item = &'a TextDocumentContentChangeEvent>,
+    ) -> PositionMapper {
+        let mut mapper = PositionMapper::new(encoding);
+        for change in changes {
+            mapper.push_content_change(change);
+        }
+        mapper
+    }
+
+    /// Creates a mapper from a set of `TextEdit`s that all refer to the same document version.
+    pub fn from_text_edits(encoding: PositionEncodingKind, edits: &[TextEdit]) -> PositionMapper {
+        let mut mapper = PositionMapper::new(encoding);
+        mapper.push_text_edits(edits);
+        mapper
+    }
+
+    /// Returns `true` if no edits have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Records a content change. A change without a range replaces the whole document, which
+    /// deletes every position that is mapped through it.
+    pub fn push_content_change(&mut self, change: &TextDocumentContentChangeEvent) {
+        let range = change.range.unwrap_or(Range {
+            start: Position::new(0, 0),
+            end: Position::new(u32::MAX, u32::MAX),
+        });
+        self.push_replace(range, &change.text);
+    }
+
+    /// Records an edit that replaces `range` with `new_text`, applied after all previously
+    /// recorded edits.
+    pub fn push_replace(&mut self, range: Range, new_text: &str) {
+        let (start, end) = if range.end < range.start {
+            (range.end, range.start)
+        } else {
+            (range.start, range.end)
+        };
+        let new_end = advance(start, new_text, &self.encoding);
+        self.steps.push(Step {
+            start,
+            end,
+            new_end,
+        });
+    }
+
+    /// Records a set of `TextEdit`s. As in a `WorkspaceEdit`, every edit refers to the document
+    /// before any of them is applied, so they must not overlap.
+    pub fn push_text_edits(&mut self, edits: &[TextEdit]) {
+        // Applying the edits back to front keeps the ranges of the remaining edits valid.
+        // Inserts at the same position keep their relative order from the array.
+        let mut order: Vec<usize> = (0..edits.len()).collect();
+        order.sort_by(|&l, &r| {
+            edits[r]
+                .range
+                .start
+                .cmp(&edits[l].range.start)
+                .then(r.cmp(&l))
+        });
+        for i in order {
+            self.push_replace(edits[i].range, &edits[i].new_text);
+        }
+    }
+
+    /// Maps a position through all recorded edits.
+    pub fn map_position(&self, position: Position, bias: Bias) -> Mapped<Position> {
+        let mut deleted = false;
+        let position = self.steps.iter().fold(position, |position, step| {
+            let (position, step_deleted) = step.map(position, bias);
+            deleted |= step_deleted;
+            position
+        });
+        Mapped {
+            value: position,
+            deleted,
+        }
+    }
+
+    /// Maps a range through all recorded edits.
+    ///
+    /// Text inserted at either end of a non-empty range is not included in the mapped range. The
+    /// range is marked as deleted once all of the text it covered has been removed.
+    pub fn map_range(&self, range: Range) -> Mapped<Range> {
+        let mut deleted = false;
+        let range = self.steps.iter().fold(range, |range, step| {
+            deleted |= step.deletes(range);
+            let (start_bias, end_bias) = if range.start < range.end {
+                (Bias::Right, Bias::Left)
+            } else {
+                (Bias::Left, Bias::Left)
+            };
+            let start = step.map(range.start, start_bias).0;
+            let end = step.map(range.end, end_bias).0;
+            Range {
+                start,
+                end: end.max(start),
+            }
+        });
+        Mapped {
+            value: range,
+            deleted,
+        }
+    }
+
+    /// Maps any value that implements [`MapPositions`].
+    pub fn map<T: MapPositions>(&self, value: T) -> Mapped<T> {
+        value.map_positions(self)
+    }
+
+    /// Maps every value in `values`, dropping those that were deleted.
+    pub fn retain_mapped<T: MapPositions>(&self, values: Vec<T>) -> Vec<T> {
+        values
+            .into_iter()
+            .filter_map(|value| self.map(value).into_option())
+            .collect()
+    }
+}
+
+impl Step {
+    fn map(&self, position: Position, bias: Bias) -> (Position, bool) {
+        if position < self.start {
+            return (position, false);
+        }
+        if position > self.end {
+            let position = if position.line == self.end.line {
+                Position::new(
+                    self.new_end.line,
+                    self.new_end
+                        .character
+                        .saturating_add(position.character - self.end.character),
+                )
+            } else {
+                Position::new(
+                    (position.line - self.end.line).saturating_add(self.new_end.line),
+                    position.character,
+                )
+            };
+            return (position, false);
+        }
+
+        // The position lies inside (or at the boundary of) the replaced text. The boundaries of a
+        // deletion stay with the surviving text, only a pure insertion consults the bias.
+        let bias = if self.start == self.end {
+            bias
+        } else if position == self.start {
+            Bias::Left
+        } else if position == self.end {
+            Bias::Right
+        } else {
+            bias
+        };
+        let deleted = self.start < position && position < self.end;
+        let position = match bias {
+            Bias::Left => self.start,
+            Bias::Right => self.new_end,
+        };
+        (position, deleted)
+    }
+
+    fn deletes(&self, range: Range) -> bool {
+        if self.start == self.end || range.start < self.start || self.end < range.end {
+            return false;
+        }
+        range.start < range.end || (self.start < range.start && range.end < self.end)
+    }
+}
+
+/// Returns the position reached after inserting `text` at `start`.
+fn advance(start: Position, text: &str, encoding: &PositionEncodingKind) -> Position {
+    let mut lines = 0u32;
+    let mut last_line = text;
+    let mut rest = text;
+    while let Some(i) = rest.find(['\r', '\n']) {
+        let skip = if rest[i..].starts_with("\r\n") { 2 } else { 1 };
+        rest = &rest[i + skip..];
+        last_line = rest;
+        lines += 1;
+    }
+    let width = encoded_len(last_line, encoding);
+    if lines == 0 {
+        Position::new(start.line, start.character.saturating_add(width))
+    } else {
+        Position::new(start.line.saturating_add(lines), width)
+    }
+}
+
+/// Returns the length of `text` in the code units of `encoding`. Unknown encodings are treated as
+/// UTF-16, the encoding every client must support.
+pub(crate) fn encoded_len(text: &str, encoding: &PositionEncodingKind) -> u32 {
+    let len = if *encoding == PositionEncodingKind::UTF8 {
+        text.len()
+    } else if *encoding == PositionEncodingKind::UTF32 {
+        text.chars().count()
+    } else {
+        text.encode_utf16().count()
+    };
+    len.min(u32::MAX as usize) as u32
+}
+
+/// Values that contain positions which can be mapped through a [`PositionMapper`].
+pub trait MapPositions: Sized {
+    fn map_positions(self, mapper: &PositionMapper) -> Mapped<Self>;
+}
+
+impl MapPositions for Range {
+    fn map_positions(self, mapper: &PositionMapper) -> Mapped<Self> {
+        mapper.map_range(self)
+    }
+}
+
+impl MapPositions for TextEdit {
+    fn map_positions(self, mapper: &PositionMapper) -> Mapped<Self> {
+        let range = mapper.map_range(self.range);
+        Mapped {
+            value: TextEdit {
+                range: range.value,
+                new_text: self.new_text,
+            },
+            deleted: range.deleted,
+        }
+    }
+}
+
+impl MapPositions for Diagnostic {
+    fn map_positions(self, mapper: &PositionMapper) -> Mapped<Self> {
+        let range = mapper.map_range(self.range);
+        Mapped {
+            value: Diagnostic {
+                range: range.value,
+                ..self
+            },
+            deleted: range.deleted,
+        }
+    }
+}
+
+impl MapPositions for InlayHint {
+    /// Parameter hints are rendered in front of the argument they label, so they stick to the
+    /// text on their right. All other hints stick to the text on their left.
+    fn map_positions(self, mapper: &PositionMapper) -> Mapped<Self> {
+        let bias = if self.kind == Some(InlayHintKind::PARAMETER) {
+            Bias::Right
+        } else {
+            Bias::Left
+        };
+        let position = mapper.map_position(self.position, bias);
+        let text_edits = self.text_edits.map(|edits| mapper.retain_mapped(edits));
+        Mapped {
+            value: InlayHint {
+                position: position.value,
+                text_edits,
+                ..self
+            },
+            deleted: position.deleted,
+        }
+    }
+}
+
+impl MapPositions for FoldingRange {
+    fn map_positions(self, mapper: &PositionMapper) -> Mapped<Self> {
+        // A missing character means the end of the line.
+        let range = mapper.map_range(Range::new(
+            Position::new(self.start_line, self.start_character.unwrap_or(u32::MAX)),
+            Position::new(self.end_line, self.end_character.unwrap_or(u32::MAX)),
+        ));
+        Mapped {
+            value: FoldingRange {
+                start_line: range.value.start.line,
+                start_character: self.start_character.map(|_| range.value.start.character),
+                end_line: range.value.end.line,
+                end_character: self.end_character.map(|_| range.value.end.character),
+                ..self
+            },
+            deleted: range.deleted,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(line: u32, character: u32) -> Position {
+        Position::new(line, character)
+    }
+
+    fn range(start: (u32, u32), end: (u32, u32)) -> Range {
+        Range::new(pos(start.0, start.1), pos(end.0, end.1))
+    }
+
+    fn change(start: (u32, u32), end: (u32, u32), text: &str) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range: Some(range(start, end)),
+            range_length: None,
+            text: text.into(),
+        }
+    }
+
+    #[test]
+    fn test_map_position_through_insert() {
+        let mapper = PositionMapper::from_content_changes(
+            PositionEncodingKind::UTF16,
+            &[change((1, 4), (1, 4), "ab\ncde")],
+        );
+
+        assert_eq!(mapper.map_position(pos(1, 2), Bias::Right).value, pos(1, 2));
+        assert_eq!(mapper.map_position(pos(1, 4), Bias::Left).value, pos(1, 4));
+        assert_eq!(mapper.map_position(pos(1, 4), Bias::Right).value, pos(2, 3));
+        assert_eq!(mapper.map_position(pos(1, 6), Bias::Left).value, pos(2, 5));
+        assert_eq!(mapper.map_position(pos(3, 6), Bias::Left).value, pos(4, 6));
+        assert!(!mapper.map_position(pos(1, 4), Bias::Right).deleted);
+    }
+
+    #[test]
+    fn test_map_position_through_delete() {
+        let mapper = PositionMapper::from_content_changes(
+            PositionEncodingKind::UTF16,
+            &[change((0, 2), (2, 1), "")],
+        );
+
+        assert_eq!(
+            mapper.map_position(pos(1, 0), Bias::Left),
+            Mapped {
+                value: pos(0, 2),
+                deleted: true
+            }
+        );
+        assert_eq!(
+            mapper.map_position(pos(2, 1), Bias::Left),
+            Mapped {
+                value: pos(0, 2),
+                deleted: false
+            }
+        );
+        assert_eq!(mapper.map_position(pos(2, 5), Bias::Left).value, pos(0, 6));
+        assert_eq!(mapper.map_position(pos(3, 5), Bias::Left).value, pos(1, 5));
+    }
+
+    #[test]
+    fn test_map_range_through_content_changes() {
+        let mapper = PositionMapper::from_content_changes(
+            PositionEncodingKind::UTF16,
+            &[change((0, 0), (0, 0), "x"), change((0, 5), (0, 5), "y")],
+        );
+        // The first insert lands on the start of the range, the second on its end.
+        let mapped = mapper.map_range(range((0, 0), (0, 4)));
+        assert_eq!(mapped.value, range((0, 1), (0, 5)));
+        assert!(!mapped.deleted);
+
+        let mapper = PositionMapper::from_content_changes(
+            PositionEncodingKind::UTF16,
+            &[change((0, 2), (0, 8), "")],
+        );
+        assert!(mapper.map_range(range((0, 3), (0, 5))).deleted);
+        assert!(mapper.map_range(range((0, 2), (0, 8))).deleted);
+        assert!(!mapper.map_range(range((0, 1), (0, 5))).deleted);
+        assert_eq!(
+            mapper.map_range(range((0, 1), (0, 5))).value,
+            range((0, 1), (0, 2))
+        );
+    }
+
+    #[test]
+    fn test_map_through_full_replacement() {
+        let mapper = PositionMapper::from_content_changes(
+            PositionEncodingKind::UTF16,
+            &[TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: "new".into(),
+            }],
+        );
+        assert!(mapper.map_range(range((1, 0), (1, 3))).deleted);
+    }
+
+    #[test]
+    fn test_map_through_text_edits() {
+        let mapper = PositionMapper::from_text_edits(
+            PositionEncodingKind::UTF8,
+            &[
+                TextEdit::new(range((0, 0), (0, 1)), "ä".into()),
+                TextEdit::new(range((0, 4), (0, 4)), "\n".into()),
+            ],
+        );
+        assert_eq!(mapper.map_position(pos(0, 2), Bias::Left).value, pos(0, 3));
+        assert_eq!(mapper.map_position(pos(0, 6), Bias::Left).value, pos(1, 2));
+    }
+
+    #[test]
+    fn test_map_folding_range() {
+        let mapper = PositionMapper::from_content_changes(
+            PositionEncodingKind::UTF16,
+            &[change((0, 0), (0, 0), "\n\n")],
+        );
+        let folding_range = FoldingRange {
+            start_line: 1,
+            end_line: 4,
+            ..FoldingRange::default()
+        };
+        assert_eq!(
+            mapper.map(folding_range),
+            Mapped {
+                value: FoldingRange {
+                    start_line: 3,
+                    end_line: 6,
+                    ..FoldingRange::default()
+                },
+                deleted: false
+            }
+        );
+    }
+}
@@ -0,0 +1,344 @@
+/*!
+
+Tracking which capabilities of a server are in effect.
+
+Servers announce capabilities statically in `ServerCapabilities` and add or remove them at runtime
+with `client/registerCapability` and `client/unregisterCapability`. A [`RegistrationRegistry`]
+applies both and answers whether a method currently applies to a document.
+
+*/
+
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::{
+    capabilities_merge::merge_server_capabilities, document_selector::DocumentSelectorMatcher,
+    DocumentSelector, RegistrationParams, ServerCapabilities, UnregistrationParams, Uri,
+};
+
+/// The static capabilities whose options may carry a `documentSelector` or an `id`, with the
+/// methods they enable.
+const STATIC_REGISTRATION_OPTIONS: &[(&str, &[&str])] = &[
+    ("selectionRangeProvider", &["textDocument/selectionRange"]),
+    ("typeDefinitionProvider", &["textDocument/typeDefinition"]),
+    ("implementationProvider", &["textDocument/implementation"]),
+    ("colorProvider", &["textDocument/documentColor"]),
+    ("foldingRangeProvider", &["textDocument/foldingRange"]),
+    ("declarationProvider", &["textDocument/declaration"]),
+    (
+        "semanticTokensProvider",
+        &[
+            "textDocument/semanticTokens/full",
+            "textDocument/semanticTokens/full/delta",
+            "textDocument/semanticTokens/range",
+        ],
+    ),
+    ("monikerProvider", &["textDocument/moniker"]),
+    (
+        "linkedEditingRangeProvider",
+        &["textDocument/linkedEditingRange"],
+    ),
+    ("inlineValueProvider", &["textDocument/inlineValue"]),
+    ("inlayHintProvider", &["textDocument/inlayHint"]),
+    ("diagnosticProvider", &["textDocument/diagnostic"]),
+    ("notebookDocumentSync", &["notebookDocument/sync"]),
+];
+
+/// Whether a registration for `registered` covers requests of `method`. Semantic tokens are
+/// registered as `textDocument/semanticTokens` and requested with more specific methods.
+fn covers(registered: &str, method: &str) -> bool {
+    registered == method
+        || (registered == "textDocument/semanticTokens"
+            && method
+                .strip_prefix(registered)
+                .is_some_and(|rest| rest.starts_with('/')))
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum RegistrationError {
+    /// A registration with this id is already in effect.
+    DuplicateId(String),
+    /// No registration with this id is in effect.
+    UnknownId(String),
+    /// The registration with this id is not for the method given to unregister it.
+    MethodMismatch { id: String, method: String },
+    /// The `documentSelector` of the registration with this id, or of the static capability with
+    /// this name if it has no id, is malformed.
+    InvalidDocumentSelector(String),
+}
+
+impl fmt::Display for RegistrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistrationError::DuplicateId(id) => write!(f, "duplicate registration id `{}`", id),
+            RegistrationError::UnknownId(id) => write!(f, "unknown registration id `{}`", id),
+            RegistrationError::MethodMismatch { id, method } => {
+                write!(f, "registration `{}` is not for `{}`", id, method)
+            }
+            RegistrationError::InvalidDocumentSelector(id) => {
+                write!(f, "invalid document selector in registration `{}`", id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegistrationError {}
+
+#[derive(Debug, PartialEq, Clone)]
+struct Entry {
+    /// `None` for static capabilities that can not be unregistered.
+    id: Option<String>,
+    method: String,
+    /// `None` if the registration applies to every document.
+    document_selector: Option<DocumentSelectorMatcher>,
+    register_options: Option<Value>,
+}
+
+impl Entry {
+    fn applies_to(&self, uri: &Uri, language_id: &str) -> bool {
+        self.document_selector
+            .as_ref()
+            .is_none_or(|selector| selector.matches(uri, language_id))
+    }
+}
+
+/// Parses the `documentSelector` of registration options. A missing or `null` selector applies to
+/// every document.
+fn document_selector(options: Option<&Value>) -> Result<Option<DocumentSelectorMatcher>, ()> {
+    match options.and_then(|options| options.get("documentSelector")) {
+        None | Some(Value::Null) => Ok(None),
+        Some(selector) => serde_json::from_value::<DocumentSelector>(selector.clone())
+            .map(|selector| Some(selector.into()))
+            .map_err(|_| ()),
+    }
+}
+
+/// The live registrations of a server.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct RegistrationRegistry {
+    entries: Vec<Entry>,
+}
+
+impl RegistrationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry with the static `capabilities` of a server in effect. Static options with an
+    /// `id` can be unregistered, and a `documentSelector` in them limits the documents they apply
+    /// to.
+    pub fn with_capabilities(capabilities: &ServerCapabilities) -> Result<Self, RegistrationError> {
+        let methods = merge_server_capabilities(std::slice::from_ref(capabilities)).routes;
+        let json = serde_json::to_value(capabilities).unwrap_or_default();
+        let entries = methods
+            .methods()
+            .map(|(method, _)| {
+                let (key, options) = STATIC_REGISTRATION_OPTIONS
+                    .iter()
+                    .find(|(_, methods)| methods.contains(&method))
+                    .map(|(key, _)| (*key, json.get(key).filter(|options| options.is_object())))
+                    .unwrap_or_default();
+                let id = options
+                    .and_then(|options| options.get("id"))
+                    .and_then(Value::as_str)
+                    .map(String::from);
+                let document_selector = document_selector(options).map_err(|()| {
+                    RegistrationError::InvalidDocumentSelector(
+                        id.clone().unwrap_or_else(|| key.to_string()),
+                    )
+                })?;
+                Ok(Entry {
+                    id,
+                    method: method.to_string(),
+                    document_selector,
+                    register_options: options.cloned(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(RegistrationRegistry { entries })
+    }
+
+    fn contains_id(&self, id: &str) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.id.as_deref() == Some(id))
+    }
+
+    /// Applies `client/registerCapability`. Nothing is registered if any registration is invalid.
+    pub fn register(&mut self, params: &RegistrationParams) -> Result<(), RegistrationError> {
+        let mut entries = Vec::with_capacity(params.registrations.len());
+        for registration in &params.registrations {
+            let id = &registration.id;
+            if self.contains_id(id)
+                || entries
+                    .iter()
+                    .any(|entry: &Entry| entry.id.as_ref() == Some(id))
+            {
+                return Err(RegistrationError::DuplicateId(id.clone()));
+            }
+            let document_selector = document_selector(registration.register_options.as_ref())
+                .map_err(|()| RegistrationError::InvalidDocumentSelector(id.clone()))?;
+            entries.push(Entry {
+                id: Some(id.clone()),
+                method: registration.method.clone(),
+                document_selector,
+                register_options: registration.register_options.clone(),
+            });
+        }
+        self.entries.extend(entries);
+        Ok(())
+    }
+
+    /// Applies `client/unregisterCapability`. Nothing is unregistered if any id is unknown, or is
+    /// registered for another method.
+    pub fn unregister(&mut self, params: &UnregistrationParams) -> Result<(), RegistrationError> {
+        for unregistration in &params.unregisterations {
+            let id = &unregistration.id;
+            if !self.contains_id(id) {
+                return Err(RegistrationError::UnknownId(id.clone()));
+            }
+            if !self.entries.iter().any(|entry| {
+                entry.id.as_ref() == Some(id) && covers(&unregistration.method, &entry.method)
+            }) {
+                return Err(RegistrationError::MethodMismatch {
+                    id: id.clone(),
+                    method: unregistration.method.clone(),
+                });
+            }
+        }
+        self.entries.retain(|entry| {
+            !params.unregisterations.iter().any(|unregistration| {
+                entry.id.as_ref() == Some(&unregistration.id)
+                    && covers(&unregistration.method, &entry.method)
+            })
+        });
+        Ok(())
+    }
+
+    /// Whether `method` is registered for any document.
+    pub fn is_registered(&self, method: &str) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| covers(&entry.method, method))
+    }
+
+    /// Whether `method` applies to the document at `uri` with `language_id`.
+    pub fn is_enabled(&self, method: &str, uri: &Uri, language_id: &str) -> bool {
+        self.registration_options(method, uri, language_id)
+            .next()
+            .is_some()
+    }
+
+    /// The registration options of the registrations of `method` that apply to the document, in
+    /// the order they were registered. Registrations without options yield `Value::Null`.
+    pub fn registration_options<'a>(
+        &'a self,
+        method: &'a str,
+        uri: &'a Uri,
+        language_id: &'a str,
+    ) -> impl Iterator<Item = &'a Value> + 'a {
+        self.entries
+            .iter()
+            .filter(move |entry| {
+                covers(&entry.method, method) && entry.applies_to(uri, language_id)
+            })
+            .map(|entry| entry.register_options.as_ref().unwrap_or(&Value::Null))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        DocumentFilter, OneOf, Registration, StaticTextDocumentRegistrationOptions,
+        TypeDefinitionProviderCapability, Unregistration,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn test_dynamic_registration() {
+        let capabilities = ServerCapabilities {
+            hover_provider: Some(true.into()),
+            ..ServerCapabilities::default()
+        };
+        let mut registry = RegistrationRegistry::with_capabilities(&capabilities).unwrap();
+        let rust_file: Uri = "file:///a.rs".parse().unwrap();
+        let toml_file: Uri = "file:///Cargo.toml".parse().unwrap();
+        assert!(registry.is_enabled("textDocument/hover", &toml_file, "toml"));
+        assert!(!registry.is_enabled("textDocument/formatting", &rust_file, "rust"));
+
+        let params = RegistrationParams {
+            registrations: vec![Registration {
+                id: "fmt".into(),
+                method: "textDocument/formatting".into(),
+                register_options: Some(json!({
+                    "documentSelector": [{ "language": "rust" }],
+                })),
+            }],
+        };
+        registry.register(&params).unwrap();
+        assert_eq!(
+            registry.register(&params),
+            Err(RegistrationError::DuplicateId("fmt".into()))
+        );
+        assert!(registry.is_enabled("textDocument/formatting", &rust_file, "rust"));
+        assert!(!registry.is_enabled("textDocument/formatting", &toml_file, "toml"));
+
+        let unregister = |id: &str, method: &str| UnregistrationParams {
+            unregisterations: vec![Unregistration {
+                id: id.into(),
+                method: method.into(),
+            }],
+        };
+        assert_eq!(
+            registry.unregister(&unregister("lint", "textDocument/formatting")),
+            Err(RegistrationError::UnknownId("lint".into()))
+        );
+        assert_eq!(
+            registry.unregister(&unregister("fmt", "textDocument/rangeFormatting")),
+            Err(RegistrationError::MethodMismatch {
+                id: "fmt".into(),
+                method: "textDocument/rangeFormatting".into(),
+            })
+        );
+        assert!(registry.is_registered("textDocument/formatting"));
+        registry
+            .unregister(&unregister("fmt", "textDocument/formatting"))
+            .unwrap();
+        assert!(!registry.is_registered("textDocument/formatting"));
+    }
+
+    #[test]
+    fn test_static_registration_options() {
+        let capabilities = ServerCapabilities {
+            type_definition_provider: Some(TypeDefinitionProviderCapability::Options(
+                StaticTextDocumentRegistrationOptions {
+                    document_selector: Some(vec![DocumentFilter {
+                        language: Some("c".into()),
+                        scheme: None,
+                        pattern: None,
+                    }]),
+                    id: Some("types".into()),
+                },
+            )),
+            definition_provider: Some(OneOf::Left(true)),
+            ..ServerCapabilities::default()
+        };
+        let mut registry = RegistrationRegistry::with_capabilities(&capabilities).unwrap();
+        let header: Uri = "file:///a.h".parse().unwrap();
+        assert!(registry.is_enabled("textDocument/typeDefinition", &header, "c"));
+        assert!(!registry.is_enabled("textDocument/typeDefinition", &header, "cpp"));
+        assert!(registry.is_enabled("textDocument/definition", &header, "cpp"));
+
+        registry
+            .unregister(&UnregistrationParams {
+                unregisterations: vec![Unregistration {
+                    id: "types".into(),
+                    method: "textDocument/typeDefinition".into(),
+                }],
+            })
+            .unwrap();
+        assert!(!registry.is_registered("textDocument/typeDefinition"));
+    }
+}
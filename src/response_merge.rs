@@ -0,0 +1,728 @@
+/*!
+
+Combining the responses of several language servers to the same request.
+
+A proxy multiplexing several servers sends a request to every backend serving it and merges the
+results with [`MergeResponses`], which is implemented for the `Result` types of the requests whose
+responses can be combined. [`DocumentDiagnosticReports`] merges pulled document diagnostics
+when servers answer with unchanged reports, and [`PublishedDiagnostics`] merges the diagnostics
+servers push with `textDocument/publishDiagnostics`.
+
+*/
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+use crate::{
+    markup::escape_markdown, CodeActionOrCommand, CodeLens, CompletionList, CompletionResponse,
+    Diagnostic, DocumentDiagnosticReport, DocumentDiagnosticReportKind,
+    DocumentDiagnosticReportPartialResult, DocumentDiagnosticReportResult, DocumentHighlight,
+    DocumentLink, FoldingRange, FullDocumentDiagnosticReport, GotoDefinitionResponse, Hover,
+    HoverContents, InlayHint, Location, LocationLink, MarkupContent, MarkupKind, Moniker,
+    PublishDiagnosticsParams, RelatedFullDocumentDiagnosticReport, Uri,
+};
+
+/// The separator between the hovers of different servers in markdown.
+const HOVER_SEPARATOR: &str = "\n\n---\n\n";
+
+/// A response that can be combined with the responses of other servers to the same request.
+pub trait MergeResponses: Sized {
+    /// Combines the `responses`, given in the order of the servers they came from.
+    fn merge_responses<I: IntoIterator<Item = Self>>(responses: I) -> Self;
+}
+
+/// Completion items are concatenated, and the result is incomplete if any response is.
+impl MergeResponses for Option<CompletionResponse> {
+    fn merge_responses<I: IntoIterator<Item = Self>>(responses: I) -> Self {
+        let mut list: Option<CompletionList> = None;
+        let mut is_list = false;
+        for response in responses.into_iter().flatten() {
+            let merged = list.get_or_insert_with(Default::default);
+            match response {
+                CompletionResponse::Array(items) => merged.items.extend(items),
+                CompletionResponse::List(response) => {
+                    is_list = true;
+                    merged.is_incomplete |= response.is_incomplete;
+                    merged.items.extend(response.items);
+                }
+            }
+        }
+        list.map(|list| {
+            if is_list {
+                CompletionResponse::List(list)
+            } else {
+                CompletionResponse::Array(list.items)
+            }
+        })
+    }
+}
+
+/// Hover contents are joined with horizontal rules, or blank lines if none of them is markdown.
+/// The range of the first hover with a range is kept.
+impl MergeResponses for Option<Hover> {
+    fn merge_responses<I: IntoIterator<Item = Self>>(responses: I) -> Self {
+        let mut contents: Vec<MarkupContent> = Vec::new();
+        let mut range = None;
+        for hover in responses.into_iter().flatten() {
+            range = range.or(hover.range);
+            contents.push(hover.contents.normalize());
+        }
+        if contents.len() <= 1 {
+            let contents = contents.pop()?;
+            return Some(Hover {
+                contents: HoverContents::Markup(contents),
+                range,
+            });
+        }
+
+        let markdown = contents
+            .iter()
+            .any(|content| content.kind == MarkupKind::Markdown);
+        let value = if markdown {
+            contents
+                .iter()
+                .map(|content| match content.kind {
+                    MarkupKind::Markdown => content.value.clone(),
+                    MarkupKind::PlainText => escape_markdown(&content.value),
+                })
+                .collect::<Vec<_>>()
+                .join(HOVER_SEPARATOR)
+        } else {
+            contents
+                .iter()
+                .map(|content| content.value.as_str())
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        };
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: if markdown {
+                    MarkupKind::Markdown
+                } else {
+                    MarkupKind::PlainText
+                },
+                value,
+            }),
+            range,
+        })
+    }
+}
+
+fn location_link(location: Location) -> LocationLink {
+    LocationLink {
+        origin_selection_range: None,
+        target_uri: location.uri,
+        target_range: location.range,
+        target_selection_range: location.range,
+    }
+}
+
+/// Locations are united without duplicates. If any server responds with location links, all
+/// locations are turned into links, and links to the same selection range are duplicates.
+impl MergeResponses for Option<GotoDefinitionResponse> {
+    fn merge_responses<I: IntoIterator<Item = Self>>(responses: I) -> Self {
+        let mut locations: Vec<Location> = Vec::new();
+        let mut links: Option<Vec<LocationLink>> = None;
+        let mut any = false;
+        for response in responses.into_iter().flatten() {
+            any = true;
+            match response {
+                GotoDefinitionResponse::Scalar(location) => locations.push(location),
+                GotoDefinitionResponse::Array(array) => locations.extend(array),
+                GotoDefinitionResponse::Link(response) => {
+                    links.get_or_insert_with(Vec::new).extend(response)
+                }
+            }
+        }
+        if !any {
+            return None;
+        }
+
+        match links {
+            Some(mut links) => {
+                links.extend(locations.into_iter().map(location_link));
+                let mut unique: Vec<LocationLink> = Vec::with_capacity(links.len());
+                for link in links {
+                    if !unique.iter().any(|other| {
+                        other.target_uri == link.target_uri
+                            && other.target_selection_range == link.target_selection_range
+                    }) {
+                        unique.push(link);
+                    }
+                }
+                Some(GotoDefinitionResponse::Link(unique))
+            }
+            None => Some(GotoDefinitionResponse::Array(dedup(locations))),
+        }
+    }
+}
+
+fn dedup<T: PartialEq>(values: Vec<T>) -> Vec<T> {
+    let mut unique = Vec::with_capacity(values.len());
+    for value in values {
+        if !unique.contains(&value) {
+            unique.push(value);
+        }
+    }
+    unique
+}
+
+/// References are united without duplicates.
+impl MergeResponses for Option<Vec<Location>> {
+    fn merge_responses<I: IntoIterator<Item = Self>>(responses: I) -> Self {
+        let mut any = false;
+        let locations = responses
+            .into_iter()
+            .flatten()
+            .inspect(|_| any = true)
+            .flatten()
+            .collect();
+        if any {
+            Some(dedup(locations))
+        } else {
+            None
+        }
+    }
+}
+
+macro_rules! concat_responses {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            /// The responses are concatenated.
+            impl MergeResponses for Option<Vec<$ty>> {
+                fn merge_responses<I: IntoIterator<Item = Self>>(responses: I) -> Self {
+                    responses.into_iter().flatten().reduce(|mut merged, response| {
+                        merged.extend(response);
+                        merged
+                    })
+                }
+            }
+        )*
+    };
+}
+
+concat_responses!(
+    CodeActionOrCommand,
+    CodeLens,
+    DocumentHighlight,
+    DocumentLink,
+    FoldingRange,
+    InlayHint,
+    Moniker,
+);
+
+/// Adds the diagnostics of full reports in `related_documents` to `into`. Unchanged reports are
+/// skipped, see the `MergeResponses` implementation of `DocumentDiagnosticReportResult`.
+#[allow(clippy::mutable_key_type)]
+fn merge_related_documents(
+    into: &mut HashMap<Uri, Vec<Diagnostic>>,
+    related_documents: Option<HashMap<Uri, DocumentDiagnosticReportKind>>,
+) {
+    for (uri, report) in related_documents.into_iter().flatten() {
+        if let DocumentDiagnosticReportKind::Full(report) = report {
+            into.entry(uri).or_default().extend(report.items);
+        }
+    }
+}
+
+#[allow(clippy::mutable_key_type)]
+fn full_reports(
+    related_documents: HashMap<Uri, Vec<Diagnostic>>,
+) -> Option<HashMap<Uri, DocumentDiagnosticReportKind>> {
+    if related_documents.is_empty() {
+        return None;
+    }
+    Some(
+        related_documents
+            .into_iter()
+            .map(|(uri, items)| {
+                let report = FullDocumentDiagnosticReport {
+                    result_id: None,
+                    items,
+                };
+                (uri, DocumentDiagnosticReportKind::Full(report))
+            })
+            .collect(),
+    )
+}
+
+/// The diagnostics of all servers are concatenated, keeping the `source` each server set.
+///
+/// The merged report has no result id, so clients do not send previous result ids. Unchanged
+/// reports carry no diagnostics and are skipped, which drops the diagnostics of their server: a
+/// proxy that sends previous result ids to its servers merges with [`DocumentDiagnosticReports`]
+/// instead.
+impl MergeResponses for DocumentDiagnosticReportResult {
+    #[allow(clippy::mutable_key_type)]
+    fn merge_responses<I: IntoIterator<Item = Self>>(responses: I) -> Self {
+        let mut items = Vec::new();
+        let mut related_documents = HashMap::new();
+        let mut partial = true;
+        for response in responses {
+            match response {
+                DocumentDiagnosticReportResult::Report(DocumentDiagnosticReport::Full(report)) => {
+                    partial = false;
+                    items.extend(report.full_document_diagnostic_report.items);
+                    merge_related_documents(&mut related_documents, report.related_documents);
+                }
+                DocumentDiagnosticReportResult::Report(DocumentDiagnosticReport::Unchanged(
+                    report,
+                )) => {
+                    partial = false;
+                    merge_related_documents(&mut related_documents, report.related_documents);
+                }
+                DocumentDiagnosticReportResult::Partial(report) => {
+                    merge_related_documents(&mut related_documents, report.related_documents);
+                }
+            }
+        }
+
+        let related_documents = full_reports(related_documents);
+        if partial {
+            return DocumentDiagnosticReportResult::Partial(
+                DocumentDiagnosticReportPartialResult { related_documents },
+            );
+        }
+        DocumentDiagnosticReportResult::Report(DocumentDiagnosticReport::Full(
+            RelatedFullDocumentDiagnosticReport {
+                related_documents,
+                full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                    result_id: None,
+                    items,
+                },
+            },
+        ))
+    }
+}
+
+/// An unchanged diagnostic report whose result id is not the one of the last full report of its
+/// server for the document.
+#[derive(Debug, PartialEq, Clone)]
+pub struct UnknownResultId {
+    pub backend: usize,
+    pub uri: Uri,
+    pub result_id: String,
+}
+
+impl fmt::Display for UnknownResultId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unchanged diagnostic report of backend {} for `{}` has unknown result id `{}`",
+            self.backend,
+            self.uri.as_str(),
+            self.result_id
+        )
+    }
+}
+
+impl std::error::Error for UnknownResultId {}
+
+/// The last full document diagnostic reports of several servers, so that the unchanged reports of
+/// one server can be merged with the full reports of the others.
+#[derive(Debug, Clone, Default)]
+pub struct DocumentDiagnosticReports {
+    documents: HashMap<Uri, BTreeMap<usize, FullDocumentDiagnosticReport>>,
+}
+
+impl DocumentDiagnosticReports {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The result id of the last full report of `backend` for `uri`, to send to it as the
+    /// previous result id.
+    pub fn previous_result_id(&self, backend: usize, uri: &Uri) -> Option<&str> {
+        self.documents.get(uri)?.get(&backend)?.result_id.as_deref()
+    }
+
+    /// Merges the responses of the `backends` to a `textDocument/diagnostic` request for `uri`.
+    ///
+    /// Full reports, also of related documents, are remembered, and unchanged reports are replaced
+    /// by the last full report of their server for the document.
+    pub fn merge<I: IntoIterator<Item = (usize, DocumentDiagnosticReportResult)>>(
+        &mut self,
+        uri: &Uri,
+        responses: I,
+    ) -> Result<DocumentDiagnosticReportResult, UnknownResultId> {
+        let mut resolved = Vec::new();
+        for (backend, response) in responses {
+            let (related_documents, report) = match response {
+                DocumentDiagnosticReportResult::Report(DocumentDiagnosticReport::Full(report)) => (
+                    report.related_documents,
+                    report.full_document_diagnostic_report.into(),
+                ),
+                DocumentDiagnosticReportResult::Report(DocumentDiagnosticReport::Unchanged(
+                    report,
+                )) => (
+                    report.related_documents,
+                    report.unchanged_document_diagnostic_report.into(),
+                ),
+                DocumentDiagnosticReportResult::Partial(report) => {
+                    let related_documents =
+                        self.resolve_related(backend, report.related_documents)?;
+                    resolved.push(DocumentDiagnosticReportResult::Partial(
+                        DocumentDiagnosticReportPartialResult { related_documents },
+                    ));
+                    continue;
+                }
+            };
+            let report = RelatedFullDocumentDiagnosticReport {
+                related_documents: self.resolve_related(backend, related_documents)?,
+                full_document_diagnostic_report: self.resolve(backend, uri, report)?,
+            };
+            resolved.push(DocumentDiagnosticReportResult::Report(
+                DocumentDiagnosticReport::Full(report),
+            ));
+        }
+        Ok(DocumentDiagnosticReportResult::merge_responses(resolved))
+    }
+
+    /// Remembers a full report of `backend` for `uri`, or returns the remembered report an
+    /// unchanged report refers to.
+    fn resolve(
+        &mut self,
+        backend: usize,
+        uri: &Uri,
+        report: DocumentDiagnosticReportKind,
+    ) -> Result<FullDocumentDiagnosticReport, UnknownResultId> {
+        match report {
+            DocumentDiagnosticReportKind::Full(report) => {
+                self.documents
+                    .entry(uri.clone())
+                    .or_default()
+                    .insert(backend, report.clone());
+                Ok(report)
+            }
+            DocumentDiagnosticReportKind::Unchanged(report) => self
+                .documents
+                .get(uri)
+                .and_then(|document| document.get(&backend))
+                .filter(|full| full.result_id.as_deref() == Some(report.result_id.as_str()))
+                .cloned()
+                .ok_or(UnknownResultId {
+                    backend,
+                    uri: uri.clone(),
+                    result_id: report.result_id,
+                }),
+        }
+    }
+
+    fn resolve_related(
+        &mut self,
+        backend: usize,
+        related_documents: Option<HashMap<Uri, DocumentDiagnosticReportKind>>,
+    ) -> Result<Option<HashMap<Uri, DocumentDiagnosticReportKind>>, UnknownResultId> {
+        related_documents
+            .map(|related_documents| {
+                related_documents
+                    .into_iter()
+                    .map(|(uri, report)| {
+                        let full = self.resolve(backend, &uri, report)?;
+                        Ok((uri, DocumentDiagnosticReportKind::Full(full)))
+                    })
+                    .collect()
+            })
+            .transpose()
+    }
+
+    /// Forgets the reports for `uri`, for example after it was closed.
+    pub fn remove_document(&mut self, uri: &Uri) {
+        self.documents.remove(uri);
+    }
+
+    /// Forgets the reports of `backend`, for example after it exited.
+    pub fn remove_backend(&mut self, backend: usize) {
+        self.documents.retain(|_, document| {
+            document.remove(&backend);
+            !document.is_empty()
+        });
+    }
+}
+
+/// The diagnostics published by several servers, kept per server so that a new publication of one
+/// server only replaces its own diagnostics.
+#[derive(Debug, Clone, Default)]
+pub struct PublishedDiagnostics {
+    documents: HashMap<Uri, BTreeMap<usize, Vec<Diagnostic>>>,
+}
+
+impl PublishedDiagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the diagnostics `backend` published, and returns the diagnostics of all servers for
+    /// the document to publish to the client.
+    pub fn publish(
+        &mut self,
+        backend: usize,
+        params: PublishDiagnosticsParams,
+    ) -> PublishDiagnosticsParams {
+        let document = self.documents.entry(params.uri.clone()).or_default();
+        if params.diagnostics.is_empty() {
+            document.remove(&backend);
+        } else {
+            document.insert(backend, params.diagnostics);
+        }
+        let diagnostics = document.values().flatten().cloned().collect();
+        if document.is_empty() {
+            self.documents.remove(&params.uri);
+        }
+        PublishDiagnosticsParams {
+            uri: params.uri,
+            diagnostics,
+            version: params.version,
+        }
+    }
+
+    /// The diagnostics of all servers for `uri`.
+    pub fn diagnostics(&self, uri: &Uri) -> Vec<Diagnostic> {
+        self.documents
+            .get(uri)
+            .map(|document| document.values().flatten().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Forgets the diagnostics of `backend`, for example after it exited, and returns the
+    /// publications that update the client.
+    pub fn remove_backend(&mut self, backend: usize) -> Vec<PublishDiagnosticsParams> {
+        let mut updates = Vec::new();
+        self.documents.retain(|uri, document| {
+            if document.remove(&backend).is_some() {
+                updates.push(PublishDiagnosticsParams {
+                    uri: uri.clone(),
+                    diagnostics: document.values().flatten().cloned().collect(),
+                    version: None,
+                });
+            }
+            !document.is_empty()
+        });
+        updates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CompletionItem, Position, Range, RelatedUnchangedDocumentDiagnosticReport,
+        UnchangedDocumentDiagnosticReport,
+    };
+
+    fn range(line: u32) -> Range {
+        Range::new(Position::new(line, 0), Position::new(line, 1))
+    }
+
+    fn uri(s: &str) -> Uri {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_merge_completion_and_hover() {
+        let item = |label: &str| CompletionItem::new_simple(label.into(), String::new());
+        let merged = Option::<CompletionResponse>::merge_responses(vec![
+            Some(CompletionResponse::Array(vec![item("a")])),
+            None,
+            Some(CompletionResponse::List(CompletionList {
+                is_incomplete: true,
+                items: vec![item("b")],
+            })),
+        ]);
+        assert_eq!(
+            merged,
+            Some(CompletionResponse::List(CompletionList {
+                is_incomplete: true,
+                items: vec![item("a"), item("b")],
+            }))
+        );
+        assert_eq!(
+            Option::<CompletionResponse>::merge_responses(vec![None]),
+            None
+        );
+
+        let hover = |kind, value: &str| Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind,
+                value: value.into(),
+            }),
+            range: None,
+        };
+        let merged = Option::<Hover>::merge_responses(vec![
+            Some(hover(MarkupKind::Markdown, "`fn f()`")),
+            Some(hover(MarkupKind::PlainText, "unused *variable*")),
+        ]);
+        assert_eq!(
+            merged,
+            Some(hover(
+                MarkupKind::Markdown,
+                "`fn f()`\n\n---\n\nunused \\*variable\\*"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_merge_goto_definition() {
+        let a = Location::new(uri("file:///a.rs"), range(1));
+        let b = Location::new(uri("file:///b.rs"), range(2));
+        let merged = Option::<GotoDefinitionResponse>::merge_responses(vec![
+            Some(GotoDefinitionResponse::Scalar(a.clone())),
+            Some(GotoDefinitionResponse::Array(vec![a.clone(), b.clone()])),
+        ]);
+        assert_eq!(
+            merged,
+            Some(GotoDefinitionResponse::Array(vec![a.clone(), b.clone()]))
+        );
+
+        let link = LocationLink {
+            origin_selection_range: Some(range(0)),
+            target_uri: a.uri.clone(),
+            target_range: range(0),
+            target_selection_range: a.range,
+        };
+        let merged = Option::<GotoDefinitionResponse>::merge_responses(vec![
+            Some(GotoDefinitionResponse::Link(vec![link.clone()])),
+            Some(GotoDefinitionResponse::Array(vec![a, b.clone()])),
+        ]);
+        assert_eq!(
+            merged,
+            Some(GotoDefinitionResponse::Link(vec![link, location_link(b)]))
+        );
+    }
+
+    #[test]
+    fn test_merge_document_diagnostic_reports() {
+        let file = uri("file:///a.rs");
+        let header = uri("file:///a.h");
+        let diagnostic = |source: &str| Diagnostic {
+            source: Some(source.into()),
+            ..Diagnostic::default()
+        };
+        let full = |result_id: &str, source: &str| FullDocumentDiagnosticReport {
+            result_id: Some(result_id.into()),
+            items: vec![diagnostic(source)],
+        };
+        let unchanged = |result_id: &str| UnchangedDocumentDiagnosticReport {
+            result_id: result_id.into(),
+        };
+        let report = |related_documents: Option<HashMap<Uri, DocumentDiagnosticReportKind>>,
+                      report: DocumentDiagnosticReportKind| {
+            let report = match report {
+                DocumentDiagnosticReportKind::Full(report) => RelatedFullDocumentDiagnosticReport {
+                    related_documents,
+                    full_document_diagnostic_report: report,
+                }
+                .into(),
+                DocumentDiagnosticReportKind::Unchanged(report) => {
+                    RelatedUnchangedDocumentDiagnosticReport {
+                        related_documents,
+                        unchanged_document_diagnostic_report: report,
+                    }
+                    .into()
+                }
+            };
+            DocumentDiagnosticReportResult::Report(report)
+        };
+        let items = |result: DocumentDiagnosticReportResult| match result {
+            DocumentDiagnosticReportResult::Report(DocumentDiagnosticReport::Full(report)) => (
+                report.full_document_diagnostic_report.items,
+                report.related_documents,
+            ),
+            result => panic!("not a full report: {:?}", result),
+        };
+
+        let mut reports = DocumentDiagnosticReports::new();
+        let merged = reports
+            .merge(
+                &file,
+                vec![
+                    (
+                        0,
+                        report(
+                            Some([(header.clone(), full("h1", "check").into())].into()),
+                            full("1", "check").into(),
+                        ),
+                    ),
+                    (1, report(None, full("a", "lint").into())),
+                ],
+            )
+            .unwrap();
+        assert_eq!(items(merged).0, [diagnostic("check"), diagnostic("lint")]);
+        assert_eq!(reports.previous_result_id(0, &file), Some("1"));
+        assert_eq!(reports.previous_result_id(0, &header), Some("h1"));
+
+        // The unchanged reports of the first server keep its diagnostics.
+        let merged = reports
+            .merge(
+                &file,
+                vec![
+                    (
+                        0,
+                        report(
+                            Some([(header.clone(), unchanged("h1").into())].into()),
+                            unchanged("1").into(),
+                        ),
+                    ),
+                    (1, report(None, full("b", "lint").into())),
+                ],
+            )
+            .unwrap();
+        let (items, related_documents) = items(merged);
+        assert_eq!(items, [diagnostic("check"), diagnostic("lint")]);
+        assert_eq!(
+            related_documents.unwrap()[&header],
+            DocumentDiagnosticReportKind::Full(FullDocumentDiagnosticReport {
+                result_id: None,
+                items: vec![diagnostic("check")],
+            })
+        );
+
+        assert_eq!(
+            reports.merge(&file, vec![(1, report(None, unchanged("a").into()))]),
+            Err(UnknownResultId {
+                backend: 1,
+                uri: file.clone(),
+                result_id: "a".into(),
+            })
+        );
+        reports.remove_backend(0);
+        assert_eq!(reports.previous_result_id(0, &file), None);
+        assert_eq!(reports.previous_result_id(1, &file), Some("b"));
+    }
+
+    #[test]
+    fn test_published_diagnostics() {
+        let file = uri("file:///a.rs");
+        let diagnostic = |source: &str| Diagnostic {
+            source: Some(source.into()),
+            ..Diagnostic::default()
+        };
+        let mut published = PublishedDiagnostics::new();
+        published.publish(
+            0,
+            PublishDiagnosticsParams::new(file.clone(), vec![diagnostic("check")], None),
+        );
+        let merged = published.publish(
+            1,
+            PublishDiagnosticsParams::new(file.clone(), vec![diagnostic("lint")], Some(2)),
+        );
+        assert_eq!(
+            merged.diagnostics,
+            [diagnostic("check"), diagnostic("lint")]
+        );
+        assert_eq!(merged.version, Some(2));
+
+        let merged =
+            published.publish(0, PublishDiagnosticsParams::new(file.clone(), vec![], None));
+        assert_eq!(merged.diagnostics, [diagnostic("lint")]);
+
+        let updates = published.remove_backend(1);
+        assert_eq!(
+            updates,
+            [PublishDiagnosticsParams::new(file.clone(), vec![], None)]
+        );
+        assert!(published.diagnostics(&file).is_empty());
+    }
+}
@@ -5,6 +5,8 @@ use crate::{
     TextDocumentIdentifier, WorkDoneProgressOptions, WorkDoneProgressParams,
 };
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SelectionRangeClientCapabilities {
     /// Whether implementation supports dynamic registration for selection range
@@ -16,12 +18,16 @@ pub struct SelectionRangeClientCapabilities {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SelectionRangeOptions {
     #[serde(flatten)]
     pub work_done_progress_options: WorkDoneProgressOptions,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SelectionRangeRegistrationOptions {
     #[serde(flatten)]
     pub selection_range_options: SelectionRangeOptions,
@@ -30,7 +36,9 @@ pub struct SelectionRangeRegistrationOptions {
     pub registration_options: StaticTextDocumentRegistrationOptions,
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum SelectionRangeProviderCapability {
     Simple(bool),
@@ -38,6 +46,20 @@ pub enum SelectionRangeProviderCapability {
     RegistrationOptions(SelectionRangeRegistrationOptions),
 }
 
+impl<'de> Deserialize<'de> for SelectionRangeProviderCapability {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::deserialize_registration_options(deserializer, Self::RegistrationOptions, |value| {
+            match value {
+                serde_json::Value::Bool(simple) => Ok(Self::Simple(simple)),
+                value => SelectionRangeOptions::deserialize(value).map(Self::Options),
+            }
+        })
+    }
+}
+
 impl From<SelectionRangeRegistrationOptions> for SelectionRangeProviderCapability {
     fn from(from: SelectionRangeRegistrationOptions) -> Self {
         Self::RegistrationOptions(from)
@@ -58,6 +80,8 @@ impl From<bool> for SelectionRangeProviderCapability {
 
 /// A parameter literal used in selection range requests.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SelectionRangeParams {
     /// The text document.
@@ -75,6 +99,8 @@ pub struct SelectionRangeParams {
 
 /// Represents a selection range.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SelectionRange {
     /// Range of the selection.
@@ -84,3 +110,29 @@ pub struct SelectionRange {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent: Option<Box<SelectionRange>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::test_deserialization, DocumentFilter};
+
+    #[test]
+    fn test_registration_options_deserialization() {
+        test_deserialization(
+            r#"{"documentSelector":[{"language":"rust"}],"id":"x"}"#,
+            &SelectionRangeProviderCapability::RegistrationOptions(
+                SelectionRangeRegistrationOptions {
+                    selection_range_options: SelectionRangeOptions::default(),
+                    registration_options: StaticTextDocumentRegistrationOptions {
+                        document_selector: Some(vec![DocumentFilter {
+                            language: Some("rust".to_string()),
+                            scheme: None,
+                            pattern: None,
+                        }]),
+                        id: Some("x".to_string()),
+                    },
+                },
+            ),
+        );
+    }
+}
@@ -13,7 +13,12 @@ use crate::{
 ///
 /// @since 3.16.0
 #[derive(Debug, Eq, PartialEq, Hash, PartialOrd, Clone, Deserialize, Serialize)]
-pub struct SemanticTokenType(Cow<'static, str>);
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SemanticTokenType(
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::cow_str))]
+    Cow<'static, str>,
+);
 
 impl SemanticTokenType {
     pub const NAMESPACE: SemanticTokenType = SemanticTokenType::new("namespace");
@@ -69,7 +74,12 @@ impl From<&'static str> for SemanticTokenType {
 ///
 /// @since 3.16.0
 #[derive(Debug, Eq, PartialEq, Hash, PartialOrd, Clone, Deserialize, Serialize)]
-pub struct SemanticTokenModifier(Cow<'static, str>);
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SemanticTokenModifier(
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::cow_str))]
+    Cow<'static, str>,
+);
 
 impl SemanticTokenModifier {
     pub const DECLARATION: SemanticTokenModifier = SemanticTokenModifier::new("declaration");
@@ -105,7 +115,12 @@ impl From<&'static str> for SemanticTokenModifier {
 }
 
 #[derive(Debug, Eq, PartialEq, Hash, PartialOrd, Clone, Deserialize, Serialize)]
-pub struct TokenFormat(Cow<'static, str>);
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TokenFormat(
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::cow_str))]
+    Cow<'static, str>,
+);
 
 impl TokenFormat {
     pub const RELATIVE: TokenFormat = TokenFormat::new("relative");
@@ -133,6 +148,8 @@ impl From<&'static str> for TokenFormat {
 
 /// @since 3.16.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTokensLegend {
     /// The token types a server uses.
@@ -144,6 +161,7 @@ pub struct SemanticTokensLegend {
 
 /// The actual tokens.
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SemanticToken {
     pub delta_line: u32,
     pub delta_start: u32,
@@ -152,8 +170,33 @@ pub struct SemanticToken {
     pub token_modifiers_bitset: u32,
 }
 
+/// The JSON form of a `Vec<SemanticToken>`, the flattened fields of the tokens.
+#[cfg(feature = "schemars")]
+pub(crate) struct SemanticTokenData;
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for SemanticTokenData {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        "SemanticTokenData".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "Groups of five integers per token: `deltaLine`, `deltaStart`, `length`, `tokenType` and `tokenModifiers`.",
+            "type": "array",
+            "items": { "type": "integer", "format": "uint32", "minimum": 0 },
+        })
+    }
+}
+
 impl SemanticToken {
-    fn deserialize_tokens<'de, D>(deserializer: D) -> Result<Vec<SemanticToken>, D::Error>
+    pub(crate) fn deserialize_tokens<'de, D>(
+        deserializer: D,
+    ) -> Result<Vec<SemanticToken>, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
@@ -177,7 +220,10 @@ impl SemanticToken {
         )
     }
 
-    fn serialize_tokens<S>(tokens: &[SemanticToken], serializer: S) -> Result<S::Ok, S::Error>
+    pub(crate) fn serialize_tokens<S>(
+        tokens: &[SemanticToken],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
@@ -230,6 +276,8 @@ impl SemanticToken {
 
 /// @since 3.16.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTokens {
     /// An optional result id. If provided and clients support delta updating
@@ -246,21 +294,27 @@ pub struct SemanticTokens {
         deserialize_with = "SemanticToken::deserialize_tokens",
         serialize_with = "SemanticToken::serialize_tokens"
     )]
+    #[cfg_attr(feature = "schemars", schemars(with = "SemanticTokenData"))]
     pub data: Vec<SemanticToken>,
 }
 
 /// @since 3.16.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTokensPartialResult {
     #[serde(
         deserialize_with = "SemanticToken::deserialize_tokens",
         serialize_with = "SemanticToken::serialize_tokens"
     )]
+    #[cfg_attr(feature = "schemars", schemars(with = "SemanticTokenData"))]
     pub data: Vec<SemanticToken>,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(untagged)]
 pub enum SemanticTokensResult {
@@ -282,6 +336,8 @@ impl From<SemanticTokensPartialResult> for SemanticTokensResult {
 
 /// @since 3.16.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTokensEdit {
     pub start: u32,
@@ -293,10 +349,13 @@ pub struct SemanticTokensEdit {
         deserialize_with = "SemanticToken::deserialize_tokens_opt",
         serialize_with = "SemanticToken::serialize_tokens_opt"
     )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<SemanticTokenData>"))]
     pub data: Option<Vec<SemanticToken>>,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(untagged)]
 pub enum SemanticTokensFullDeltaResult {
@@ -319,6 +378,8 @@ impl From<SemanticTokensDelta> for SemanticTokensFullDeltaResult {
 
 /// @since 3.16.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTokensDelta {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -332,6 +393,8 @@ pub struct SemanticTokensDelta {
 ///
 /// @since 3.16.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTokensClientCapabilities {
     /// Whether implementation supports dynamic registration. If this is set to `true`
@@ -391,6 +454,8 @@ pub struct SemanticTokensClientCapabilities {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTokensClientCapabilitiesRequests {
     /// The client will send the `textDocument/semanticTokens/range` request if the server provides a corresponding handler.
@@ -403,6 +468,8 @@ pub struct SemanticTokensClientCapabilitiesRequests {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(untagged)]
 pub enum SemanticTokensFullOptions {
@@ -417,6 +484,8 @@ pub enum SemanticTokensFullOptions {
 
 /// @since 3.16.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTokensOptions {
     #[serde(flatten)]
@@ -436,6 +505,8 @@ pub struct SemanticTokensOptions {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTokensRegistrationOptions {
     #[serde(flatten)]
@@ -448,7 +519,9 @@ pub struct SemanticTokensRegistrationOptions {
     pub static_registration_options: StaticRegistrationOptions,
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(untagged)]
 pub enum SemanticTokensServerCapabilities {
@@ -456,6 +529,19 @@ pub enum SemanticTokensServerCapabilities {
     SemanticTokensRegistrationOptions(SemanticTokensRegistrationOptions),
 }
 
+impl<'de> Deserialize<'de> for SemanticTokensServerCapabilities {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::deserialize_registration_options(
+            deserializer,
+            Self::SemanticTokensRegistrationOptions,
+            |value| SemanticTokensOptions::deserialize(value).map(Self::SemanticTokensOptions),
+        )
+    }
+}
+
 impl From<SemanticTokensOptions> for SemanticTokensServerCapabilities {
     fn from(from: SemanticTokensOptions) -> Self {
         SemanticTokensServerCapabilities::SemanticTokensOptions(from)
@@ -469,6 +555,8 @@ impl From<SemanticTokensRegistrationOptions> for SemanticTokensServerCapabilitie
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTokensWorkspaceClientCapabilities {
     /// Whether the client implementation supports a refresh request sent from
@@ -482,6 +570,8 @@ pub struct SemanticTokensWorkspaceClientCapabilities {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTokensParams {
     #[serde(flatten)]
@@ -495,6 +585,8 @@ pub struct SemanticTokensParams {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTokensDeltaParams {
     #[serde(flatten)]
@@ -512,6 +604,8 @@ pub struct SemanticTokensDeltaParams {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTokensRangeParams {
     #[serde(flatten)]
@@ -528,6 +622,8 @@ pub struct SemanticTokensRangeParams {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(untagged)]
 pub enum SemanticTokensRangeResult {
@@ -551,6 +647,7 @@ impl From<SemanticTokensPartialResult> for SemanticTokensRangeResult {
 mod tests {
     use super::*;
     use crate::tests::{test_deserialization, test_serialization};
+    use crate::DocumentFilter;
 
     #[test]
     fn test_semantic_tokens_support_serialization() {
@@ -731,4 +828,43 @@ mod tests {
             r#"{"start":0,"deleteCount":1}"#,
         );
     }
+
+    #[test]
+    fn test_semantic_tokens_registration_options_deserialization() {
+        test_deserialization(
+            r#"{"documentSelector":[{"language":"rust"}],"legend":{"tokenTypes":[],"tokenModifiers":[]},"full":true,"id":"x"}"#,
+            &SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(
+                SemanticTokensRegistrationOptions {
+                    text_document_registration_options: TextDocumentRegistrationOptions {
+                        document_selector: Some(vec![DocumentFilter {
+                            language: Some("rust".to_string()),
+                            scheme: None,
+                            pattern: None,
+                        }]),
+                    },
+                    semantic_tokens_options: SemanticTokensOptions {
+                        full: Some(SemanticTokensFullOptions::Bool(true)),
+                        ..SemanticTokensOptions::default()
+                    },
+                    static_registration_options: StaticRegistrationOptions {
+                        id: Some("x".to_string()),
+                    },
+                },
+            ),
+        );
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_semantic_tokens_schema() {
+        let schema = serde_json::to_value(schemars::schema_for!(SemanticTokens)).unwrap();
+        assert_eq!(schema["properties"]["data"]["type"], "array");
+        assert_eq!(schema["properties"]["data"]["items"]["type"], "integer");
+
+        let schema = serde_json::to_value(schemars::schema_for!(SemanticTokensEdit)).unwrap();
+        assert_eq!(
+            schema["properties"]["data"]["type"],
+            serde_json::json!(["array", "null"])
+        );
+    }
 }
@@ -0,0 +1,370 @@
+/*!
+
+Encoding and decoding of the relative `SemanticToken` format.
+
+Servers push tokens with absolute positions into a [`SemanticTokensBuilder`], which resolves the
+token types and modifiers against a `SemanticTokensLegend` and produces the delta-encoded
+`SemanticTokens`. [`decode_semantic_tokens`] does the reverse.
+
+*/
+
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    Position, Range, SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens,
+    SemanticTokensLegend,
+};
+
+/// An error raised while encoding or decoding semantic tokens.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SemanticTokensError {
+    /// The token type is not part of the legend.
+    UnknownTokenType(SemanticTokenType),
+    /// The token modifier is not part of the legend, or its index does not fit in the bitset.
+    UnknownTokenModifier(SemanticTokenModifier),
+    /// The token spans several lines. Such tokens must be split per line first.
+    MultilineToken(Range),
+    /// An encoded token refers to a token type index outside of the legend.
+    TokenTypeOutOfRange(u32),
+    /// An encoded token sets a modifier bit outside of the legend.
+    TokenModifierOutOfRange(u32),
+}
+
+impl fmt::Display for SemanticTokensError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SemanticTokensError::UnknownTokenType(token_type) => {
+                write!(
+                    f,
+                    "token type `{}` is not in the legend",
+                    token_type.as_str()
+                )
+            }
+            SemanticTokensError::UnknownTokenModifier(modifier) => write!(
+                f,
+                "token modifier `{}` is not in the legend",
+                modifier.as_str()
+            ),
+            SemanticTokensError::MultilineToken(range) => write!(
+                f,
+                "token spans lines {} to {}",
+                range.start.line, range.end.line
+            ),
+            SemanticTokensError::TokenTypeOutOfRange(index) => {
+                write!(f, "token type index {} is not in the legend", index)
+            }
+            SemanticTokensError::TokenModifierOutOfRange(bitset) => {
+                write!(
+                    f,
+                    "token modifier bitset {:#b} is not in the legend",
+                    bitset
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for SemanticTokensError {}
+
+/// A semantic token with an absolute position and resolved type and modifiers.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct AbsoluteSemanticToken {
+    pub line: u32,
+    pub start: u32,
+    pub length: u32,
+    pub token_type: SemanticTokenType,
+    pub token_modifiers: Vec<SemanticTokenModifier>,
+}
+
+impl AbsoluteSemanticToken {
+    /// The range covered by this token, assuming it does not extend past the end of its line.
+    pub fn range(&self) -> Range {
+        Range::new(
+            Position::new(self.line, self.start),
+            Position::new(self.line, self.start.saturating_add(self.length)),
+        )
+    }
+}
+
+/// Collects tokens with absolute positions, in any order, and encodes them into `SemanticTokens`.
+#[derive(Debug, Clone)]
+pub struct SemanticTokensBuilder<'a> {
+    token_types: HashMap<&'a str, u32>,
+    token_modifiers: HashMap<&'a str, u32>,
+    result_id: Option<String>,
+    /// Tokens as `(line, start, length, token_type, token_modifiers_bitset)`.
+    tokens: Vec<[u32; 5]>,
+}
+
+impl<'a> SemanticTokensBuilder<'a> {
+    pub fn new(legend: &'a SemanticTokensLegend) -> Self {
+        let index = |names: Vec<&'a str>| -> HashMap<&'a str, u32> {
+            // The first occurrence wins if the legend contains duplicates.
+            let mut map = HashMap::with_capacity(names.len());
+            for (i, name) in names.into_iter().enumerate() {
+                map.entry(name).or_insert(i as u32);
+            }
+            map
+        };
+        SemanticTokensBuilder {
+            token_types: index(legend.token_types.iter().map(|t| t.as_str()).collect()),
+            token_modifiers: index(legend.token_modifiers.iter().map(|m| m.as_str()).collect()),
+            result_id: None,
+            tokens: Vec::new(),
+        }
+    }
+
+    /// Sets the `result_id` of the produced tokens.
+    pub fn result_id(mut self, result_id: impl Into<String>) -> Self {
+        self.result_id = Some(result_id.into());
+        self
+    }
+
+    /// Adds a token covering `range`, which must not span several lines.
+    pub fn push(
+        &mut self,
+        range: Range,
+        token_type: &SemanticTokenType,
+        token_modifiers: &[SemanticTokenModifier],
+    ) -> Result<(), SemanticTokensError> {
+        if range.start.line != range.end.line || range.end.character < range.start.character {
+            return Err(SemanticTokensError::MultilineToken(range));
+        }
+        self.push_raw(
+            range.start.line,
+            range.start.character,
+            range.end.character - range.start.character,
+            token_type,
+            token_modifiers,
+        )
+    }
+
+    /// Adds a token of `length` starting at `line` and `start`. Unlike [`Self::push`] the length
+    /// may extend past the end of the line, for clients that support multiline tokens.
+    pub fn push_raw(
+        &mut self,
+        line: u32,
+        start: u32,
+        length: u32,
+        token_type: &SemanticTokenType,
+        token_modifiers: &[SemanticTokenModifier],
+    ) -> Result<(), SemanticTokensError> {
+        let token_type_index = *self
+            .token_types
+            .get(token_type.as_str())
+            .ok_or_else(|| SemanticTokensError::UnknownTokenType(token_type.clone()))?;
+        let mut bitset = 0u32;
+        for modifier in token_modifiers {
+            let bit = self
+                .token_modifiers
+                .get(modifier.as_str())
+                .and_then(|&index| 1u32.checked_shl(index))
+                .ok_or_else(|| SemanticTokensError::UnknownTokenModifier(modifier.clone()))?;
+            bitset |= bit;
+        }
+        self.tokens
+            .push([line, start, length, token_type_index, bitset]);
+        Ok(())
+    }
+
+    /// Adds an already resolved token.
+    pub fn push_token(&mut self, token: &AbsoluteSemanticToken) -> Result<(), SemanticTokensError> {
+        self.push_raw(
+            token.line,
+            token.start,
+            token.length,
+            &token.token_type,
+            &token.token_modifiers,
+        )
+    }
+
+    /// Sorts the tokens by position and encodes them.
+    pub fn build(mut self) -> SemanticTokens {
+        // A stable sort keeps tokens that start at the same position in insertion order.
+        self.tokens.sort_by_key(|token| (token[0], token[1]));
+        let mut data = Vec::with_capacity(self.tokens.len());
+        let (mut prev_line, mut prev_start) = (0, 0);
+        for [line, start, length, token_type, token_modifiers_bitset] in self.tokens {
+            let delta_line = line - prev_line;
+            let delta_start = if delta_line == 0 {
+                start - prev_start
+            } else {
+                start
+            };
+            data.push(SemanticToken {
+                delta_line,
+                delta_start,
+                length,
+                token_type,
+                token_modifiers_bitset,
+            });
+            prev_line = line;
+            prev_start = start;
+        }
+        SemanticTokens {
+            result_id: self.result_id,
+            data,
+        }
+    }
+}
+
+/// Decodes relative tokens into tokens with absolute positions, resolving their types and
+/// modifiers against `legend`.
+pub fn decode_semantic_tokens(
+    data: &[SemanticToken],
+    legend: &SemanticTokensLegend,
+) -> Result<Vec<AbsoluteSemanticToken>, SemanticTokensError> {
+    let (mut line, mut start) = (0u32, 0u32);
+    data.iter()
+        .map(|token| {
+            if token.delta_line == 0 {
+                start = start.saturating_add(token.delta_start);
+            } else {
+                line = line.saturating_add(token.delta_line);
+                start = token.delta_start;
+            }
+            let token_type = legend
+                .token_types
+                .get(token.token_type as usize)
+                .ok_or(SemanticTokensError::TokenTypeOutOfRange(token.token_type))?
+                .clone();
+            let token_modifiers = decode_modifiers(token.token_modifiers_bitset, legend)?;
+            Ok(AbsoluteSemanticToken {
+                line,
+                start,
+                length: token.length,
+                token_type,
+                token_modifiers,
+            })
+        })
+        .collect()
+}
+
+fn decode_modifiers(
+    bitset: u32,
+    legend: &SemanticTokensLegend,
+) -> Result<Vec<SemanticTokenModifier>, SemanticTokensError> {
+    let mut modifiers = Vec::new();
+    for bit in 0..32 {
+        if bitset & (1 << bit) == 0 {
+            continue;
+        }
+        let modifier = legend
+            .token_modifiers
+            .get(bit)
+            .ok_or(SemanticTokensError::TokenModifierOutOfRange(bitset))?;
+        modifiers.push(modifier.clone());
+    }
+    Ok(modifiers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legend() -> SemanticTokensLegend {
+        SemanticTokensLegend {
+            token_types: vec![SemanticTokenType::KEYWORD, SemanticTokenType::FUNCTION],
+            token_modifiers: vec![
+                SemanticTokenModifier::DECLARATION,
+                SemanticTokenModifier::STATIC,
+            ],
+        }
+    }
+
+    fn range(line: u32, start: u32, end: u32) -> Range {
+        Range::new(Position::new(line, start), Position::new(line, end))
+    }
+
+    #[test]
+    fn test_build_and_decode() {
+        let legend = legend();
+        let mut builder = SemanticTokensBuilder::new(&legend).result_id("1");
+        builder
+            .push(
+                range(2, 4, 7),
+                &SemanticTokenType::FUNCTION,
+                &[SemanticTokenModifier::STATIC],
+            )
+            .unwrap();
+        builder
+            .push(range(0, 0, 2), &SemanticTokenType::KEYWORD, &[])
+            .unwrap();
+        builder
+            .push(
+                range(2, 10, 11),
+                &SemanticTokenType::FUNCTION,
+                &[
+                    SemanticTokenModifier::DECLARATION,
+                    SemanticTokenModifier::STATIC,
+                ],
+            )
+            .unwrap();
+        let tokens = builder.build();
+
+        assert_eq!(tokens.result_id.as_deref(), Some("1"));
+        let flat: Vec<[u32; 5]> = tokens
+            .data
+            .iter()
+            .map(|t| {
+                [
+                    t.delta_line,
+                    t.delta_start,
+                    t.length,
+                    t.token_type,
+                    t.token_modifiers_bitset,
+                ]
+            })
+            .collect();
+        assert_eq!(flat, [[0, 0, 2, 0, 0], [2, 4, 3, 1, 2], [0, 6, 1, 1, 3]]);
+
+        let decoded = decode_semantic_tokens(&tokens.data, &legend).unwrap();
+        assert_eq!(decoded[1].range(), range(2, 4, 7));
+        assert_eq!(
+            decoded[2],
+            AbsoluteSemanticToken {
+                line: 2,
+                start: 10,
+                length: 1,
+                token_type: SemanticTokenType::FUNCTION,
+                token_modifiers: vec![
+                    SemanticTokenModifier::DECLARATION,
+                    SemanticTokenModifier::STATIC
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let legend = legend();
+        let mut builder = SemanticTokensBuilder::new(&legend);
+        assert_eq!(
+            builder.push(range(0, 0, 1), &SemanticTokenType::MACRO, &[]),
+            Err(SemanticTokensError::UnknownTokenType(
+                SemanticTokenType::MACRO
+            ))
+        );
+        assert_eq!(
+            builder.push(
+                Range::new(Position::new(0, 0), Position::new(1, 0)),
+                &SemanticTokenType::KEYWORD,
+                &[]
+            ),
+            Err(SemanticTokensError::MultilineToken(Range::new(
+                Position::new(0, 0),
+                Position::new(1, 0)
+            )))
+        );
+        assert_eq!(
+            decode_semantic_tokens(
+                &[SemanticToken {
+                    token_type: 5,
+                    ..SemanticToken::default()
+                }],
+                &legend
+            ),
+            Err(SemanticTokensError::TokenTypeOutOfRange(5))
+        );
+    }
+}
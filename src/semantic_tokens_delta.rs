@@ -0,0 +1,393 @@
+/*!
+
+Computing and applying `SemanticTokensDelta` edits for `textDocument/semanticTokens/full/delta`.
+
+*/
+
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    SemanticToken, SemanticTokens, SemanticTokensDelta, SemanticTokensEdit,
+    SemanticTokensFullDeltaResult, Uri,
+};
+
+/// The number of integers a single token occupies in the flattened `data` array.
+const TOKEN_LEN: usize = 5;
+
+/// An error raised when a delta can not be applied to the previous tokens.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SemanticTokensDeltaError {
+    /// The edited data does not divide into tokens of 5 integers.
+    Misaligned,
+    /// An edit refers to data past the end of the previous tokens, or overlaps another edit.
+    OutOfBounds(SemanticTokensEdit),
+    /// No tokens are cached for the document the delta applies to.
+    UnknownResultId,
+}
+
+impl fmt::Display for SemanticTokensDeltaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SemanticTokensDeltaError::Misaligned => {
+                write!(f, "the edited data does not divide into tokens")
+            }
+            SemanticTokensDeltaError::OutOfBounds(edit) => write!(
+                f,
+                "edit at {} deleting {} is out of bounds",
+                edit.start, edit.delete_count
+            ),
+            SemanticTokensDeltaError::UnknownResultId => {
+                write!(f, "no tokens are cached for the previous result id")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SemanticTokensDeltaError {}
+
+/// The integers of the `data` array of `tokens`.
+fn flatten(tokens: &[SemanticToken]) -> Vec<u32> {
+    tokens
+        .iter()
+        .flat_map(|token| {
+            [
+                token.delta_line,
+                token.delta_start,
+                token.length,
+                token.token_type,
+                token.token_modifiers_bitset,
+            ]
+        })
+        .collect()
+}
+
+/// The tokens of a `data` array, if its length is a multiple of 5.
+fn chunk(data: &[u32]) -> Option<Vec<SemanticToken>> {
+    let chunks = data.chunks_exact(TOKEN_LEN);
+    if !chunks.remainder().is_empty() {
+        return None;
+    }
+    Some(
+        chunks
+            .map(|chunk| SemanticToken {
+                delta_line: chunk[0],
+                delta_start: chunk[1],
+                length: chunk[2],
+                token_type: chunk[3],
+                token_modifiers_bitset: chunk[4],
+            })
+            .collect(),
+    )
+}
+
+/// Computes the edits that turn `old` into `new`.
+///
+/// The integers shared at the start and end of both `data` arrays are kept, everything in between
+/// is replaced by a single edit. `SemanticTokensEdit::data` holds whole tokens, so the edit is
+/// widened by up to 4 integers to insert a multiple of 5, but it may start and end anywhere.
+/// Returns no edits if the tokens are equal.
+pub fn diff_semantic_tokens(
+    old: &[SemanticToken],
+    new: &[SemanticToken],
+) -> Vec<SemanticTokensEdit> {
+    let (old, new) = (flatten(old), flatten(new));
+    let prefix = old
+        .iter()
+        .zip(&new)
+        .take_while(|(old, new)| old == new)
+        .count();
+    if prefix == old.len() && prefix == new.len() {
+        return Vec::new();
+    }
+    let mut suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+
+    // Both arrays hold whole tokens, so the shared integers around the edit are enough to widen it.
+    let widen = (TOKEN_LEN - (new.len() - prefix - suffix) % TOKEN_LEN) % TOKEN_LEN;
+    let from_suffix = widen.min(suffix);
+    suffix -= from_suffix;
+    let start = prefix - (widen - from_suffix);
+
+    let inserted = &new[start..new.len() - suffix];
+    vec![SemanticTokensEdit {
+        start: start as u32,
+        delete_count: (old.len() - suffix - start) as u32,
+        data: if inserted.is_empty() {
+            None
+        } else {
+            chunk(inserted)
+        },
+    }]
+}
+
+/// Applies `edits` to `data`. As described in the specification, every edit refers to the data
+/// before any of the edits is applied, as offsets into the flattened integers, which need not be
+/// on token boundaries.
+pub fn apply_semantic_tokens_edits(
+    data: &mut Vec<SemanticToken>,
+    edits: &[SemanticTokensEdit],
+) -> Result<(), SemanticTokensDeltaError> {
+    let mut sorted: Vec<&SemanticTokensEdit> = edits.iter().collect();
+    sorted.sort_by_key(|edit| edit.start);
+
+    let mut flat = flatten(data);
+    let mut end_of_previous = 0;
+    for edit in &sorted {
+        let start = edit.start as usize;
+        let end = start + edit.delete_count as usize;
+        if start < end_of_previous || end > flat.len() {
+            return Err(SemanticTokensDeltaError::OutOfBounds((*edit).clone()));
+        }
+        end_of_previous = end;
+    }
+
+    // Apply back to front so the offsets of the remaining edits stay valid.
+    for edit in sorted.into_iter().rev() {
+        let start = edit.start as usize;
+        let end = start + edit.delete_count as usize;
+        let inserted = edit.data.as_deref().map(flatten).unwrap_or_default();
+        flat.splice(start..end, inserted);
+    }
+    *data = chunk(&flat).ok_or(SemanticTokensDeltaError::Misaligned)?;
+    Ok(())
+}
+
+/// Remembers the last semantic tokens sent for (or received from) each document, so full and
+/// delta requests can be answered or applied without bookkeeping in the caller.
+#[derive(Debug, Clone, Default)]
+pub struct SemanticTokensCache {
+    next_result_id: u64,
+    entries: HashMap<Uri, (String, Vec<SemanticToken>)>,
+}
+
+impl SemanticTokensCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The result id of the tokens cached for `uri`.
+    pub fn result_id(&self, uri: &Uri) -> Option<&str> {
+        self.entries.get(uri).map(|(result_id, _)| &result_id[..])
+    }
+
+    /// The tokens cached for `uri`.
+    pub fn tokens(&self, uri: &Uri) -> Option<&[SemanticToken]> {
+        self.entries.get(uri).map(|(_, data)| &data[..])
+    }
+
+    /// Forgets the tokens of `uri`, for example when the document is closed.
+    pub fn remove(&mut self, uri: &Uri) {
+        self.entries.remove(uri);
+    }
+
+    fn store(&mut self, uri: Uri, data: Vec<SemanticToken>) -> String {
+        self.next_result_id += 1;
+        let result_id = self.next_result_id.to_string();
+        self.entries.insert(uri, (result_id.clone(), data));
+        result_id
+    }
+
+    /// Server side: answers a `textDocument/semanticTokens/full` request with `data`, assigning a
+    /// new result id.
+    pub fn full(&mut self, uri: Uri, data: Vec<SemanticToken>) -> SemanticTokens {
+        let result_id = self.store(uri, data.clone());
+        SemanticTokens {
+            result_id: Some(result_id),
+            data,
+        }
+    }
+
+    /// Server side: answers a `textDocument/semanticTokens/full/delta` request with `data`.
+    ///
+    /// If the tokens for `previous_result_id` are still cached the response is a delta against
+    /// them, otherwise it contains the full tokens.
+    pub fn delta(
+        &mut self,
+        uri: Uri,
+        previous_result_id: &str,
+        data: Vec<SemanticToken>,
+    ) -> SemanticTokensFullDeltaResult {
+        let edits = match self.entries.get(&uri) {
+            Some((result_id, previous)) if result_id == previous_result_id => {
+                diff_semantic_tokens(previous, &data)
+            }
+            _ => return SemanticTokensFullDeltaResult::Tokens(self.full(uri, data)),
+        };
+        let result_id = self.store(uri, data);
+        SemanticTokensFullDeltaResult::TokensDelta(SemanticTokensDelta {
+            result_id: Some(result_id),
+            edits,
+        })
+    }
+
+    /// Client side: updates the cached tokens of `uri` with a response to a full or delta request
+    /// and returns the resulting tokens.
+    ///
+    /// Responses without a result id can not be used as the base of a later delta, so they
+    /// remove the cached tokens.
+    pub fn apply(
+        &mut self,
+        uri: Uri,
+        result: SemanticTokensFullDeltaResult,
+    ) -> Result<Vec<SemanticToken>, SemanticTokensDeltaError> {
+        let (result_id, data) = match result {
+            SemanticTokensFullDeltaResult::Tokens(tokens) => (tokens.result_id, tokens.data),
+            SemanticTokensFullDeltaResult::TokensDelta(delta) => {
+                let mut data = self
+                    .tokens(&uri)
+                    .ok_or(SemanticTokensDeltaError::UnknownResultId)?
+                    .to_vec();
+                apply_semantic_tokens_edits(&mut data, &delta.edits)?;
+                (delta.result_id, data)
+            }
+            SemanticTokensFullDeltaResult::PartialTokensDelta { edits } => {
+                let mut data = self
+                    .tokens(&uri)
+                    .ok_or(SemanticTokensDeltaError::UnknownResultId)?
+                    .to_vec();
+                apply_semantic_tokens_edits(&mut data, &edits)?;
+                (self.result_id(&uri).map(String::from), data)
+            }
+        };
+        match result_id {
+            Some(result_id) => {
+                self.entries.insert(uri, (result_id, data.clone()));
+            }
+            None => self.remove(&uri),
+        }
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(delta_line: u32, delta_start: u32) -> SemanticToken {
+        SemanticToken {
+            delta_line,
+            delta_start,
+            length: 1,
+            token_type: 0,
+            token_modifiers_bitset: 0,
+        }
+    }
+
+    #[test]
+    fn test_diff_and_apply() {
+        let old = vec![token(0, 0), token(1, 0), token(1, 2), token(2, 0)];
+        let new = vec![token(0, 0), token(1, 4), token(2, 0)];
+
+        let edits = diff_semantic_tokens(&old, &new);
+        let mut data = old.clone();
+        apply_semantic_tokens_edits(&mut data, &edits).unwrap();
+        assert_eq!(data, new);
+
+        // A single changed integer is sent as the 5 integers around it.
+        let mut changed = old.clone();
+        changed[1].token_type = 3;
+        assert_eq!(
+            diff_semantic_tokens(&old, &changed),
+            [SemanticTokensEdit {
+                start: 8,
+                delete_count: 5,
+                data: Some(vec![SemanticToken {
+                    delta_line: 3,
+                    delta_start: 0,
+                    length: 1,
+                    token_type: 2,
+                    token_modifiers_bitset: 1,
+                }]),
+            }]
+        );
+
+        assert!(diff_semantic_tokens(&new, &new).is_empty());
+        assert_eq!(
+            diff_semantic_tokens(&old, &old[..2]),
+            [SemanticTokensEdit {
+                start: 10,
+                delete_count: 10,
+                data: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_apply_unaligned_edits() {
+        // Changes the type of the first token and the line of the second.
+        let mut data = vec![token(0, 0), token(1, 0)];
+        let edit = SemanticTokensEdit {
+            start: 3,
+            delete_count: 5,
+            data: Some(vec![SemanticToken {
+                delta_line: 3,
+                delta_start: 0,
+                length: 2,
+                token_type: 0,
+                token_modifiers_bitset: 1,
+            }]),
+        };
+        apply_semantic_tokens_edits(&mut data, &[edit]).unwrap();
+        let mut expected = vec![token(0, 0), token(2, 0)];
+        expected[0].token_type = 3;
+        assert_eq!(data, expected);
+
+        let edit = SemanticTokensEdit {
+            start: 3,
+            delete_count: 1,
+            data: None,
+        };
+        assert_eq!(
+            apply_semantic_tokens_edits(&mut data, &[edit]),
+            Err(SemanticTokensDeltaError::Misaligned)
+        );
+        let edit = SemanticTokensEdit {
+            start: 5,
+            delete_count: 6,
+            data: None,
+        };
+        assert_eq!(
+            apply_semantic_tokens_edits(&mut data, std::slice::from_ref(&edit)),
+            Err(SemanticTokensDeltaError::OutOfBounds(edit))
+        );
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let uri: Uri = "file:///a.rs".parse().unwrap();
+        let mut server = SemanticTokensCache::new();
+        let mut client = SemanticTokensCache::new();
+
+        let full = server.full(uri.clone(), vec![token(0, 0), token(1, 0)]);
+        let previous_result_id = full.result_id.clone().unwrap();
+        client
+            .apply(uri.clone(), SemanticTokensFullDeltaResult::Tokens(full))
+            .unwrap();
+
+        let delta = server.delta(
+            uri.clone(),
+            &previous_result_id,
+            vec![token(0, 0), token(3, 0)],
+        );
+        assert!(matches!(
+            delta,
+            SemanticTokensFullDeltaResult::TokensDelta(_)
+        ));
+        assert_eq!(
+            client.apply(uri.clone(), delta).unwrap(),
+            [token(0, 0), token(3, 0)]
+        );
+        assert_eq!(client.result_id(&uri), server.result_id(&uri));
+
+        // An unknown result id falls back to the full tokens.
+        assert!(matches!(
+            server.delta(uri, "stale", vec![]),
+            SemanticTokensFullDeltaResult::Tokens(_)
+        ));
+    }
+}
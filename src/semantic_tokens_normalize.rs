@@ -0,0 +1,436 @@
+/*!
+
+Rewriting semantic tokens into a form a client accepts.
+
+Servers can compute tokens that span several lines or overlap each other, and let a
+[`SemanticTokensNormalizer`] split and flatten them for clients without
+`multiline_token_support` or `overlapping_token_support`.
+
+*/
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::{
+    semantic_tokens_builder::{AbsoluteSemanticToken, SemanticTokensBuilder, SemanticTokensError},
+    text_document::LineIndex,
+    Position, Range, SemanticTokenModifier, SemanticTokenType, SemanticTokens,
+    SemanticTokensClientCapabilities, SemanticTokensLegend,
+};
+
+/// A semantic token covering an arbitrary range, which may span several lines.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct RangeSemanticToken {
+    pub range: Range,
+    pub token_type: SemanticTokenType,
+    pub token_modifiers: Vec<SemanticTokenModifier>,
+}
+
+impl RangeSemanticToken {
+    pub fn new(
+        range: Range,
+        token_type: SemanticTokenType,
+        token_modifiers: Vec<SemanticTokenModifier>,
+    ) -> RangeSemanticToken {
+        RangeSemanticToken {
+            range,
+            token_type,
+            token_modifiers,
+        }
+    }
+}
+
+/// Rewrites tokens according to a client's `SemanticTokensClientCapabilities`.
+///
+/// * Tokens whose type is not listed in the client's `token_types` (or the server's legend) are
+///   dropped, and so are modifiers missing from the client's `token_modifiers`.
+/// * Without `multiline_token_support`, tokens spanning several lines are split into one token
+///   per line.
+/// * Without `overlapping_token_support`, overlapping tokens are flattened so that the innermost
+///   token wins. Flattening works line by line, so multiline tokens are split as well.
+#[derive(Debug, Clone)]
+pub struct SemanticTokensNormalizer<'a> {
+    capabilities: &'a SemanticTokensClientCapabilities,
+    legend: &'a SemanticTokensLegend,
+}
+
+impl<'a> SemanticTokensNormalizer<'a> {
+    pub fn new(
+        capabilities: &'a SemanticTokensClientCapabilities,
+        legend: &'a SemanticTokensLegend,
+    ) -> Self {
+        SemanticTokensNormalizer {
+            capabilities,
+            legend,
+        }
+    }
+
+    fn supports_multiline(&self) -> bool {
+        self.capabilities.multiline_token_support == Some(true)
+    }
+
+    fn supports_overlapping(&self) -> bool {
+        self.capabilities.overlapping_token_support == Some(true)
+    }
+
+    /// Normalises `tokens`, which are positioned in the document described by `line_index`, and
+    /// returns them sorted by position.
+    ///
+    /// The `length` of a returned multiline token counts the line terminators it spans.
+    pub fn normalize(
+        &self,
+        tokens: impl IntoIterator<Item = RangeSemanticToken>,
+        line_index: &LineIndex,
+    ) -> Vec<AbsoluteSemanticToken> {
+        let tokens = tokens.into_iter().filter_map(|token| self.filter(token));
+        let split = !self.supports_multiline() || !self.supports_overlapping();
+
+        let mut result = Vec::new();
+        let mut segments = Vec::new();
+        for (index, token) in tokens.enumerate() {
+            let range = Range::new(
+                line_index.clamp(token.range.start),
+                line_index.clamp(token.range.end),
+            );
+            if range.end <= range.start {
+                continue;
+            }
+            if !split || range.start.line == range.end.line {
+                segments.push(Segment::new(range, line_index, index, token));
+                continue;
+            }
+            for line in range.start.line..=range.end.line {
+                let start = if line == range.start.line {
+                    range.start.character
+                } else {
+                    0
+                };
+                let end = if line == range.end.line {
+                    range.end.character
+                } else {
+                    line_index.line_len(line).unwrap_or(0)
+                };
+                if start < end {
+                    let range = Range::new(Position::new(line, start), Position::new(line, end));
+                    segments.push(Segment::new(range, line_index, index, token.clone()));
+                }
+            }
+        }
+
+        segments.sort_by_key(|segment| (segment.line, segment.start));
+        if self.supports_overlapping() {
+            result.extend(segments.into_iter().map(Segment::into_token));
+        } else {
+            let mut rest = &segments[..];
+            while let Some(first) = rest.first() {
+                let line_len = rest
+                    .iter()
+                    .take_while(|segment| segment.line == first.line)
+                    .count();
+                flatten_line(&rest[..line_len], &mut result);
+                rest = &rest[line_len..];
+            }
+        }
+        result
+    }
+
+    /// Normalises `tokens` and encodes them using the server's legend.
+    pub fn encode(
+        &self,
+        tokens: impl IntoIterator<Item = RangeSemanticToken>,
+        line_index: &LineIndex,
+    ) -> Result<SemanticTokens, SemanticTokensError> {
+        let mut builder = SemanticTokensBuilder::new(self.legend);
+        for token in self.normalize(tokens, line_index) {
+            builder.push_token(&token)?;
+        }
+        Ok(builder.build())
+    }
+
+    fn filter(&self, mut token: RangeSemanticToken) -> Option<RangeSemanticToken> {
+        if !self.capabilities.token_types.contains(&token.token_type)
+            || !self.legend.token_types.contains(&token.token_type)
+        {
+            return None;
+        }
+        token.token_modifiers.retain(|modifier| {
+            self.capabilities.token_modifiers.contains(modifier)
+                && self.legend.token_modifiers.contains(modifier)
+        });
+        Some(token)
+    }
+}
+
+/// A token on a single line, or a multiline token that is kept as is.
+#[derive(Debug, Clone)]
+struct Segment {
+    line: u32,
+    start: u32,
+    length: u32,
+    /// The position of the token in the input, later tokens win ties when flattening.
+    index: usize,
+    token: RangeSemanticToken,
+}
+
+impl Segment {
+    fn new(range: Range, line_index: &LineIndex, index: usize, token: RangeSemanticToken) -> Self {
+        let length = if range.start.line == range.end.line {
+            range.end.character - range.start.character
+        } else {
+            let mut length = line_index.line_len(range.start.line).unwrap_or(0)
+                - range.start.character
+                + line_index.line_terminator_len(range.start.line);
+            for line in range.start.line + 1..range.end.line {
+                length +=
+                    line_index.line_len(line).unwrap_or(0) + line_index.line_terminator_len(line);
+            }
+            length + range.end.character
+        };
+        Segment {
+            line: range.start.line,
+            start: range.start.character,
+            length,
+            index,
+            token,
+        }
+    }
+
+    fn end(&self) -> u32 {
+        self.start + self.length
+    }
+
+    fn into_token(self) -> AbsoluteSemanticToken {
+        AbsoluteSemanticToken {
+            line: self.line,
+            start: self.start,
+            length: self.length,
+            token_type: self.token.token_type,
+            token_modifiers: self.token.token_modifiers,
+        }
+    }
+}
+
+/// Flattens the single-line `segments`, sorted by start, into non-overlapping tokens. Where tokens
+/// overlap the shortest one wins, and the later one among tokens of equal length.
+///
+/// The boundaries of the segments are swept in order, keeping the segments that cover the
+/// current position in a heap ordered by which one wins.
+fn flatten_line(segments: &[Segment], result: &mut Vec<AbsoluteSemanticToken>) {
+    let overlapping = segments
+        .windows(2)
+        .any(|pair| pair[1].start < pair[0].end());
+    if !overlapping {
+        result.extend(segments.iter().cloned().map(Segment::into_token));
+        return;
+    }
+
+    let mut boundaries: Vec<u32> = segments
+        .iter()
+        .flat_map(|segment| [segment.start, segment.end()])
+        .collect();
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    let mut open = BinaryHeap::new();
+    let mut next = 0;
+    let mut current: Option<(usize, AbsoluteSemanticToken)> = None;
+    for window in boundaries.windows(2) {
+        let (start, end) = (window[0], window[1]);
+        while let Some(segment) = segments.get(next).filter(|segment| segment.start <= start) {
+            open.push(Reverse((segment.length, Reverse(segment.index), next)));
+            next += 1;
+        }
+        // Segments that ended are only removed once they would win.
+        while let Some(&Reverse((_, _, i))) = open.peek() {
+            if segments[i].end() > start {
+                break;
+            }
+            open.pop();
+        }
+        let i = match open.peek() {
+            Some(&Reverse((_, _, i))) => i,
+            None => {
+                result.extend(current.take().map(|(_, token)| token));
+                continue;
+            }
+        };
+        let segment = &segments[i];
+        match &mut current {
+            Some((current_i, token)) if *current_i == i && token.start + token.length == start => {
+                token.length += end - start;
+            }
+            _ => {
+                result.extend(current.take().map(|(_, token)| token));
+                current = Some((
+                    i,
+                    AbsoluteSemanticToken {
+                        line: segment.line,
+                        start,
+                        length: end - start,
+                        token_type: segment.token.token_type.clone(),
+                        token_modifiers: segment.token.token_modifiers.clone(),
+                    },
+                ));
+            }
+        }
+    }
+    result.extend(current.map(|(_, token)| token));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PositionEncodingKind;
+
+    fn capabilities(multiline: bool, overlapping: bool) -> SemanticTokensClientCapabilities {
+        SemanticTokensClientCapabilities {
+            token_types: vec![SemanticTokenType::STRING, SemanticTokenType::VARIABLE],
+            token_modifiers: vec![SemanticTokenModifier::READONLY],
+            multiline_token_support: Some(multiline),
+            overlapping_token_support: Some(overlapping),
+            ..SemanticTokensClientCapabilities::default()
+        }
+    }
+
+    fn legend() -> SemanticTokensLegend {
+        SemanticTokensLegend {
+            token_types: vec![
+                SemanticTokenType::STRING,
+                SemanticTokenType::VARIABLE,
+                SemanticTokenType::MACRO,
+            ],
+            token_modifiers: vec![
+                SemanticTokenModifier::READONLY,
+                SemanticTokenModifier::STATIC,
+            ],
+        }
+    }
+
+    fn token(
+        line: u32,
+        start: u32,
+        length: u32,
+        token_type: SemanticTokenType,
+    ) -> AbsoluteSemanticToken {
+        AbsoluteSemanticToken {
+            line,
+            start,
+            length,
+            token_type,
+            token_modifiers: vec![],
+        }
+    }
+
+    fn range(start: (u32, u32), end: (u32, u32)) -> Range {
+        Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1))
+    }
+
+    #[test]
+    fn test_split_multiline_tokens() {
+        let line_index = LineIndex::new("let s = \"a\r\nbc\nd\";", PositionEncodingKind::UTF16);
+        let tokens = vec![RangeSemanticToken::new(
+            range((0, 8), (2, 2)),
+            SemanticTokenType::STRING,
+            vec![],
+        )];
+        let legend = legend();
+
+        let split_lines = capabilities(false, true);
+        let normalizer = SemanticTokensNormalizer::new(&split_lines, &legend);
+        assert_eq!(
+            normalizer.normalize(tokens.clone(), &line_index),
+            [
+                token(0, 8, 2, SemanticTokenType::STRING),
+                token(1, 0, 2, SemanticTokenType::STRING),
+                token(2, 0, 2, SemanticTokenType::STRING),
+            ]
+        );
+
+        let multiline = capabilities(true, true);
+        let normalizer = SemanticTokensNormalizer::new(&multiline, &legend);
+        assert_eq!(
+            normalizer.normalize(tokens, &line_index),
+            [token(0, 8, 9, SemanticTokenType::STRING)]
+        );
+    }
+
+    #[test]
+    fn test_flatten_overlapping_tokens() {
+        let line_index = LineIndex::new("format!(\"{x}\")", PositionEncodingKind::UTF16);
+        let capabilities = capabilities(true, false);
+        let legend = legend();
+        let normalizer = SemanticTokensNormalizer::new(&capabilities, &legend);
+
+        let normalized = normalizer.normalize(
+            vec![
+                RangeSemanticToken::new(range((0, 8), (0, 13)), SemanticTokenType::STRING, vec![]),
+                RangeSemanticToken::new(
+                    range((0, 10), (0, 11)),
+                    SemanticTokenType::VARIABLE,
+                    vec![
+                        SemanticTokenModifier::READONLY,
+                        SemanticTokenModifier::STATIC,
+                    ],
+                ),
+                // Not supported by the client.
+                RangeSemanticToken::new(range((0, 0), (0, 7)), SemanticTokenType::MACRO, vec![]),
+            ],
+            &line_index,
+        );
+        assert_eq!(
+            normalized,
+            [
+                token(0, 8, 2, SemanticTokenType::STRING),
+                AbsoluteSemanticToken {
+                    token_modifiers: vec![SemanticTokenModifier::READONLY],
+                    ..token(0, 10, 1, SemanticTokenType::VARIABLE)
+                },
+                token(0, 11, 2, SemanticTokenType::STRING),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flatten_long_line() {
+        const COUNT: u32 = 50_000;
+        let line_index = LineIndex::new(
+            &"x".repeat(2 * COUNT as usize + 1),
+            PositionEncodingKind::UTF16,
+        );
+        let capabilities = capabilities(true, false);
+        let legend = legend();
+        let normalizer = SemanticTokensNormalizer::new(&capabilities, &legend);
+
+        // A string spanning the line, with a variable at every other character.
+        let variables = (0..COUNT).map(|i| {
+            RangeSemanticToken::new(
+                range((0, 2 * i + 1), (0, 2 * i + 2)),
+                SemanticTokenType::VARIABLE,
+                vec![],
+            )
+        });
+        let string = RangeSemanticToken::new(
+            range((0, 0), (0, 2 * COUNT + 1)),
+            SemanticTokenType::STRING,
+            vec![],
+        );
+        let normalized = normalizer.normalize(
+            std::iter::once(string).chain(variables.clone()),
+            &line_index,
+        );
+        assert_eq!(normalized.len(), 2 * COUNT as usize + 1);
+        assert_eq!(normalized[0], token(0, 0, 1, SemanticTokenType::STRING));
+        assert_eq!(normalized[1], token(0, 1, 1, SemanticTokenType::VARIABLE));
+        assert_eq!(
+            normalized[2 * COUNT as usize],
+            token(0, 2 * COUNT, 1, SemanticTokenType::STRING)
+        );
+
+        // Tokens that do not overlap are passed through.
+        let normalized = normalizer.normalize(variables, &line_index);
+        assert_eq!(normalized.len(), COUNT as usize);
+        assert_eq!(
+            normalized[COUNT as usize - 1],
+            token(0, 2 * COUNT - 1, 1, SemanticTokenType::VARIABLE)
+        );
+    }
+}
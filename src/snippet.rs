@@ -0,0 +1,870 @@
+/*!
+
+The snippet syntax used by `InsertTextFormat::SNIPPET`.
+
+Snippets can be parsed into a [`Snippet`] (or just validated), written back with escaping
+applied, or rendered to plain text for clients without `snippet_support`.
+
+Based on: <https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#snippet_syntax>
+
+*/
+
+use std::fmt;
+
+/// A parsed snippet.
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct Snippet {
+    pub elements: Vec<SnippetElement>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SnippetElement {
+    /// Literal text, without escapes.
+    Text(String),
+    /// `$1`, `${1}` or `${1/regex/format/options}`.
+    Tabstop {
+        index: u32,
+        transform: Option<SnippetTransform>,
+    },
+    /// `${1:default}`.
+    Placeholder {
+        index: u32,
+        elements: Vec<SnippetElement>,
+    },
+    /// `${1|one,two,three|}`.
+    Choice { index: u32, options: Vec<String> },
+    /// `$name`, `${name}`, `${name:default}` or `${name/regex/format/options}`.
+    Variable {
+        name: String,
+        default: Option<Vec<SnippetElement>>,
+        transform: Option<SnippetTransform>,
+    },
+}
+
+/// A regular expression transformation applied to a tabstop or variable.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SnippetTransform {
+    /// The JavaScript regular expression, as written in the snippet.
+    pub regex: String,
+    pub format: Vec<SnippetFormat>,
+    /// The JavaScript regular expression options, like `g` or `i`.
+    pub options: String,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SnippetFormat {
+    /// Literal text, without escapes.
+    Text(String),
+    /// `$1` or `${1}`: the text of a capture group.
+    Group(u32),
+    /// `${1:/upcase}` and friends.
+    Case { group: u32, modifier: CaseModifier },
+    /// `${1:+if}`, `${1:-else}`, `${1:else}` or `${1:?if:else}`.
+    Conditional {
+        group: u32,
+        if_matched: Option<String>,
+        otherwise: Option<String>,
+    },
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum CaseModifier {
+    Upcase,
+    Downcase,
+    Capitalize,
+    Camelcase,
+    Pascalcase,
+}
+
+impl CaseModifier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CaseModifier::Upcase => "upcase",
+            CaseModifier::Downcase => "downcase",
+            CaseModifier::Capitalize => "capitalize",
+            CaseModifier::Camelcase => "camelcase",
+            CaseModifier::Pascalcase => "pascalcase",
+        }
+    }
+
+    fn from_str(s: &str) -> Option<Self> {
+        Some(match s {
+            "upcase" => CaseModifier::Upcase,
+            "downcase" => CaseModifier::Downcase,
+            "capitalize" => CaseModifier::Capitalize,
+            "camelcase" => CaseModifier::Camelcase,
+            "pascalcase" => CaseModifier::Pascalcase,
+            _ => return None,
+        })
+    }
+}
+
+/// An error describing a malformed snippet.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SnippetError {
+    /// The byte offset in the snippet at which the error was detected.
+    pub offset: usize,
+    pub kind: SnippetErrorKind,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SnippetErrorKind {
+    /// A `${` construct is missing its closing `}`.
+    Unclosed,
+    /// `${` is not followed by a tabstop index or a variable name.
+    ExpectedIndexOrVariable,
+    /// A tabstop index does not fit in a `u32`.
+    InvalidIndex,
+    /// An unexpected character follows the index or name in a `${` construct.
+    UnexpectedCharacter(char),
+    /// A choice is missing its closing `|}`.
+    UnclosedChoice,
+    /// A transform is missing one of its `/` separators.
+    UnclosedTransform,
+    /// A format item in a transform is malformed.
+    InvalidFormat,
+    /// A placeholder contains a placeholder with the same index.
+    RecursivePlaceholder(u32),
+}
+
+impl fmt::Display for SnippetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            SnippetErrorKind::Unclosed => write!(f, "missing `}}`")?,
+            SnippetErrorKind::ExpectedIndexOrVariable => {
+                write!(f, "expected a tabstop index or a variable name")?
+            }
+            SnippetErrorKind::InvalidIndex => write!(f, "tabstop index is too large")?,
+            SnippetErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected `{}`", c)?,
+            SnippetErrorKind::UnclosedChoice => write!(f, "missing `|}}` after choice")?,
+            SnippetErrorKind::UnclosedTransform => write!(f, "missing `/` in transform")?,
+            SnippetErrorKind::InvalidFormat => write!(f, "invalid format in transform")?,
+            SnippetErrorKind::RecursivePlaceholder(index) => {
+                write!(f, "placeholder ${} contains itself", index)?
+            }
+        }
+        write!(f, " at offset {}", self.offset)
+    }
+}
+
+impl std::error::Error for SnippetError {}
+
+impl std::str::FromStr for Snippet {
+    type Err = SnippetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Snippet::parse(s)
+    }
+}
+
+impl Snippet {
+    /// Parses `input`. A `$` that does not start a tabstop, placeholder, choice or variable is
+    /// taken literally, every other malformed construct is an error.
+    pub fn parse(input: &str) -> Result<Snippet, SnippetError> {
+        let mut parser = Parser {
+            input,
+            pos: 0,
+            open_placeholders: Vec::new(),
+        };
+        let elements = parser.parse_any(false)?;
+        Ok(Snippet { elements })
+    }
+
+    /// Renders the snippet as plain text, as it would appear right after insertion.
+    ///
+    /// Tabstops are removed, placeholders are replaced by their content, choices by their first
+    /// option and variables by `resolve_variable`, falling back to their default. Transforms are
+    /// not applied.
+    pub fn render(&self, mut resolve_variable: impl FnMut(&str) -> Option<String>) -> String {
+        let mut out = String::new();
+        render(&self.elements, &mut resolve_variable, &mut out);
+        out
+    }
+
+    /// Renders the snippet as plain text without resolving any variables.
+    pub fn to_plain_text(&self) -> String {
+        self.render(|_| None)
+    }
+}
+
+fn render(
+    elements: &[SnippetElement],
+    resolve_variable: &mut dyn FnMut(&str) -> Option<String>,
+    out: &mut String,
+) {
+    for element in elements {
+        match element {
+            SnippetElement::Text(text) => out.push_str(text),
+            SnippetElement::Tabstop { .. } => (),
+            SnippetElement::Placeholder { elements, .. } => render(elements, resolve_variable, out),
+            SnippetElement::Choice { options, .. } => {
+                out.push_str(options.first().map_or("", |s| s))
+            }
+            SnippetElement::Variable { name, default, .. } => match resolve_variable(name) {
+                Some(value) => out.push_str(&value),
+                None => {
+                    if let Some(default) = default {
+                        render(default, resolve_variable, out)
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// Checks that `input` is a well-formed snippet.
+pub fn validate_snippet(input: &str) -> Result<(), SnippetError> {
+    Snippet::parse(input).map(drop)
+}
+
+/// Escapes `text` so it is inserted literally when used in a snippet.
+pub fn escape_snippet_text(text: &str) -> String {
+    escape(text, &['\\', '$', '}'])
+}
+
+/// Escapes `text` so it can be used as an option of a choice.
+pub fn escape_choice_text(text: &str) -> String {
+    escape(text, &['\\', '$', '}', ',', '|'])
+}
+
+fn escape(text: &str, special: &[char]) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if special.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    /// The indices of the placeholders being parsed, to reject placeholders containing themselves.
+    open_placeholders: Vec<u32>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error<T>(&self, kind: SnippetErrorKind) -> Result<T, SnippetError> {
+        Err(SnippetError {
+            offset: self.pos,
+            kind,
+        })
+    }
+
+    fn expect_close(&mut self, open: usize) -> Result<(), SnippetError> {
+        match self.peek() {
+            Some('}') => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) => self.error(SnippetErrorKind::UnexpectedCharacter(c)),
+            None => Err(SnippetError {
+                offset: open,
+                kind: SnippetErrorKind::Unclosed,
+            }),
+        }
+    }
+
+    /// Parses elements until the end of input, or until an unescaped `}` if `nested`.
+    fn parse_any(&mut self, nested: bool) -> Result<Vec<SnippetElement>, SnippetError> {
+        let mut elements = Vec::new();
+        let mut text = String::new();
+        loop {
+            match self.peek() {
+                None => break,
+                Some('}') if nested => break,
+                Some('$') => {
+                    let start = self.pos;
+                    self.pos += 1;
+                    match self.parse_dollar(start)? {
+                        Some(element) => {
+                            if !text.is_empty() {
+                                elements.push(SnippetElement::Text(std::mem::take(&mut text)));
+                            }
+                            elements.push(element);
+                        }
+                        None => text.push('$'),
+                    }
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(c @ ('\\' | '$' | '}')) => {
+                            self.pos += 1;
+                            text.push(c);
+                        }
+                        _ => text.push('\\'),
+                    }
+                }
+                Some(c) => {
+                    self.pos += c.len_utf8();
+                    text.push(c);
+                }
+            }
+        }
+        if !text.is_empty() {
+            elements.push(SnippetElement::Text(text));
+        }
+        Ok(elements)
+    }
+
+    fn parse_int(&mut self) -> Result<Option<u32>, SnippetError> {
+        let start = self.pos;
+        while matches!(self.peek(), Some('0'..='9')) {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Ok(None);
+        }
+        match self.input[start..self.pos].parse() {
+            Ok(index) => Ok(Some(index)),
+            Err(_) => Err(SnippetError {
+                offset: start,
+                kind: SnippetErrorKind::InvalidIndex,
+            }),
+        }
+    }
+
+    fn parse_var(&mut self) -> Option<String> {
+        let start = self.pos;
+        if !matches!(self.peek(), Some('_' | 'a'..='z' | 'A'..='Z')) {
+            return None;
+        }
+        while matches!(self.peek(), Some('_' | 'a'..='z' | 'A'..='Z' | '0'..='9')) {
+            self.pos += 1;
+        }
+        Some(self.input[start..self.pos].to_string())
+    }
+
+    /// Parses what follows a `$` at `start`. Returns `None` if the `$` is a literal.
+    fn parse_dollar(&mut self, start: usize) -> Result<Option<SnippetElement>, SnippetError> {
+        if let Some(index) = self.parse_int()? {
+            return Ok(Some(SnippetElement::Tabstop {
+                index,
+                transform: None,
+            }));
+        }
+        if let Some(name) = self.parse_var() {
+            return Ok(Some(SnippetElement::Variable {
+                name,
+                default: None,
+                transform: None,
+            }));
+        }
+        if !self.eat('{') {
+            return Ok(None);
+        }
+
+        if let Some(index) = self.parse_int()? {
+            let element = match self.peek() {
+                Some(':') => {
+                    if self.open_placeholders.contains(&index) {
+                        return Err(SnippetError {
+                            offset: start,
+                            kind: SnippetErrorKind::RecursivePlaceholder(index),
+                        });
+                    }
+                    self.pos += 1;
+                    self.open_placeholders.push(index);
+                    let elements = self.parse_any(true)?;
+                    self.open_placeholders.pop();
+                    SnippetElement::Placeholder { index, elements }
+                }
+                Some('|') => {
+                    self.pos += 1;
+                    let options = self.parse_choice(start)?;
+                    return Ok(Some(SnippetElement::Choice { index, options }));
+                }
+                Some('/') => SnippetElement::Tabstop {
+                    index,
+                    transform: Some(self.parse_transform()?),
+                },
+                _ => SnippetElement::Tabstop {
+                    index,
+                    transform: None,
+                },
+            };
+            self.expect_close(start)?;
+            return Ok(Some(element));
+        }
+
+        if let Some(name) = self.parse_var() {
+            let element = match self.peek() {
+                Some(':') => {
+                    self.pos += 1;
+                    SnippetElement::Variable {
+                        name,
+                        default: Some(self.parse_any(true)?),
+                        transform: None,
+                    }
+                }
+                Some('/') => SnippetElement::Variable {
+                    name,
+                    default: None,
+                    transform: Some(self.parse_transform()?),
+                },
+                _ => SnippetElement::Variable {
+                    name,
+                    default: None,
+                    transform: None,
+                },
+            };
+            self.expect_close(start)?;
+            return Ok(Some(element));
+        }
+
+        match self.peek() {
+            None => Err(SnippetError {
+                offset: start,
+                kind: SnippetErrorKind::Unclosed,
+            }),
+            Some(_) => self.error(SnippetErrorKind::ExpectedIndexOrVariable),
+        }
+    }
+
+    /// Parses the options of a choice, after the opening `|`.
+    fn parse_choice(&mut self, start: usize) -> Result<Vec<String>, SnippetError> {
+        let mut options = Vec::new();
+        let mut option = String::new();
+        loop {
+            match self.bump() {
+                None => {
+                    return Err(SnippetError {
+                        offset: start,
+                        kind: SnippetErrorKind::UnclosedChoice,
+                    })
+                }
+                Some('\\') => match self.peek() {
+                    Some(c @ ('\\' | '$' | '}' | ',' | '|')) => {
+                        self.pos += 1;
+                        option.push(c);
+                    }
+                    _ => option.push('\\'),
+                },
+                Some(',') => options.push(std::mem::take(&mut option)),
+                Some('|') if self.eat('}') => {
+                    options.push(option);
+                    return Ok(options);
+                }
+                Some(c) => option.push(c),
+            }
+        }
+    }
+
+    /// Parses `/regex/format/options`, up to but excluding the closing `}`.
+    fn parse_transform(&mut self) -> Result<SnippetTransform, SnippetError> {
+        self.eat('/');
+        let mut regex = String::new();
+        loop {
+            match self.bump() {
+                None => return self.error(SnippetErrorKind::UnclosedTransform),
+                Some('/') => break,
+                // Escapes are part of the regular expression, except for an escaped `/`.
+                Some('\\') => match self.bump() {
+                    Some('/') => regex.push('/'),
+                    Some(c) => {
+                        regex.push('\\');
+                        regex.push(c);
+                    }
+                    None => return self.error(SnippetErrorKind::UnclosedTransform),
+                },
+                Some(c) => regex.push(c),
+            }
+        }
+
+        let mut format = Vec::new();
+        let mut text = String::new();
+        loop {
+            match self.peek() {
+                None => return self.error(SnippetErrorKind::UnclosedTransform),
+                Some('/') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some('$') => {
+                    let start = self.pos;
+                    self.pos += 1;
+                    match self.parse_format(start)? {
+                        Some(item) => {
+                            if !text.is_empty() {
+                                format.push(SnippetFormat::Text(std::mem::take(&mut text)));
+                            }
+                            format.push(item);
+                        }
+                        None => text.push('$'),
+                    }
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(c @ ('\\' | '$' | '/')) => {
+                            self.pos += 1;
+                            text.push(c);
+                        }
+                        _ => text.push('\\'),
+                    }
+                }
+                Some(c) => {
+                    self.pos += c.len_utf8();
+                    text.push(c);
+                }
+            }
+        }
+        if !text.is_empty() {
+            format.push(SnippetFormat::Text(text));
+        }
+
+        let options_start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+        Ok(SnippetTransform {
+            regex,
+            format,
+            options: self.input[options_start..self.pos].to_string(),
+        })
+    }
+
+    /// Parses a format item after `$`. Returns `None` if the `$` is a literal.
+    fn parse_format(&mut self, start: usize) -> Result<Option<SnippetFormat>, SnippetError> {
+        if let Some(group) = self.parse_int()? {
+            return Ok(Some(SnippetFormat::Group(group)));
+        }
+        if !self.eat('{') {
+            return Ok(None);
+        }
+        let group = match self.parse_int()? {
+            Some(group) => group,
+            None => return self.error(SnippetErrorKind::InvalidFormat),
+        };
+        let item = if self.eat(':') {
+            match self.peek() {
+                Some('/') => {
+                    self.pos += 1;
+                    let name = self.parse_var().unwrap_or_default();
+                    match CaseModifier::from_str(&name) {
+                        Some(modifier) => SnippetFormat::Case { group, modifier },
+                        None => return self.error(SnippetErrorKind::InvalidFormat),
+                    }
+                }
+                Some('+') => {
+                    self.pos += 1;
+                    SnippetFormat::Conditional {
+                        group,
+                        if_matched: Some(self.parse_format_text(false)),
+                        otherwise: None,
+                    }
+                }
+                Some('?') => {
+                    self.pos += 1;
+                    let if_matched = self.parse_format_text(true);
+                    if !self.eat(':') {
+                        return self.error(SnippetErrorKind::InvalidFormat);
+                    }
+                    SnippetFormat::Conditional {
+                        group,
+                        if_matched: Some(if_matched),
+                        otherwise: Some(self.parse_format_text(false)),
+                    }
+                }
+                Some('-') => {
+                    self.pos += 1;
+                    SnippetFormat::Conditional {
+                        group,
+                        if_matched: None,
+                        otherwise: Some(self.parse_format_text(false)),
+                    }
+                }
+                _ => SnippetFormat::Conditional {
+                    group,
+                    if_matched: None,
+                    otherwise: Some(self.parse_format_text(false)),
+                },
+            }
+        } else {
+            SnippetFormat::Group(group)
+        };
+        self.expect_close(start)?;
+        Ok(Some(item))
+    }
+
+    /// Parses the text of a conditional format item, up to `}` (or `:` if `stop_at_colon`).
+    fn parse_format_text(&mut self, stop_at_colon: bool) -> String {
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            match c {
+                '}' => break,
+                ':' if stop_at_colon => break,
+                '\\' => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(c @ ('\\' | '$' | '}' | ':')) => {
+                            self.pos += 1;
+                            text.push(c);
+                        }
+                        _ => text.push('\\'),
+                    }
+                }
+                c => {
+                    self.pos += c.len_utf8();
+                    text.push(c);
+                }
+            }
+        }
+        text
+    }
+}
+
+impl fmt::Display for Snippet {
+    /// Writes the snippet back in snippet syntax, escaping text where needed.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_elements(f, &self.elements)
+    }
+}
+
+fn write_elements(f: &mut fmt::Formatter<'_>, elements: &[SnippetElement]) -> fmt::Result {
+    for (i, element) in elements.iter().enumerate() {
+        // `$1` followed by `2` would read as `$12`, so use braces if the next text could continue
+        // the index or name.
+        let next_continues = match elements.get(i + 1) {
+            Some(SnippetElement::Text(text)) => {
+                text.starts_with(|c: char| c == '_' || c.is_ascii_alphanumeric())
+            }
+            _ => false,
+        };
+        match element {
+            SnippetElement::Text(text) => f.write_str(&escape_snippet_text(text))?,
+            SnippetElement::Tabstop {
+                index,
+                transform: None,
+            } if !next_continues => write!(f, "${}", index)?,
+            SnippetElement::Tabstop { index, transform } => {
+                write!(f, "${{{}", index)?;
+                if let Some(transform) = transform {
+                    write_transform(f, transform)?;
+                }
+                f.write_str("}")?;
+            }
+            SnippetElement::Placeholder { index, elements } => {
+                write!(f, "${{{}:", index)?;
+                write_elements(f, elements)?;
+                f.write_str("}")?;
+            }
+            SnippetElement::Choice { index, options } => {
+                write!(f, "${{{}|", index)?;
+                for (i, option) in options.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(",")?;
+                    }
+                    f.write_str(&escape_choice_text(option))?;
+                }
+                f.write_str("|}")?;
+            }
+            SnippetElement::Variable {
+                name,
+                default: None,
+                transform: None,
+            } if !next_continues => write!(f, "${}", name)?,
+            SnippetElement::Variable {
+                name,
+                default,
+                transform,
+            } => {
+                write!(f, "${{{}", name)?;
+                if let Some(default) = default {
+                    f.write_str(":")?;
+                    write_elements(f, default)?;
+                } else if let Some(transform) = transform {
+                    write_transform(f, transform)?;
+                }
+                f.write_str("}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_transform(f: &mut fmt::Formatter<'_>, transform: &SnippetTransform) -> fmt::Result {
+    write!(f, "/{}/", transform.regex.replace('/', "\\/"))?;
+    for item in &transform.format {
+        match item {
+            SnippetFormat::Text(text) => f.write_str(&escape(text, &['\\', '$', '/']))?,
+            SnippetFormat::Group(group) => write!(f, "${{{}}}", group)?,
+            SnippetFormat::Case { group, modifier } => {
+                write!(f, "${{{}:/{}}}", group, modifier.as_str())?
+            }
+            SnippetFormat::Conditional {
+                group,
+                if_matched,
+                otherwise,
+            } => {
+                let text = |s: &str| escape(s, &['\\', '$', '}', ':']);
+                match (if_matched, otherwise) {
+                    (Some(if_matched), Some(otherwise)) => write!(
+                        f,
+                        "${{{}:?{}:{}}}",
+                        group,
+                        text(if_matched),
+                        text(otherwise)
+                    )?,
+                    (Some(if_matched), None) => write!(f, "${{{}:+{}}}", group, text(if_matched))?,
+                    (None, otherwise) => write!(
+                        f,
+                        "${{{}:-{}}}",
+                        group,
+                        text(otherwise.as_deref().unwrap_or(""))
+                    )?,
+                }
+            }
+        }
+    }
+    write!(f, "/{}", transform.options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> SnippetElement {
+        SnippetElement::Text(s.into())
+    }
+
+    #[test]
+    fn test_parse() {
+        let snippet =
+            Snippet::parse(r"fn ${1:name}(${2|a,b\,c|}) -> $TM_FILENAME {\$ $0 \}}").unwrap();
+        assert_eq!(
+            snippet.elements,
+            [
+                text("fn "),
+                SnippetElement::Placeholder {
+                    index: 1,
+                    elements: vec![text("name")],
+                },
+                text("("),
+                SnippetElement::Choice {
+                    index: 2,
+                    options: vec!["a".into(), "b,c".into()],
+                },
+                text(") -> "),
+                SnippetElement::Variable {
+                    name: "TM_FILENAME".into(),
+                    default: None,
+                    transform: None,
+                },
+                text(" {$ "),
+                SnippetElement::Tabstop {
+                    index: 0,
+                    transform: None,
+                },
+                text(" }}"),
+            ]
+        );
+        assert_eq!(snippet.to_plain_text(), "fn name(a) ->  {$  }}");
+        assert_eq!(
+            snippet.render(|name| Some(format!("<{}>", name))),
+            "fn name(a) -> <TM_FILENAME> {$  }}"
+        );
+    }
+
+    #[test]
+    fn test_parse_transform() {
+        let snippet =
+            Snippet::parse(r"${TM_FILENAME/(.*)\..+$/${1:/upcase}${2:?yes:no}\/$1/g}").unwrap();
+        assert_eq!(
+            snippet.elements,
+            [SnippetElement::Variable {
+                name: "TM_FILENAME".into(),
+                default: None,
+                transform: Some(SnippetTransform {
+                    regex: r"(.*)\..+$".into(),
+                    format: vec![
+                        SnippetFormat::Case {
+                            group: 1,
+                            modifier: CaseModifier::Upcase,
+                        },
+                        SnippetFormat::Conditional {
+                            group: 2,
+                            if_matched: Some("yes".into()),
+                            otherwise: Some("no".into()),
+                        },
+                        SnippetFormat::Text("/".into()),
+                        SnippetFormat::Group(1),
+                    ],
+                    options: "g".into(),
+                }),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for input in [
+            r"${1:outer ${2:inner}}$0",
+            r"${1}2 $name ${name}x ${3|a\,b,c\|d|}",
+            r"${1/(a)/${1:+x}\/${1:-y}/gi} \$\\\}",
+        ] {
+            let snippet = Snippet::parse(input).unwrap();
+            assert_eq!(Snippet::parse(&snippet.to_string()).unwrap(), snippet);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Snippet::parse("a ${1:b").unwrap_err(),
+            SnippetError {
+                offset: 2,
+                kind: SnippetErrorKind::Unclosed
+            }
+        );
+        assert_eq!(
+            Snippet::parse("${-}").unwrap_err().kind,
+            SnippetErrorKind::ExpectedIndexOrVariable
+        );
+        assert_eq!(
+            Snippet::parse("${1|a,b}").unwrap_err().kind,
+            SnippetErrorKind::UnclosedChoice
+        );
+        assert_eq!(
+            Snippet::parse("${1/a/b}").unwrap_err().kind,
+            SnippetErrorKind::UnclosedTransform
+        );
+        assert_eq!(
+            validate_snippet("${1:a ${1:x}}"),
+            Err(SnippetError {
+                offset: 6,
+                kind: SnippetErrorKind::RecursivePlaceholder(1)
+            })
+        );
+        // A `$` that starts nothing is literal text.
+        assert_eq!(Snippet::parse("$ 5").unwrap().to_plain_text(), "$ 5");
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape_snippet_text(r"a$b}\c"), r"a\$b\}\\c");
+        assert_eq!(escape_choice_text("a,b|c"), r"a\,b\|c");
+    }
+}
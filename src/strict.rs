@@ -0,0 +1,148 @@
+/*!
+
+Deserialization that reports the fields this crate does not model.
+
+The types of this crate ignore unknown fields, which hides misspelled fields and fields of newer
+protocol versions. The functions of this module deserialize a value as usual and additionally
+report the paths of the fields that were dropped, such as `contentChanges[0].rangeLenght`.
+
+Dropped fields are found by serializing the deserialized value again and comparing it with the
+input, which also covers flattened structs and untagged enums. Fields that are `null` in the input
+are not reported, as they are equivalent to absent optional fields.
+
+*/
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::{notification::Notification, request::Request};
+
+/// A deserialized value with the paths of the input fields it does not contain.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Strict<T> {
+    pub value: T,
+    pub unknown_fields: Vec<String>,
+}
+
+impl<T> Strict<T> {
+    pub fn is_exact(&self) -> bool {
+        self.unknown_fields.is_empty()
+    }
+}
+
+/// Deserializes `value` and collects the paths of its fields that `T` does not model.
+pub fn from_value<T>(value: Value) -> Result<Strict<T>, serde_json::Error>
+where
+    T: DeserializeOwned + Serialize,
+{
+    let deserialized = T::deserialize(&value)?;
+    let known = serde_json::to_value(&deserialized)?;
+    let mut unknown_fields = Vec::new();
+    collect_unknown_fields(&value, &known, &mut String::new(), &mut unknown_fields);
+    Ok(Strict {
+        value: deserialized,
+        unknown_fields,
+    })
+}
+
+/// Like [`from_value`], for JSON text.
+pub fn from_str<T>(s: &str) -> Result<Strict<T>, serde_json::Error>
+where
+    T: DeserializeOwned + Serialize,
+{
+    from_value(serde_json::from_str(s)?)
+}
+
+/// Deserializes the params of the request `R`.
+pub fn request_params<R: Request>(params: Value) -> Result<Strict<R::Params>, serde_json::Error> {
+    from_value(params)
+}
+
+/// Deserializes the result of the request `R`.
+pub fn request_result<R: Request>(result: Value) -> Result<Strict<R::Result>, serde_json::Error> {
+    from_value(result)
+}
+
+/// Deserializes the params of the notification `N`.
+pub fn notification_params<N: Notification>(
+    params: Value,
+) -> Result<Strict<N::Params>, serde_json::Error> {
+    from_value(params)
+}
+
+fn collect_unknown_fields(input: &Value, known: &Value, path: &mut String, out: &mut Vec<String>) {
+    match (input, known) {
+        (Value::Object(input), Value::Object(known)) => {
+            for (key, value) in input {
+                let len = path.len();
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(key);
+                match known.get(key) {
+                    Some(known) => collect_unknown_fields(value, known, path, out),
+                    None if value.is_null() => {}
+                    None => out.push(path.clone()),
+                }
+                path.truncate(len);
+            }
+        }
+        (Value::Array(input), Value::Array(known)) => {
+            for (index, (value, known)) in input.iter().zip(known).enumerate() {
+                let len = path.len();
+                path.push_str(&format!("[{}]", index));
+                collect_unknown_fields(value, known, path, out);
+                path.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{notification::DidChangeTextDocument, request::Initialize};
+    use serde_json::json;
+
+    #[test]
+    fn test_unknown_fields() {
+        let params = json!({
+            "textDocument": { "uri": "file:///a.rs", "version": 2 },
+            "contentChanges": [
+                { "text": "a" },
+                {
+                    "range": {
+                        "start": { "line": 0, "character": 0 },
+                        "end": { "line": 0, "character": 1 },
+                    },
+                    "rangeLenght": 1,
+                    "text": "b",
+                },
+            ],
+            "reason": null,
+        });
+        let strict = notification_params::<DidChangeTextDocument>(params).unwrap();
+        assert_eq!(strict.unknown_fields, ["contentChanges[1].rangeLenght"]);
+        assert_eq!(strict.value.content_changes[1].text, "b");
+    }
+
+    #[test]
+    fn test_flattened_fields() {
+        let params = json!({
+            "processId": null,
+            "rootUri": null,
+            "capabilities": { "general": { "positionEncodings": ["utf-8"], "future": true } },
+            "workDoneToken": "token",
+            "clientInfo": { "name": "editor", "build": 7 },
+        });
+        let strict = request_params::<Initialize>(params).unwrap();
+        assert_eq!(
+            strict.unknown_fields,
+            ["capabilities.general.future", "clientInfo.build"]
+        );
+        assert!(from_str::<crate::Position>(r#"{"line":1,"character":2}"#)
+            .unwrap()
+            .is_exact());
+    }
+}
@@ -0,0 +1,311 @@
+/*!
+
+An in-memory text document that applies `textDocument/didChange` content changes.
+
+*/
+
+use crate::{
+    Position, PositionEncodingKind, Range, TextDocumentContentChangeEvent, TextDocumentItem, Uri,
+};
+
+use crate::position_mapping::encoded_len;
+
+/// Maps between byte offsets into a text and `Position`s in a given `PositionEncodingKind`.
+///
+/// Lines are terminated by `\n`, `\r\n` or `\r`, as described in the specification.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct LineIndex {
+    encoding: PositionEncodingKind,
+    /// The byte offset at which each line starts.
+    line_starts: Vec<usize>,
+    /// The length of each line in code units of `encoding`, excluding the line terminator.
+    line_lens: Vec<u32>,
+    /// The byte offset at which each line's content ends (the start of its line terminator).
+    line_ends: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(text: &str, encoding: PositionEncodingKind) -> LineIndex {
+        let mut line_starts = vec![0];
+        let mut line_ends = Vec::new();
+        let bytes = text.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\n' => {
+                    line_ends.push(i);
+                    line_starts.push(i + 1);
+                }
+                b'\r' => {
+                    line_ends.push(i);
+                    if bytes.get(i + 1) == Some(&b'\n') {
+                        i += 1;
+                    }
+                    line_starts.push(i + 1);
+                }
+                _ => (),
+            }
+            i += 1;
+        }
+        line_ends.push(text.len());
+        let line_lens = line_starts
+            .iter()
+            .zip(&line_ends)
+            .map(|(&start, &end)| encoded_len(&text[start..end], &encoding))
+            .collect();
+        LineIndex {
+            encoding,
+            line_starts,
+            line_lens,
+            line_ends,
+        }
+    }
+
+    /// The encoding that the `character` of positions is expressed in.
+    pub fn encoding(&self) -> &PositionEncodingKind {
+        &self.encoding
+    }
+
+    /// The number of lines in the text. An empty text, or a text ending with a line terminator,
+    /// has an empty last line.
+    pub fn line_count(&self) -> u32 {
+        self.line_starts.len() as u32
+    }
+
+    /// The length of `line`, excluding its line terminator.
+    pub fn line_len(&self, line: u32) -> Option<u32> {
+        self.line_lens.get(line as usize).copied()
+    }
+
+    /// The length of the line terminator of `line`, which is 0 for the last line.
+    pub(crate) fn line_terminator_len(&self, line: u32) -> u32 {
+        let line = line as usize;
+        match self.line_starts.get(line + 1) {
+            Some(&next) => (next - self.line_ends[line]) as u32,
+            None => 0,
+        }
+    }
+
+    /// The position at the very end of the text.
+    pub fn end_position(&self) -> Position {
+        let last = self.line_starts.len() - 1;
+        Position::new(last as u32, self.line_lens[last])
+    }
+
+    /// Clamps `position` to the text, following the specification: a character past the end of
+    /// the line defaults back to the line length.
+    pub fn clamp(&self, position: Position) -> Position {
+        match self.line_len(position.line) {
+            Some(len) => Position::new(position.line, position.character.min(len)),
+            None => self.end_position(),
+        }
+    }
+
+    /// Converts `position` into a byte offset into `text`, which must be the text this index was
+    /// built from. Positions outside of the text are clamped and positions in the middle of a
+    /// character are moved to its start.
+    pub fn offset(&self, text: &str, position: Position) -> usize {
+        let line = position.line as usize;
+        if line >= self.line_starts.len() {
+            return text.len();
+        }
+        let (start, end) = (self.line_starts[line], self.line_ends[line]);
+        let mut units = 0;
+        for (i, c) in text[start..end].char_indices() {
+            let next = units + encoded_len(c.encode_utf8(&mut [0; 4]), &self.encoding);
+            if next > position.character {
+                return start + i;
+            }
+            units = next;
+        }
+        end
+    }
+
+    /// Converts a byte offset into `text` into a position. Offsets past the end of the text are
+    /// clamped.
+    pub fn position(&self, text: &str, offset: usize) -> Position {
+        let offset = offset.min(text.len());
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        };
+        let start = self.line_starts[line];
+        let end = offset.min(self.line_ends[line]);
+        let mut end = end;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        Position::new(line as u32, encoded_len(&text[start..end], &self.encoding))
+    }
+
+    /// Converts `range` into a byte range into `text`.
+    pub fn byte_range(&self, text: &str, range: Range) -> std::ops::Range<usize> {
+        let start = self.offset(text, range.start);
+        let end = self.offset(text, range.end);
+        start.min(end)..start.max(end)
+    }
+}
+
+/// A text document which is kept in sync with the client.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct TextDocument {
+    uri: Uri,
+    language_id: String,
+    version: i32,
+    text: String,
+    line_index: LineIndex,
+}
+
+impl TextDocument {
+    pub fn new(
+        uri: Uri,
+        language_id: String,
+        version: i32,
+        text: String,
+        encoding: PositionEncodingKind,
+    ) -> TextDocument {
+        let line_index = LineIndex::new(&text, encoding);
+        TextDocument {
+            uri,
+            language_id,
+            version,
+            text,
+            line_index,
+        }
+    }
+
+    /// Creates a document from the item sent in a `textDocument/didOpen` notification.
+    pub fn from_item(item: TextDocumentItem, encoding: PositionEncodingKind) -> TextDocument {
+        TextDocument::new(
+            item.uri,
+            item.language_id,
+            item.version,
+            item.text,
+            encoding,
+        )
+    }
+
+    pub fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    pub fn language_id(&self) -> &str {
+        &self.language_id
+    }
+
+    pub fn version(&self) -> i32 {
+        self.version
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn line_index(&self) -> &LineIndex {
+        &self.line_index
+    }
+
+    /// Converts `position` into a byte offset into the text.
+    pub fn offset_at(&self, position: Position) -> usize {
+        self.line_index.offset(&self.text, position)
+    }
+
+    /// Converts a byte offset into the text into a position.
+    pub fn position_at(&self, offset: usize) -> Position {
+        self.line_index.position(&self.text, offset)
+    }
+
+    /// Returns the text covered by `range`.
+    pub fn text_in(&self, range: Range) -> &str {
+        &self.text[self.line_index.byte_range(&self.text, range)]
+    }
+
+    /// Applies the content changes of a `textDocument/didChange` notification, in order, and
+    /// updates the version.
+    pub fn apply_changes<'a>(
+        &mut self,
+        version: i32,
+        changes: impl IntoIterator<Item = &'a TextDocumentContentChangeEvent>,
+    ) {
+        for change in changes {
+            self.apply_change(change);
+        }
+        self.version = version;
+    }
+
+    /// Applies a single content change without touching the version.
+    pub fn apply_change(&mut self, change: &TextDocumentContentChangeEvent) {
+        match change.range {
+            Some(range) => {
+                let range = self.line_index.byte_range(&self.text, range);
+                self.text.replace_range(range, &change.text);
+            }
+            None => self.text.clone_from(&change.text),
+        }
+        self.line_index = LineIndex::new(&self.text, self.line_index.encoding.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(range: Range, text: &str) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range: Some(range),
+            range_length: None,
+            text: text.into(),
+        }
+    }
+
+    #[test]
+    fn test_line_index() {
+        let text = "a😀b\r\nc\rd\n";
+        let index = LineIndex::new(text, PositionEncodingKind::UTF16);
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line_len(0), Some(4));
+        assert_eq!(index.end_position(), Position::new(3, 0));
+        assert_eq!(index.offset(text, Position::new(0, 3)), 5);
+        // The middle of a surrogate pair is moved to the start of the character.
+        assert_eq!(index.offset(text, Position::new(0, 2)), 1);
+        assert_eq!(index.offset(text, Position::new(1, 9)), 9);
+        assert_eq!(index.position(text, 9), Position::new(1, 1));
+        assert_eq!(index.position(text, 11), Position::new(2, 1));
+
+        let index = LineIndex::new(text, PositionEncodingKind::UTF8);
+        assert_eq!(index.line_len(0), Some(6));
+        assert_eq!(index.position(text, 5), Position::new(0, 5));
+    }
+
+    #[test]
+    fn test_apply_changes() {
+        let mut document = TextDocument::new(
+            "file:///a.txt".parse().unwrap(),
+            "plaintext".into(),
+            1,
+            "hello\nworld".into(),
+            PositionEncodingKind::UTF16,
+        );
+        document.apply_changes(
+            2,
+            &[
+                change(
+                    Range::new(Position::new(1, 0), Position::new(1, 5)),
+                    "there",
+                ),
+                change(Range::new(Position::new(0, 5), Position::new(0, 5)), ",\n"),
+                TextDocumentContentChangeEvent {
+                    range: Some(Range::new(Position::new(9, 0), Position::new(9, 0))),
+                    range_length: None,
+                    text: "!".into(),
+                },
+            ],
+        );
+        assert_eq!(document.text(), "hello,\n\nthere!");
+        assert_eq!(document.version(), 2);
+        assert_eq!(
+            document.text_in(Range::new(Position::new(2, 0), Position::new(2, 5))),
+            "there"
+        );
+    }
+}
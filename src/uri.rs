@@ -1,10 +1,20 @@
-use std::{hash::Hash, ops::Deref, str::FromStr};
+use std::{
+    fmt,
+    hash::Hash,
+    ops::Deref,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
 
 use serde::{de::Error, Deserialize, Serialize};
 
 /// Newtype struct around `fluent_uri::Uri<String>` with serialization implementations that use `as_str()` and 'from_str()' respectively.
+///
+/// The parsed URI is shared behind an `Arc`, so cloning a `Uri` is cheap and clones compare equal
+/// without looking at their contents.
 #[derive(Debug, Clone)]
-pub struct Uri(fluent_uri::Uri<String>);
+pub struct Uri(Arc<fluent_uri::Uri<String>>);
 
 impl Serialize for Uri {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -22,7 +32,7 @@ impl<'de> Deserialize<'de> for Uri {
     {
         let string = String::deserialize(deserializer)?;
         fluent_uri::Uri::<String>::parse_from(string)
-            .map(Uri)
+            .map(|uri| Uri(Arc::new(uri)))
             .map_err(|(_, error)| Error::custom(error.to_string()))
     }
 }
@@ -48,7 +58,38 @@ impl FromStr for Uri {
         // https://github.com/yescallop/fluent-uri-rs/pull/10
         // gets merged.
         // fluent_uri::Uri::from_str(s).map(Self)
-        fluent_uri::Uri::parse(s).map(|uri| Self(uri.to_owned()))
+        fluent_uri::Uri::parse(s).map(|uri| Self(Arc::new(uri.to_owned())))
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Uri {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Uri".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "format": "uri",
+        })
+    }
+}
+
+/// Generates URIs of a few common schemes with percent-encoded path segments.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Uri {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut uri =
+            String::from(*u.choose(&["file:///", "untitled:", "https://example.com/"])?);
+        let segments = Vec::<String>::arbitrary(u)?;
+        for (i, segment) in segments.iter().enumerate() {
+            if i > 0 {
+                uri.push('/');
+            }
+            percent_encode(&mut uri, segment, is_unreserved);
+        }
+        uri.parse().map_err(|_| arbitrary::Error::IncorrectFormat)
     }
 }
 
@@ -67,7 +108,7 @@ impl Deref for Uri {
 */
 impl PartialEq for Uri {
     fn eq(&self, other: &Self) -> bool {
-        self.as_str() == other.as_str()
+        Arc::ptr_eq(&self.0, &other.0) || self.as_str() == other.as_str()
     }
 }
 
@@ -78,3 +119,759 @@ impl Hash for Uri {
         self.as_str().hash(state)
     }
 }
+
+/// The flavour of filesystem paths converted from and to `file` URIs.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum PathStyle {
+    /// Paths like `/home/user/file.rs`.
+    Posix,
+    /// Paths like `C:\Users\user\file.rs` or `\\server\share\file.rs`.
+    Windows,
+}
+
+impl PathStyle {
+    /// The style of the paths of the platform this crate is compiled for.
+    pub fn native() -> PathStyle {
+        if cfg!(windows) {
+            PathStyle::Windows
+        } else {
+            PathStyle::Posix
+        }
+    }
+}
+
+/// An error raised when converting between `file` URIs and filesystem paths.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum FilePathError {
+    /// The path is not absolute. Windows paths need a drive letter or a UNC server name.
+    RelativePath(String),
+    /// The path is not valid UTF-8.
+    InvalidEncoding,
+    /// The URI does not use the `file` scheme.
+    NotFileScheme(String),
+    /// The URI names a server, which Posix paths can not express.
+    UnsupportedHost(String),
+    /// The URI has neither a drive letter nor a server, so it has no Windows path.
+    MissingDriveLetter(String),
+}
+
+impl fmt::Display for FilePathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilePathError::RelativePath(path) => write!(f, "path `{}` is not absolute", path),
+            FilePathError::InvalidEncoding => write!(f, "path is not valid UTF-8"),
+            FilePathError::NotFileScheme(uri) => write!(f, "`{}` is not a file URI", uri),
+            FilePathError::UnsupportedHost(host) => {
+                write!(f, "host `{}` can not be part of a Posix path", host)
+            }
+            FilePathError::MissingDriveLetter(uri) => {
+                write!(f, "`{}` has no drive letter or server", uri)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FilePathError {}
+
+impl Uri {
+    /// Converts an absolute path of the current platform into a `file` URI.
+    pub fn from_file_path(path: impl AsRef<Path>) -> Result<Uri, FilePathError> {
+        let path = path
+            .as_ref()
+            .to_str()
+            .ok_or(FilePathError::InvalidEncoding)?;
+        Uri::from_file_path_with_style(path, PathStyle::native())
+    }
+
+    /// Converts this `file` URI into a path of the current platform.
+    pub fn to_file_path(&self) -> Result<PathBuf, FilePathError> {
+        self.to_file_path_with_style(PathStyle::native())
+            .map(PathBuf::from)
+    }
+
+    /// Converts an absolute path of the given style into a `file` URI.
+    ///
+    /// Windows drive letters are written the way VS Code does, lower case with an encoded colon:
+    /// `C:\src\a b.rs` becomes `file:///c%3A/src/a%20b.rs`. UNC paths like `\\server\share`
+    /// become `file://server/share`.
+    pub fn from_file_path_with_style(path: &str, style: PathStyle) -> Result<Uri, FilePathError> {
+        let relative = || FilePathError::RelativePath(path.to_string());
+        let keep = |byte| is_unreserved(byte) || byte == b'/';
+
+        let mut uri = String::from("file://");
+        match style {
+            PathStyle::Posix => {
+                if !path.starts_with('/') {
+                    return Err(relative());
+                }
+                percent_encode(&mut uri, path, keep);
+            }
+            PathStyle::Windows => {
+                let mut normalized = path.replace('\\', "/");
+                // Strip the prefix of extended-length paths, `\\?\C:\` and `\\?\UNC\server\`.
+                if let Some(rest) = normalized.strip_prefix("//?/UNC/") {
+                    normalized = format!("//{}", rest);
+                } else if let Some(rest) = normalized.strip_prefix("//?/") {
+                    normalized = rest.to_string();
+                }
+
+                if let Some(unc) = normalized.strip_prefix("//") {
+                    let (server, rest) = unc.split_at(unc.find('/').unwrap_or(unc.len()));
+                    if server.is_empty() {
+                        return Err(relative());
+                    }
+                    percent_encode(&mut uri, server, is_unreserved);
+                    percent_encode(&mut uri, if rest.is_empty() { "/" } else { rest }, keep);
+                } else {
+                    let bytes = normalized.as_bytes();
+                    let has_drive = bytes.len() >= 3
+                        && bytes[0].is_ascii_alphabetic()
+                        && bytes[1] == b':'
+                        && bytes[2] == b'/';
+                    if !has_drive {
+                        return Err(relative());
+                    }
+                    uri.push('/');
+                    uri.push(bytes[0].to_ascii_lowercase() as char);
+                    uri.push_str("%3A");
+                    percent_encode(&mut uri, &normalized[2..], keep);
+                }
+            }
+        }
+        Ok(uri
+            .parse()
+            .expect("percent-encoded file URIs are always valid"))
+    }
+
+    /// Converts this `file` URI into a path of the given style.
+    ///
+    /// Both `file:///c:/a` and `file:///c%3A/a` are understood as the Windows path `c:\a`. The
+    /// query and fragment are ignored.
+    pub fn to_file_path_with_style(&self, style: PathStyle) -> Result<String, FilePathError> {
+        if !self
+            .scheme()
+            .is_some_and(|scheme| scheme.as_str().eq_ignore_ascii_case("file"))
+        {
+            return Err(FilePathError::NotFileScheme(self.as_str().to_string()));
+        }
+        let host = match self.authority() {
+            Some(authority) => String::from_utf8(percent_decode(authority.host().as_str()))
+                .map_err(|_| FilePathError::InvalidEncoding)?,
+            None => String::new(),
+        };
+        let host = if host.eq_ignore_ascii_case("localhost") {
+            ""
+        } else {
+            &host
+        };
+        let path = String::from_utf8(percent_decode(self.path().as_str()))
+            .map_err(|_| FilePathError::InvalidEncoding)?;
+
+        match style {
+            PathStyle::Posix => {
+                if !host.is_empty() {
+                    return Err(FilePathError::UnsupportedHost(host.to_string()));
+                }
+                Ok(if path.is_empty() {
+                    "/".to_string()
+                } else {
+                    path
+                })
+            }
+            PathStyle::Windows => {
+                if !host.is_empty() {
+                    return Ok(format!("\\\\{}{}", host, path.replace('/', "\\")));
+                }
+                let bytes = path.as_bytes();
+                let has_drive = bytes.len() >= 3
+                    && bytes[0] == b'/'
+                    && bytes[1].is_ascii_alphabetic()
+                    && bytes[2] == b':'
+                    && matches!(bytes.get(3), None | Some(b'/'));
+                if !has_drive {
+                    return Err(FilePathError::MissingDriveLetter(self.as_str().to_string()));
+                }
+                let mut windows = path[1..].replace('/', "\\");
+                if windows.len() == 2 {
+                    windows.push('\\');
+                }
+                Ok(windows)
+            }
+        }
+    }
+}
+
+impl Uri {
+    /// Returns the normal form of this URI, in which URIs that denote the same resource are
+    /// spelled the same way:
+    ///
+    /// * the scheme and host are lower case,
+    /// * percent-encoded unreserved characters are decoded, other escapes use upper case hex
+    ///   digits,
+    /// * `.` and `..` path segments are resolved,
+    /// * for `file` URIs, a leading drive letter is lower case and followed by a plain `:`, so
+    ///   `file:///C:/x`, `file:///c%3A/x` and `file:///c:/x` all become `file:///c:/x`.
+    pub fn normalized(&self) -> Uri {
+        let mut normalized = String::with_capacity(self.as_str().len());
+        let scheme = self
+            .scheme()
+            .map(|scheme| scheme.as_str().to_ascii_lowercase());
+        if let Some(scheme) = &scheme {
+            normalized.push_str(scheme);
+            normalized.push(':');
+        }
+        if let Some(authority) = self.authority() {
+            normalized.push_str("//");
+            if let Some(userinfo) = authority.userinfo() {
+                normalized.push_str(&normalize_percent_encoding(userinfo.as_str(), false));
+                normalized.push('@');
+            }
+            normalized.push_str(&normalize_percent_encoding(authority.host().as_str(), true));
+            if let Some(port) = authority.port() {
+                normalized.push(':');
+                normalized.push_str(port);
+            }
+        }
+
+        let mut path = normalize_percent_encoding(self.path().as_str(), false);
+        if path.starts_with('/') {
+            path = remove_dot_segments(&path);
+        }
+        if scheme.as_deref() == Some("file") {
+            path = normalize_drive_letter(&path);
+        }
+        normalized.push_str(&path);
+
+        if let Some(query) = self.query() {
+            normalized.push('?');
+            normalized.push_str(&normalize_percent_encoding(query.as_str(), false));
+        }
+        if let Some(fragment) = self.fragment() {
+            normalized.push('#');
+            normalized.push_str(&normalize_percent_encoding(fragment.as_str(), false));
+        }
+        normalized
+            .parse()
+            .expect("normalizing a valid URI yields a valid URI")
+    }
+
+    /// Whether this URI and `other` have the same normal form.
+    pub fn is_equivalent(&self, other: &Uri) -> bool {
+        self == other || self.normalized() == other.normalized()
+    }
+}
+
+impl Uri {
+    /// The part of this URI before its path, like `file://` or `https://example.com`.
+    fn prefix(&self) -> &str {
+        let mut len = self.scheme().map_or(0, |scheme| scheme.as_str().len() + 1);
+        if let Some(authority) = self.authority() {
+            len += 2 + authority.as_str().len();
+        }
+        &self.as_str()[..len]
+    }
+
+    fn with_path(&self, path: &str) -> Uri {
+        format!("{}{}", self.prefix(), path)
+            .parse()
+            .expect("replacing the path of a URI with a valid path yields a valid URI")
+    }
+
+    /// Appends the `/` separated `path` to the path of this URI, which is treated as a directory.
+    /// `path` is percent-encoded, and its `.` and `..` segments are resolved. An absolute `path`
+    /// replaces the path of this URI. The query and fragment are dropped.
+    ///
+    /// `file:///a/b` joined with `../c d` is `file:///a/c%20d`.
+    pub fn join(&self, path: &str) -> Uri {
+        let mut joined = String::new();
+        if !path.starts_with('/') {
+            joined.push_str(self.path().as_str());
+            if !joined.ends_with('/') {
+                joined.push('/');
+            }
+        }
+        percent_encode(&mut joined, path, |byte| {
+            is_unreserved(byte)
+                || matches!(
+                    byte,
+                    b'/' | b':'
+                        | b'@'
+                        | b'!'
+                        | b'$'
+                        | b'&'
+                        | b'\''
+                        | b'('
+                        | b')'
+                        | b'*'
+                        | b'+'
+                        | b','
+                        | b';'
+                        | b'='
+                )
+        });
+        if joined.starts_with('/') {
+            joined = remove_dot_segments(&joined);
+        }
+        self.with_path(&joined)
+    }
+
+    /// The path segments of this URI, ignoring a trailing `/`.
+    fn segments_trimmed(&self) -> &str {
+        let path = self.path().as_str();
+        path.strip_suffix('/').unwrap_or(path)
+    }
+
+    /// The URI of the directory containing this one, or `None` if the path is the root or empty.
+    /// The query and fragment are dropped.
+    ///
+    /// The parent of `file:///a/b` is `file:///a`, and the parent of `file:///a` is `file:///`.
+    pub fn parent(&self) -> Option<Uri> {
+        let path = self.segments_trimmed();
+        let index = path.rfind('/')?;
+        Some(self.with_path(if index == 0 { "/" } else { &path[..index] }))
+    }
+
+    /// The decoded last segment of the path, ignoring a trailing `/`.
+    pub fn file_name(&self) -> Option<String> {
+        let path = self.segments_trimmed();
+        let name = &path[path.rfind('/').map_or(0, |index| index + 1)..];
+        if name.is_empty() || name == "." || name == ".." {
+            return None;
+        }
+        Some(String::from_utf8_lossy(&percent_decode(name)).into_owned())
+    }
+
+    /// The extension of [`Self::file_name`], without the leading `.`. Like `std::path::Path`,
+    /// names starting with their only `.` have no extension.
+    pub fn extension(&self) -> Option<String> {
+        let name = self.file_name()?;
+        match name.rfind('.') {
+            Some(0) | None => None,
+            Some(index) => Some(name[index + 1..].to_string()),
+        }
+    }
+
+    /// The decoded, `/` separated path of this URI relative to `base`, if this URI is `base` or
+    /// lies inside of it. Both URIs are normalized first, see [`Self::normalized`].
+    ///
+    /// `file:///a/b/c.rs` stripped of `file:///a` is `b/c.rs`.
+    pub fn strip_prefix(&self, base: &Uri) -> Option<String> {
+        strip_normalized_prefix(&self.normalized(), &base.normalized())
+    }
+
+    /// Like [`Self::strip_prefix`], but with a `base` that is already normalized, so only this URI
+    /// is normalized when stripping many URIs of the same base.
+    pub fn strip_normalized_prefix(&self, base: &NormalizedUri) -> Option<String> {
+        strip_normalized_prefix(&self.normalized(), base.normalized())
+    }
+}
+
+/// [`Uri::strip_prefix`] of two normalized URIs.
+fn strip_normalized_prefix(uri: &Uri, base: &Uri) -> Option<String> {
+    if uri.prefix() != base.prefix() {
+        return None;
+    }
+    let base_path = base.segments_trimmed();
+    let rest = uri.path().as_str().strip_prefix(base_path)?;
+    let rest = if rest.is_empty() {
+        rest
+    } else {
+        rest.strip_prefix('/')?
+    };
+    Some(String::from_utf8_lossy(&percent_decode(rest)).into_owned())
+}
+
+/// Decodes escaped unreserved characters and upper cases the hex digits of other escapes.
+/// Everything outside of escapes is lower cased if `lowercase` is set.
+fn normalize_percent_encoding(s: &str, lowercase: bool) -> String {
+    let bytes = s.as_bytes();
+    let mut out = String::with_capacity(s.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if let Some(&[b'%', high, low]) = bytes.get(i..i + 3) {
+            if let (Some(high), Some(low)) = (hex_value(high), hex_value(low)) {
+                let byte = high * 16 + low;
+                if is_unreserved(byte) {
+                    out.push(if lowercase {
+                        byte.to_ascii_lowercase()
+                    } else {
+                        byte
+                    } as char);
+                } else {
+                    out.push_str(&format!("%{:02X}", byte));
+                }
+                i += 3;
+                continue;
+            }
+        }
+        // Only ASCII bytes appear outside of escapes in a valid URI.
+        out.push(if lowercase {
+            bytes[i].to_ascii_lowercase()
+        } else {
+            bytes[i]
+        } as char);
+        i += 1;
+    }
+    out
+}
+
+/// Resolves `.` and `..` segments of an absolute path, as described in RFC 3986, section 5.2.4.
+fn remove_dot_segments(path: &str) -> String {
+    let mut output: Vec<&str> = Vec::new();
+    let segments: Vec<&str> = path[1..].split('/').collect();
+    for (i, segment) in segments.iter().enumerate() {
+        let last = i + 1 == segments.len();
+        match *segment {
+            "." | ".." => {
+                if *segment == ".." {
+                    output.pop();
+                }
+                // A trailing dot segment still denotes a directory.
+                if last {
+                    output.push("");
+                }
+            }
+            segment => output.push(segment),
+        }
+    }
+    format!("/{}", output.join("/"))
+}
+
+/// Turns a leading `/C:` or `/C%3A` into `/c:`.
+fn normalize_drive_letter(path: &str) -> String {
+    let bytes = path.as_bytes();
+    if bytes.len() < 3 || bytes[0] != b'/' || !bytes[1].is_ascii_alphabetic() {
+        return path.to_string();
+    }
+    let rest = if bytes[2] == b':' {
+        &path[3..]
+    } else if path[2..].starts_with("%3A") {
+        &path[5..]
+    } else {
+        return path.to_string();
+    };
+    if !rest.is_empty() && !rest.starts_with('/') {
+        return path.to_string();
+    }
+    format!("/{}:{}", bytes[1].to_ascii_lowercase() as char, rest)
+}
+
+/// A `Uri` that compares, hashes and orders by its normal form, see [`Uri::normalized`].
+///
+/// It is meant to be used as a map key, so different spellings of the same URI share an entry.
+/// The original spelling is kept and used for serialization, so it can be sent back to the
+/// client as received.
+#[derive(Debug, Clone)]
+pub struct NormalizedUri {
+    original: Uri,
+    normalized: Uri,
+}
+
+impl NormalizedUri {
+    pub fn new(uri: Uri) -> NormalizedUri {
+        NormalizedUri {
+            normalized: uri.normalized(),
+            original: uri,
+        }
+    }
+
+    /// The URI as it was spelled when this `NormalizedUri` was created.
+    pub fn original(&self) -> &Uri {
+        &self.original
+    }
+
+    /// The normal form of the URI.
+    pub fn normalized(&self) -> &Uri {
+        &self.normalized
+    }
+
+    pub fn into_original(self) -> Uri {
+        self.original
+    }
+}
+
+impl From<Uri> for NormalizedUri {
+    fn from(uri: Uri) -> Self {
+        NormalizedUri::new(uri)
+    }
+}
+
+impl From<NormalizedUri> for Uri {
+    fn from(uri: NormalizedUri) -> Self {
+        uri.original
+    }
+}
+
+impl Deref for NormalizedUri {
+    type Target = Uri;
+
+    fn deref(&self) -> &Self::Target {
+        &self.original
+    }
+}
+
+impl PartialEq for NormalizedUri {
+    fn eq(&self, other: &Self) -> bool {
+        self.normalized == other.normalized
+    }
+}
+
+impl Eq for NormalizedUri {}
+
+impl Hash for NormalizedUri {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.normalized.hash(state)
+    }
+}
+
+impl Ord for NormalizedUri {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.normalized.cmp(&other.normalized)
+    }
+}
+
+impl PartialOrd for NormalizedUri {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Serialize for NormalizedUri {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.original.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for NormalizedUri {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Uri::deserialize(deserializer).map(NormalizedUri::new)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for NormalizedUri {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "NormalizedUri".into()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        generator.subschema_for::<Uri>()
+    }
+}
+
+/// Whether `byte` is an unreserved character, which never needs to be percent-encoded.
+pub(crate) fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+/// Appends `s` to `out`, percent-encoding every byte for which `keep` returns `false`.
+pub(crate) fn percent_encode(out: &mut String, s: &str, keep: impl Fn(u8) -> bool) {
+    for &byte in s.as_bytes() {
+        if keep(byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+}
+
+/// Decodes the percent-encoded bytes of `s`. Malformed escapes are kept as is.
+pub(crate) fn percent_decode(s: &str) -> Vec<u8> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = match bytes.get(i..i + 3) {
+            Some(&[b'%', high, low]) => hex_value(high).zip(hex_value(low)),
+            _ => None,
+        };
+        match escaped {
+            Some((high, low)) => {
+                out.push(high * 16 + low);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|digit| digit as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uri(s: &str) -> Uri {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_shared_clone() {
+        let original = uri("file:///a.rs");
+        let clone = original.clone();
+        assert!(Arc::ptr_eq(&original.0, &clone.0));
+        assert_eq!(original, clone);
+        assert_eq!(original, uri("file:///a.rs"));
+        assert_eq!(serde_json::to_string(&clone).unwrap(), r#""file:///a.rs""#);
+    }
+
+    #[test]
+    fn test_posix_file_path() {
+        let converted = Uri::from_file_path_with_style("/home/me/a b#1.rs", PathStyle::Posix);
+        assert_eq!(converted, Ok(uri("file:///home/me/a%20b%231.rs")));
+        assert_eq!(
+            converted.unwrap().to_file_path_with_style(PathStyle::Posix),
+            Ok("/home/me/a b#1.rs".to_string())
+        );
+        assert_eq!(
+            uri("file://localhost/etc/hosts").to_file_path_with_style(PathStyle::Posix),
+            Ok("/etc/hosts".to_string())
+        );
+        assert_eq!(
+            Uri::from_file_path_with_style("src/lib.rs", PathStyle::Posix),
+            Err(FilePathError::RelativePath("src/lib.rs".into()))
+        );
+        assert_eq!(
+            uri("file://server/share").to_file_path_with_style(PathStyle::Posix),
+            Err(FilePathError::UnsupportedHost("server".into()))
+        );
+        assert_eq!(
+            uri("untitled:Untitled-1").to_file_path_with_style(PathStyle::Posix),
+            Err(FilePathError::NotFileScheme("untitled:Untitled-1".into()))
+        );
+    }
+
+    #[test]
+    fn test_windows_file_path() {
+        let converted = Uri::from_file_path_with_style("C:\\Users\\Me\\ü.rs", PathStyle::Windows);
+        assert_eq!(converted, Ok(uri("file:///c%3A/Users/Me/%C3%BC.rs")));
+        assert_eq!(
+            converted
+                .unwrap()
+                .to_file_path_with_style(PathStyle::Windows),
+            Ok("c:\\Users\\Me\\ü.rs".to_string())
+        );
+        assert_eq!(
+            uri("file:///C:/a/b.rs").to_file_path_with_style(PathStyle::Windows),
+            Ok("C:\\a\\b.rs".to_string())
+        );
+        assert_eq!(
+            uri("file:///d%3A").to_file_path_with_style(PathStyle::Windows),
+            Ok("d:\\".to_string())
+        );
+
+        let unc = Uri::from_file_path_with_style("\\\\server\\share\\a.rs", PathStyle::Windows);
+        assert_eq!(unc, Ok(uri("file://server/share/a.rs")));
+        assert_eq!(
+            unc.unwrap().to_file_path_with_style(PathStyle::Windows),
+            Ok("\\\\server\\share\\a.rs".to_string())
+        );
+        assert_eq!(
+            Uri::from_file_path_with_style("\\\\?\\C:\\a.rs", PathStyle::Windows),
+            Ok(uri("file:///c%3A/a.rs"))
+        );
+
+        for relative in ["a\\b.rs", "C:a.rs", "\\a.rs"] {
+            assert_eq!(
+                Uri::from_file_path_with_style(relative, PathStyle::Windows),
+                Err(FilePathError::RelativePath(relative.into()))
+            );
+        }
+        assert_eq!(
+            uri("file:///a.rs").to_file_path_with_style(PathStyle::Windows),
+            Err(FilePathError::MissingDriveLetter("file:///a.rs".into()))
+        );
+    }
+    #[test]
+    fn test_normalized() {
+        for spelling in [
+            "file:///C:/x",
+            "file:///c%3A/x",
+            "file:///c:/x",
+            "FILE:///c%3a/./x",
+        ] {
+            assert_eq!(
+                uri(spelling).normalized(),
+                uri("file:///c:/x"),
+                "{}",
+                spelling
+            );
+        }
+        assert_eq!(
+            uri("HTTP://Example.COM:8080/a/b/../%7euser/%c3%bc?q=%41#%2f").normalized(),
+            uri("http://example.com:8080/a/~user/%C3%BC?q=A#%2F")
+        );
+        assert_eq!(uri("file:///a/b/..").normalized(), uri("file:///a/"));
+        assert_eq!(uri("file:///C:foo").normalized(), uri("file:///C:foo"));
+        assert!(uri("untitled:Untitled-1").is_equivalent(&uri("UNTITLED:Untitled-1")));
+    }
+
+    #[test]
+    #[allow(clippy::mutable_key_type)]
+    fn test_normalized_uri_map_key() {
+        let mut map = std::collections::HashMap::new();
+        map.insert(NormalizedUri::new(uri("file:///C:/x")), 1);
+        *map.entry(uri("file:///c%3A/x").into()).or_insert(0) += 1;
+        assert_eq!(map.len(), 1);
+
+        let (key, value) = map.into_iter().next().unwrap();
+        assert_eq!(value, 2);
+        assert_eq!(key.original(), &uri("file:///C:/x"));
+        assert_eq!(serde_json::to_string(&key).unwrap(), "\"file:///C:/x\"");
+    }
+    #[test]
+    fn test_path_manipulation() {
+        let file = uri("file:///a/b%20c/d.tar.gz?q#f");
+        assert_eq!(file.file_name().as_deref(), Some("d.tar.gz"));
+        assert_eq!(file.extension().as_deref(), Some("gz"));
+        assert_eq!(file.parent(), Some(uri("file:///a/b%20c")));
+        assert_eq!(uri("file:///a").parent(), Some(uri("file:///")));
+        assert_eq!(uri("file:///").parent(), None);
+        assert_eq!(uri("file:///a/.gitignore").extension(), None);
+
+        assert_eq!(uri("file:///a/b").join("../c d"), uri("file:///a/c%20d"));
+        assert_eq!(uri("file:///a/").join("b/c.rs"), uri("file:///a/b/c.rs"));
+        assert_eq!(uri("file://a/a").join("/b"), uri("file://a/b"));
+
+        let root = uri("file:///C:/work");
+        assert_eq!(
+            uri("file:///c%3A/work/src/a%20b.rs")
+                .strip_prefix(&root)
+                .as_deref(),
+            Some("src/a b.rs")
+        );
+        assert_eq!(
+            uri("file:///c:/work/").strip_prefix(&root).as_deref(),
+            Some("")
+        );
+        assert_eq!(uri("file:///c:/workspace").strip_prefix(&root), None);
+        assert_eq!(uri("untitled:///c:/work/a").strip_prefix(&root), None);
+        let root = NormalizedUri::new(root);
+        assert_eq!(
+            uri("file:///c%3A/work/src/a%20b.rs")
+                .strip_normalized_prefix(&root)
+                .as_deref(),
+            Some("src/a b.rs")
+        );
+        assert_eq!(
+            uri("file:///c:/workspace").strip_normalized_prefix(&root),
+            None
+        );
+    }
+}
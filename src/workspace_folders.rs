@@ -1,8 +1,10 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{OneOf, Uri};
+use crate::{InitializeParams, OneOf, Uri};
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceFoldersServerCapabilities {
     /// The server has support for workspace folders
@@ -21,6 +23,8 @@ pub struct WorkspaceFoldersServerCapabilities {
 }
 
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceFolder {
     /// The associated URI for this workspace folder.
@@ -30,6 +34,8 @@ pub struct WorkspaceFolder {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DidChangeWorkspaceFoldersParams {
     /// The actual workspace folder change event.
@@ -38,6 +44,8 @@ pub struct DidChangeWorkspaceFoldersParams {
 
 /// The workspace folder change event.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceFoldersChangeEvent {
     /// The array of added workspace folders
@@ -46,3 +54,95 @@ pub struct WorkspaceFoldersChangeEvent {
     /// The array of the removed workspace folders
     pub removed: Vec<WorkspaceFolder>,
 }
+
+impl WorkspaceFolder {
+    /// Whether `uri` is this folder or lies inside of it.
+    pub fn contains(&self, uri: &Uri) -> bool {
+        uri.strip_prefix(&self.uri).is_some()
+    }
+
+    /// The decoded, `/` separated path of `uri` relative to this folder.
+    pub fn relative_path(&self, uri: &Uri) -> Option<String> {
+        uri.strip_prefix(&self.uri)
+    }
+}
+
+/// Finds the innermost of `folders` containing `uri`, for nested workspace folders.
+pub fn innermost_workspace_folder<'a>(
+    folders: &'a [WorkspaceFolder],
+    uri: &Uri,
+) -> Option<&'a WorkspaceFolder> {
+    folders
+        .iter()
+        .filter_map(|folder| Some((folder, folder.relative_path(uri)?)))
+        .min_by_key(|(_, relative)| relative.len())
+        .map(|(folder, _)| folder)
+}
+
+impl InitializeParams {
+    /// The workspace folders of the client. Clients that do not send `workspace_folders` are
+    /// assumed to have a single folder at the deprecated `root_uri`.
+    pub fn workspace_folders_or_root(&self) -> Vec<WorkspaceFolder> {
+        if let Some(folders) = &self.workspace_folders {
+            return folders.clone();
+        }
+        #[allow(deprecated)]
+        let root_uri = self.root_uri.as_ref();
+        root_uri
+            .map(|uri| WorkspaceFolder {
+                name: uri.file_name().unwrap_or_else(|| uri.to_string()),
+                uri: uri.clone(),
+            })
+            .into_iter()
+            .collect()
+    }
+
+    /// The innermost workspace folder containing `uri`, see [`Self::workspace_folders_or_root`].
+    pub fn workspace_folder_for(&self, uri: &Uri) -> Option<WorkspaceFolder> {
+        innermost_workspace_folder(&self.workspace_folders_or_root(), uri).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folder(uri: &str) -> WorkspaceFolder {
+        WorkspaceFolder {
+            uri: uri.parse().unwrap(),
+            name: uri.into(),
+        }
+    }
+
+    #[test]
+    fn test_workspace_folder_lookup() {
+        let document: Uri = "file:///work/app/lib/src/main.rs".parse().unwrap();
+        let folders = vec![
+            folder("file:///work/app"),
+            folder("file:///work/app/lib"),
+            folder("file:///work/other"),
+        ];
+        assert_eq!(
+            innermost_workspace_folder(&folders, &document),
+            Some(&folders[1])
+        );
+        assert_eq!(
+            folders[0].relative_path(&document).as_deref(),
+            Some("lib/src/main.rs")
+        );
+        assert!(!folders[2].contains(&document));
+
+        #[allow(deprecated)]
+        let params = InitializeParams {
+            root_uri: Some("file:///work/app".parse().unwrap()),
+            ..InitializeParams::default()
+        };
+        assert_eq!(
+            params.workspace_folder_for(&document),
+            Some(WorkspaceFolder {
+                uri: "file:///work/app".parse().unwrap(),
+                name: "app".into(),
+            })
+        );
+    }
+}
@@ -6,6 +6,8 @@ use crate::{
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceSymbolClientCapabilities {
     /// This capability supports dynamic registration.
@@ -38,6 +40,8 @@ pub struct WorkspaceSymbolClientCapabilities {
 
 /// The parameters of a Workspace Symbol Request.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct WorkspaceSymbolParams {
     #[serde(flatten)]
     pub partial_result_params: PartialResultParams,
@@ -50,6 +54,8 @@ pub struct WorkspaceSymbolParams {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct WorkspaceSymbolResolveSupportCapability {
     /// The properties that a client can resolve lazily. Usually
     /// `location.range`
@@ -60,6 +66,8 @@ pub struct WorkspaceSymbolResolveSupportCapability {
 ///
 /// @since 3.17.0
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceSymbol {
     /// The name of this symbol.
@@ -89,17 +97,74 @@ pub struct WorkspaceSymbol {
     /// A data entry field that is preserved on a workspace symbol between a
     /// workspace symbol request and a workspace symbol resolve request.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::arbitrary_helpers::option_value))]
     pub data: Option<LSPAny>,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct WorkspaceLocation {
     pub uri: Uri,
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum WorkspaceSymbolResponse {
     Flat(Vec<SymbolInformation>),
     Nested(Vec<WorkspaceSymbol>),
 }
+
+impl<'de> Deserialize<'de> for WorkspaceSymbolResponse {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        // Symbols with `data` are read as `Nested`, as `SymbolInformation` would drop it.
+        let symbols = serde_json::Value::Array(Vec::deserialize(deserializer)?);
+        let has_data = symbols
+            .as_array()
+            .into_iter()
+            .flatten()
+            .any(|symbol| symbol.get("data").is_some_and(|data| !data.is_null()));
+        if !has_data {
+            if let Ok(symbols) = Vec::<SymbolInformation>::deserialize(&symbols) {
+                return Ok(WorkspaceSymbolResponse::Flat(symbols));
+            }
+        }
+        Vec::<WorkspaceSymbol>::deserialize(&symbols)
+            .map(WorkspaceSymbolResponse::Nested)
+            .map_err(|_| {
+                serde::de::Error::custom(
+                    "data did not match any variant of untagged enum WorkspaceSymbolResponse",
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_deserialization;
+    use crate::{Position, Range};
+
+    #[test]
+    fn test_workspace_symbol_response_deserialization() {
+        test_deserialization(
+            r#"[{"name":"main","kind":12,"location":{"uri":"file:///a.rs","range":{"start":{"line":0,"character":0},"end":{"line":0,"character":4}}},"data":1}]"#,
+            &WorkspaceSymbolResponse::Nested(vec![WorkspaceSymbol {
+                name: "main".to_string(),
+                kind: SymbolKind::FUNCTION,
+                tags: None,
+                container_name: None,
+                location: OneOf::Left(Location::new(
+                    "file:///a.rs".parse().unwrap(),
+                    Range::new(Position::new(0, 0), Position::new(0, 4)),
+                )),
+                data: Some(1.into()),
+            }]),
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! Checks that the params and results of every request read back as the value they were written
+//! from. Untagged enums whose variants accept each other's JSON fail this check.
+#![cfg(feature = "arbitrary")]
+
+use std::fmt::Debug;
+
+use arbitrary::{Arbitrary, Unstructured};
+use lsp_types::request::*;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+const ITERATIONS: usize = 256;
+
+/// A xorshift generator, so that failures are reproducible.
+struct Rng(u64);
+
+impl Rng {
+    fn bytes(&mut self) -> Vec<u8> {
+        let mut next = || {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        };
+        let len = (next() % 4096) as usize;
+        (0..len).map(|_| next() as u8).collect()
+    }
+}
+
+/// The untagged enums whose variants can write the same JSON, as the request part and a JSON
+/// pointer to the enum, in which `*` matches any array element. Values read back as another
+/// variant, but as one that writes the same JSON, are accepted if the JSON written has a value at
+/// one of the pointers of the request part.
+const AMBIGUOUS: &[(&str, &str)] = &[
+    // Registration options without an id or document selector, and notebook selectors and
+    // filters whose optional fields are all absent.
+    ("Initialize::Result", "/capabilities/declarationProvider"),
+    ("Initialize::Result", "/capabilities/notebookDocumentSync"),
+    // Empty arrays.
+    ("GotoDeclaration::Result", ""),
+    ("GotoDefinition::Result", ""),
+    ("GotoTypeDefinition::Result", ""),
+    ("GotoImplementation::Result", ""),
+    ("DocumentSymbolRequest::Result", ""),
+    ("WorkspaceSymbolRequest::Result", ""),
+    // Partial results are the full results without a result id.
+    ("SemanticTokensFullRequest::Result", ""),
+    ("SemanticTokensRangeRequest::Result", ""),
+    ("WorkspaceDiagnosticRequest::Result", ""),
+    ("SemanticTokensFullDeltaRequest::Result", ""),
+    // Operations that are all text document edits.
+    ("ApplyWorkspaceEdit::Params", "/edit/documentChanges"),
+    ("CodeActionRequest::Result", "/*/edit/documentChanges"),
+    ("CodeActionResolveRequest::Params", "/edit/documentChanges"),
+    ("CodeActionResolveRequest::Result", "/edit/documentChanges"),
+    ("Rename::Result", "/documentChanges"),
+    ("WillCreateFiles::Result", "/documentChanges"),
+    ("WillRenameFiles::Result", "/documentChanges"),
+    ("WillDeleteFiles::Result", "/documentChanges"),
+];
+
+/// Whether `value` has a value other than `null` at `pointer`, in which `*` matches any array
+/// element.
+fn has_value_at(value: &Value, pointer: &str) -> bool {
+    let pointer = match pointer.strip_prefix('/') {
+        Some(pointer) => pointer,
+        None => return !value.is_null(),
+    };
+    let (token, rest) = pointer.split_at(pointer.find('/').unwrap_or(pointer.len()));
+    match (value, token) {
+        (Value::Array(items), "*") => items.iter().any(|item| has_value_at(item, rest)),
+        _ => value
+            .pointer(&format!("/{}", token))
+            .is_some_and(|value| has_value_at(value, rest)),
+    }
+}
+
+fn check<T>(
+    name: &str,
+    generate: impl Fn(&mut Unstructured<'_>) -> arbitrary::Result<T>,
+    failures: &mut Vec<String>,
+) where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for _ in 0..ITERATIONS {
+        let bytes = rng.bytes();
+        let value = match generate(&mut Unstructured::new(&bytes)) {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+        let json = serde_json::to_value(&value).unwrap();
+        let failure = match T::deserialize(&json) {
+            Ok(read) if read == value => continue,
+            Ok(read)
+                if without_nulls(serde_json::to_value(&read).unwrap())
+                    != without_nulls(json.clone()) =>
+            {
+                format!(
+                    "{:?}\nread back lossily as {:?}\nwriting {}",
+                    value,
+                    read,
+                    serde_json::to_value(&read).unwrap()
+                )
+            }
+            Ok(read) => {
+                let ambiguous = AMBIGUOUS
+                    .iter()
+                    .any(|&(request, pointer)| request == name && has_value_at(&json, pointer));
+                if ambiguous {
+                    continue;
+                }
+                format!("{:?}\nread back as another variant {:?}", value, read)
+            }
+            Err(error) => format!("{:?}\nfailed with {}", value, error),
+        };
+        failures.push(format!("{}: {}\nfrom {}", name, failure, json));
+        return;
+    }
+}
+
+/// Removes the `null` fields of objects, which are equivalent to absent fields.
+fn without_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, without_nulls(value)))
+                .collect(),
+        ),
+        Value::Array(array) => Value::Array(array.into_iter().map(without_nulls).collect()),
+        value => value,
+    }
+}
+
+fn json_string(u: &mut Unstructured<'_>) -> arbitrary::Result<Value> {
+    String::arbitrary(u).map(Value::String)
+}
+
+macro_rules! check_requests {
+    ($failures:ident: $($(#[$attr:meta])* $request:ident),* $(,)?) => {
+        $(
+            $(#[$attr])*
+            {
+                check::<<$request as Request>::Params>(
+                    concat!(stringify!($request), "::Params"),
+                    |u| u.arbitrary(),
+                    &mut $failures,
+                );
+                check::<<$request as Request>::Result>(
+                    concat!(stringify!($request), "::Result"),
+                    |u| u.arbitrary(),
+                    &mut $failures,
+                );
+            }
+        )*
+    };
+}
+
+#[test]
+fn round_trip_requests() {
+    let mut failures = Vec::new();
+    check_requests!(failures:
+        Initialize,
+        Shutdown,
+        ShowMessageRequest,
+        RegisterCapability,
+        UnregisterCapability,
+        Completion,
+        ResolveCompletionItem,
+        HoverRequest,
+        SignatureHelpRequest,
+        GotoDeclaration,
+        GotoDefinition,
+        References,
+        GotoTypeDefinition,
+        GotoImplementation,
+        DocumentHighlightRequest,
+        DocumentSymbolRequest,
+        WorkspaceSymbolRequest,
+        WorkspaceSymbolResolve,
+        WillSaveWaitUntil,
+        ApplyWorkspaceEdit,
+        CodeActionRequest,
+        CodeActionResolveRequest,
+        CodeLensRequest,
+        CodeLensResolve,
+        DocumentLinkRequest,
+        DocumentLinkResolve,
+        Formatting,
+        RangeFormatting,
+        OnTypeFormatting,
+        LinkedEditingRange,
+        Rename,
+        DocumentColor,
+        ColorPresentationRequest,
+        FoldingRangeRequest,
+        PrepareRenameRequest,
+        #[cfg(feature = "proposed")]
+        InlineCompletionRequest,
+        WorkspaceFoldersRequest,
+        WorkDoneProgressCreate,
+        SelectionRangeRequest,
+        CallHierarchyPrepare,
+        CallHierarchyIncomingCalls,
+        CallHierarchyOutgoingCalls,
+        SemanticTokensFullRequest,
+        SemanticTokensFullDeltaRequest,
+        SemanticTokensRangeRequest,
+        SemanticTokensRefresh,
+        CodeLensRefresh,
+        WillCreateFiles,
+        WillRenameFiles,
+        WillDeleteFiles,
+        ShowDocument,
+        MonikerRequest,
+        InlayHintRequest,
+        InlayHintResolveRequest,
+        InlayHintRefreshRequest,
+        InlineValueRequest,
+        InlineValueRefreshRequest,
+        DocumentDiagnosticRequest,
+        WorkspaceDiagnosticRequest,
+        WorkspaceDiagnosticRefresh,
+        TypeHierarchyPrepare,
+        TypeHierarchySupertypes,
+        TypeHierarchySubtypes,
+    );
+
+    // The results of these requests are arbitrary JSON, which does not implement `Arbitrary`.
+    check::<<ExecuteCommand as Request>::Params>(
+        "ExecuteCommand::Params",
+        |u| u.arbitrary(),
+        &mut failures,
+    );
+    check::<<ExecuteCommand as Request>::Result>(
+        "ExecuteCommand::Result",
+        |u| {
+            Option::<()>::arbitrary(u)?
+                .map(|()| json_string(u))
+                .transpose()
+        },
+        &mut failures,
+    );
+    check::<<WorkspaceConfiguration as Request>::Params>(
+        "WorkspaceConfiguration::Params",
+        |u| u.arbitrary(),
+        &mut failures,
+    );
+    check::<<WorkspaceConfiguration as Request>::Result>(
+        "WorkspaceConfiguration::Result",
+        |u| {
+            (0..u.arbitrary_len::<u8>()?)
+                .map(|_| json_string(u))
+                .collect()
+        },
+        &mut failures,
+    );
+
+    assert!(failures.is_empty(), "\n\n{}\n", failures.join("\n\n"));
+}